@@ -10,7 +10,8 @@ use fs_common::game::common::world::{
         PlayerClipboardState, PlayerGrappleState, PlayerJumpState, PlayerLaunchState,
         PlayerMovementMode,
     },
-    material::{buf::MaterialBuf, MaterialInstance, PhysicsType},
+    material::{buf::MaterialBuf, PhysicsType},
+    query::{QueryFilter, QueryTarget},
     Camera, Position, Velocity, World,
 };
 
@@ -265,67 +266,58 @@ fn tick_player(
                                     - position_storage.get(eid).unwrap().y;
                                 let mag = (dx * dx + dy * dy).sqrt();
 
-                                let raycast_filter = |_pos: (i64, i64), mat: &MaterialInstance| {
-                                    mat.physics == PhysicsType::Solid
+                                let solid = QueryFilter::empty().physics(&[PhysicsType::Solid]);
+                                let raycast = |from: &Position, to: &Position| {
+                                    world
+                                        .cast_ray((from.x, from.y), (to.x, to.y), &solid)
+                                        .and_then(|hit| match hit.target {
+                                            QueryTarget::Pixel { x, y, .. } => Some((x, y)),
+                                            _ => None,
+                                        })
                                 };
 
-                                if let Some(r) = world.raycast(
-                                    position_storage.get(eid).unwrap().x as i64,
-                                    position_storage.get(eid).unwrap().y as i64,
+                                if let Some(r) = raycast(
+                                    position_storage.get(eid).unwrap(),
                                     pivots
                                         .last()
-                                        .unwrap_or_else(|| position_storage.get(*entity).unwrap())
-                                        .x as i64,
-                                    pivots
-                                        .last()
-                                        .unwrap_or_else(|| position_storage.get(*entity).unwrap())
-                                        .y as i64,
-                                    raycast_filter,
+                                        .unwrap_or_else(|| position_storage.get(*entity).unwrap()),
                                 ) {
                                     let side_1 = world.chunk_handler.pixel(
-                                        r.0 .0 + ((dy / mag) * 2.0) as i64,
-                                        r.0 .1 + ((-dx / mag) * 2.0) as i64,
+                                        r.0 + ((dy / mag) * 2.0) as i64,
+                                        r.1 + ((-dx / mag) * 2.0) as i64,
                                     );
                                     // let side_2 = world.chunk_handler.get(r.0.0 + ((-dy / mag) * 1.0) as i64, r.0.1 + ((dx / mag) * 1.0) as i64);
 
                                     if side_1.is_ok() && side_1.unwrap().physics != PhysicsType::Air
                                     {
                                         pivots.push(Position {
-                                            x: r.0 .0 as f64 + (-dy / mag) * 2.0,
-                                            y: r.0 .1 as f64 + (dx / mag) * 2.0,
+                                            x: r.0 as f64 + (-dy / mag) * 2.0,
+                                            y: r.1 as f64 + (dx / mag) * 2.0,
                                         });
                                     } else {
                                         pivots.push(Position {
-                                            x: r.0 .0 as f64 + (dy / mag) * 2.0,
-                                            y: r.0 .1 as f64 + (-dx / mag) * 2.0,
+                                            x: r.0 as f64 + (dy / mag) * 2.0,
+                                            y: r.1 as f64 + (-dx / mag) * 2.0,
                                         });
                                     }
                                 }
 
                                 #[allow(clippy::collapsible_if)]
                                 if pivots.len() > 1 {
-                                    if world
-                                        .raycast(
-                                            position_storage.get(eid).unwrap().x as i64,
-                                            position_storage.get(eid).unwrap().y as i64,
-                                            pivots[pivots.len() - 2].x as i64,
-                                            pivots[pivots.len() - 2].y as i64,
-                                            raycast_filter,
-                                        )
-                                        .is_none()
+                                    if raycast(
+                                        position_storage.get(eid).unwrap(),
+                                        &pivots[pivots.len() - 2],
+                                    )
+                                    .is_none()
                                     {
                                         pivots.pop();
                                     }
                                 } else if !pivots.is_empty() {
-                                    if world
-                                        .raycast(
-                                            position_storage.get(eid).unwrap().x as i64,
-                                            position_storage.get(eid).unwrap().y as i64,
-                                            position_storage.get(*entity).unwrap().x as i64,
-                                            position_storage.get(*entity).unwrap().y as i64,
-                                            raycast_filter,
-                                        )
-                                        .is_none()
+                                    if raycast(
+                                        position_storage.get(eid).unwrap(),
+                                        position_storage.get(*entity).unwrap(),
+                                    )
+                                    .is_none()
                                     {
                                         pivots.pop();
                                     }
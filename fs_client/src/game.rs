@@ -39,7 +39,14 @@ use crate::{
     world::{ClientChunkHandlerExt, ClientWorld, ClientWorldExt},
 };
 
-use super::{render::Renderer, world::ClientChunk, Client};
+use super::{
+    render::{
+        shaders::{Shaders, SHADERS_DIR},
+        Renderer,
+    },
+    world::ClientChunk,
+    Client,
+};
 
 pub struct ClientGame {
     pub data: GameData<ClientChunk>,
@@ -64,6 +71,9 @@ impl ClientGame {
         if args.no_tick {
             self.data.settings.simulate_chunks = false;
             self.data.settings.simulate_particles = false;
+            self.data.settings.structural_integrity = false;
+            self.data.settings.fracture_rigidbodies = false;
+            self.data.settings.settle_rigidbodies = false;
             self.data.settings.tick_physics = false;
         }
 
@@ -153,6 +163,12 @@ impl ClientGame {
                                     KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F11), state: ElementState::Pressed, .. } => {
                                         self.data.settings.fullscreen = !self.data.settings.fullscreen;
                                     }
+                                    KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F5), state: ElementState::Pressed, .. } => {
+                                        info!("Reloading assets...");
+                                        self.data.reload_registries();
+                                        self.data.reload_lang();
+                                        reload_shaders(&mut renderer, &self.data.file_helper);
+                                    }
                                     KeyboardInput { virtual_keycode: Some(VirtualKeyCode::LShift | VirtualKeyCode::RShift), state: ElementState::Pressed, .. } => {
                                         shift_key = true
                                     }
@@ -215,7 +231,7 @@ impl ClientGame {
                                                         let x = world_x as i64 + xx;
                                                         let y = world_y as i64 + yy;
                                                         let _ = w.chunk_handler.set_pixel(x, y,
-                                                            self.data.registries.material_placers.get(&debug_ui.draw.selected).unwrap().pixel(x, y),
+                                                            self.data.registries.material_placers.get(&debug_ui.draw.selected).unwrap().pixel(x, y, w.seed),
                                                         );
                                                     }
                                                 }
@@ -445,22 +461,7 @@ impl ClientGame {
                                     *control_flow = glutin::event_loop::ControlFlow::Exit;
                                 },
                                 MainMenuAction::LoadWorld(path) => {
-                                    let world_meta = World::<ClientChunk>::parse_file_meta(path.clone())
-                                        .expect("Failed to parse file meta");
-                                    if let Some(w) = &mut self.data.world {
-                                        info!("Unload current world...");
-                                        w.save().expect("World save failed");
-                                        w.close().expect("World unload failed");
-                                    }
-
-                                    info!("Load world \"{}\"...", world_meta.name);
-                                    self.data.world = Some(World::create(Some(
-                                        path.parent()
-                                            .expect("World meta file has no parent directory ??")
-                                            .to_path_buf(),
-                                        ),
-                                        Some(3), // TODO: save/load seed from file
-                                    ));
+                                    self.data.load_world(&path).expect("Failed to load world");
 
                                     if let Some(w) = &mut self.data.world {
                                         let player = Player::create_and_add(w);
@@ -476,8 +477,12 @@ impl ClientGame {
                                         w.close().expect("World unload failed");
                                     }
 
+                                    if let Err(e) = self.data.set_data_packs(&[]) {
+                                        error!("Failed to reset data packs: {}", e);
+                                    }
+
                                     info!("Loading new world...");
-                                    self.data.world = Some(World::create(None, None));
+                                    self.data.world = Some(World::create(None, None, &self.data.registries));
                                     info!("Seed is {}", self.data.world.as_ref().unwrap().seed);
 
                                     if let Some(w) = &mut self.data.world {
@@ -488,7 +493,11 @@ impl ClientGame {
                                         self.client.world =
                                             Some(ClientWorld { local_entity: Some(player) });
                                     };
-                                }
+                                },
+                                MainMenuAction::SetLanguage(language) => {
+                                    self.data.settings.language = language;
+                                    self.data.reload_lang();
+                                },
                             }
                         }
 
@@ -774,6 +783,11 @@ impl ClientGame {
     fn tick(&mut self, renderer: &mut Renderer) {
         self.data.tick_time += 1;
 
+        let changed = self.data.check_asset_changes();
+        if changed.iter().any(|p| p.starts_with(SHADERS_DIR)) {
+            reload_shaders(renderer, &self.data.file_helper);
+        }
+
         if let Some(w) = &mut self.data.world {
             self.client.tick(w, renderer);
             w.tick(
@@ -786,3 +800,13 @@ impl ClientGame {
         }
     }
 }
+
+fn reload_shaders(renderer: &mut Renderer, file_helper: &FileHelper) {
+    match Shaders::try_new(&renderer.display, file_helper) {
+        Ok(shaders) => {
+            renderer.shaders = shaders;
+            info!("Reloaded shaders");
+        },
+        Err(e) => error!("Failed to reload shaders, keeping the old ones: {e}"),
+    }
+}
@@ -135,7 +135,14 @@ impl DebugUI for Settings {
             ui.checkbox(&mut self.load_chunks, "load_chunks");
             ui.checkbox(&mut self.simulate_chunks, "simulate_chunks");
             ui.checkbox(&mut self.simulate_particles, "simulate_particles");
+            ui.checkbox(&mut self.structural_integrity, "structural_integrity");
+            ui.checkbox(&mut self.fracture_rigidbodies, "fracture_rigidbodies");
+            ui.checkbox(&mut self.settle_rigidbodies, "settle_rigidbodies");
             ui.checkbox(&mut self.pause_on_lost_focus, "pause_on_lost_focus");
         });
+
+        ui.collapsing("assets", |ui| {
+            ui.checkbox(&mut self.hot_reload_assets, "hot_reload_assets");
+        });
     }
 }
@@ -1,16 +1,35 @@
+use std::collections::HashMap;
+
 use fs_common::game::common::world::rigidbody::FSRigidBody;
 use glium::texture::Texture2d;
+use rapier2d::prelude::RigidBodyHandle;
 
 use super::drawing::RenderTarget;
 
-pub trait FSRigidBodyExt {
-    fn update_image(&mut self, target: &mut RenderTarget);
+/// Textures of [`FSRigidBody`]s, keyed by their rapier body
+#[derive(Default)]
+pub struct RigidBodyTextures {
+    textures: HashMap<RigidBodyHandle, Texture2d>,
 }
 
-impl FSRigidBodyExt for FSRigidBody {
-    fn update_image(&mut self, target: &mut RenderTarget) {
-        if self.image_dirty {
-            let pixel_data: Vec<_> = self
+impl RigidBodyTextures {
+    /// Uploads the pixels of `rb` if they changed, and returns its texture
+    pub fn update(
+        &mut self,
+        rb: &mut FSRigidBody,
+        target: &mut RenderTarget,
+    ) -> Option<&Texture2d> {
+        let handle = rb.body?;
+
+        let size_changed = self.textures.get(&handle).is_some_and(|tex| {
+            tex.width() != u32::from(rb.width) || tex.height() != u32::from(rb.height)
+        });
+        if size_changed {
+            self.textures.remove(&handle);
+        }
+
+        if rb.image_dirty || !self.textures.contains_key(&handle) {
+            let pixel_data: Vec<_> = rb
                 .pixels
                 .iter()
                 .flat_map(|m| vec![m.color.r, m.color.g, m.color.b, m.color.a])
@@ -19,27 +38,36 @@ impl FSRigidBodyExt for FSRigidBody {
             let image = {
                 glium::texture::RawImage2d {
                     data: std::borrow::Cow::Borrowed(pixel_data.as_slice()),
-                    width: self.width.into(),
-                    height: self.height.into(),
+                    width: rb.width.into(),
+                    height: rb.height.into(),
                     format: glium::texture::ClientFormat::U8U8U8U8,
                 }
             };
 
-            if self.image.is_none() {
-                self.image = Some(Texture2d::new(&target.display, image).unwrap());
-            } else {
-                self.image.as_mut().unwrap().write(
+            if let Some(tex) = self.textures.get_mut(&handle) {
+                tex.write(
                     glium::Rect {
                         left: 0,
                         bottom: 0,
-                        width: self.width.into(),
-                        height: self.height.into(),
+                        width: rb.width.into(),
+                        height: rb.height.into(),
                     },
                     image,
                 );
+            } else {
+                self.textures
+                    .insert(handle, Texture2d::new(&target.display, image).unwrap());
             }
 
-            self.image_dirty = false;
+            rb.image_dirty = false;
         }
+
+        self.textures.get(&handle)
+    }
+
+    /// Drops the textures of bodies that aren't in `rigidbodies` anymore
+    pub fn retain(&mut self, rigidbodies: &[FSRigidBody]) {
+        self.textures
+            .retain(|handle, _| rigidbodies.iter().any(|rb| rb.body == Some(*handle)));
     }
 }
@@ -1,5 +1,3 @@
-use std::fs;
-
 use fs_common::game::common::FileHelper;
 use glium::{
     program::{ComputeShader, ProgramChooserCreationError},
@@ -18,60 +16,58 @@ pub struct Shaders {
     pub lighting_compute_prep: ComputeShader,
 }
 
+pub const SHADERS_DIR: &str = "data/shaders";
+
 impl Shaders {
     pub fn new(display: &Display, file_helper: &FileHelper) -> Self {
-        profiling::scope!("Shaders::new");
+        Self::try_new(display, file_helper).expect("Failed to load shaders")
+    }
+
+    pub fn try_new(display: &Display, file_helper: &FileHelper) -> Result<Self, String> {
+        profiling::scope!("Shaders::try_new");
         let helper = ShaderFileHelper { file_helper, display };
 
-        Self {
-            common: helper
-                .load_from_files(140, "data/shaders/common.vert", "data/shaders/common.frag")
-                .unwrap(),
-            vertex_colors: helper
-                .load_from_files(
-                    140,
-                    "data/shaders/vert_colors.vert",
-                    "data/shaders/vert_colors.frag",
-                )
-                .unwrap(),
-            texture: helper
-                .load_from_files(
-                    140,
-                    "data/shaders/textured.vert",
-                    "data/shaders/textured.frag",
-                )
-                .unwrap(),
-            texture_array: helper
-                .load_from_files(
-                    140,
-                    "data/shaders/texture_array.vert",
-                    "data/shaders/texture_array.frag",
-                )
-                .unwrap(),
-            particle: helper
-                .load_from_files(
-                    140,
-                    "data/shaders/particles.vert",
-                    "data/shaders/particles.frag",
-                )
-                .unwrap(),
-            chunk: helper
-                .load_from_files(140, "data/shaders/chunk.vert", "data/shaders/chunk.frag")
-                .unwrap(),
-            chunk_light: helper
-                .load_from_files(
-                    140,
-                    "data/shaders/chunk.vert",
-                    "data/shaders/chunk_light.frag",
-                )
-                .unwrap(),
+        Ok(Self {
+            common: helper.load_from_files(
+                140,
+                "data/shaders/common.vert",
+                "data/shaders/common.frag",
+            )?,
+            vertex_colors: helper.load_from_files(
+                140,
+                "data/shaders/vert_colors.vert",
+                "data/shaders/vert_colors.frag",
+            )?,
+            texture: helper.load_from_files(
+                140,
+                "data/shaders/textured.vert",
+                "data/shaders/textured.frag",
+            )?,
+            texture_array: helper.load_from_files(
+                140,
+                "data/shaders/texture_array.vert",
+                "data/shaders/texture_array.frag",
+            )?,
+            particle: helper.load_from_files(
+                140,
+                "data/shaders/particles.vert",
+                "data/shaders/particles.frag",
+            )?,
+            chunk: helper.load_from_files(
+                140,
+                "data/shaders/chunk.vert",
+                "data/shaders/chunk.frag",
+            )?,
+            chunk_light: helper.load_from_files(
+                140,
+                "data/shaders/chunk.vert",
+                "data/shaders/chunk_light.frag",
+            )?,
             lighting_compute_propagate: helper
-                .load_compute_from_files("data/shaders/lighting_propagate.comp")
-                .unwrap(),
+                .load_compute_from_files("data/shaders/lighting_propagate.comp")?,
             lighting_compute_prep: helper
-                .load_compute_from_files("data/shaders/lighting_prep.comp")
-                .unwrap(),
-        }
+                .load_compute_from_files("data/shaders/lighting_prep.comp")?,
+        })
     }
 }
 
@@ -86,28 +82,34 @@ impl ShaderFileHelper<'_> {
         version: u32,
         vert: &str,
         frag: &str,
-    ) -> Result<glium::Program, ProgramChooserCreationError> {
+    ) -> Result<glium::Program, String> {
         use glium::program;
 
-        let vert = fs::read_to_string(self.file_helper.asset_path(vert)).unwrap();
-        let frag = fs::read_to_string(self.file_helper.asset_path(frag)).unwrap();
+        let vert_src = self.read(vert)?;
+        let frag_src = self.read(frag)?;
 
         program!(self.display,
             version => {
                 outputs_srgb: true,
-                vertex: vert.as_str(),
-                fragment: frag.as_str(),
+                vertex: vert_src.as_str(),
+                fragment: frag_src.as_str(),
             }
         )
+        .map_err(|e: ProgramChooserCreationError| format!("Failed to compile {vert} + {frag}: {e}"))
     }
 
     #[profiling::function]
     pub fn load_compute_from_files(
         &self,
         src: &str,
-    ) -> Result<glium::program::ComputeShader, ProgramCreationError> {
-        let src = fs::read_to_string(self.file_helper.asset_path(src)).unwrap();
+    ) -> Result<glium::program::ComputeShader, String> {
+        let source = self.read(src)?;
+
+        ComputeShader::from_source(self.display, &source)
+            .map_err(|e: ProgramCreationError| format!("Failed to compile {src}: {e}"))
+    }
 
-        ComputeShader::from_source(self.display, &src)
+    fn read(&self, path: &str) -> Result<String, String> {
+        self.file_helper.read_asset_to_string(path)
     }
 }
@@ -48,13 +48,14 @@ impl DrawUI {
                                         .selected(*id == self.selected),
                                 )
                                 .on_hover_text(
-                                    ctx.registries
-                                        .material_placers
-                                        .get(id)
-                                        .unwrap()
-                                        .meta
-                                        .display_name
-                                        .to_string(),
+                                    ctx.lang.translate(
+                                        &ctx.registries
+                                            .material_placers
+                                            .get(id)
+                                            .unwrap()
+                                            .meta
+                                            .display_name,
+                                    ),
                                 )
                                 .clicked()
                             {
@@ -80,7 +81,7 @@ fn gen_material_preview(placer: &dyn MaterialPlacerSampler) -> egui::ColorImage
     );
     for y in 0..height {
         for x in 0..width {
-            let mat = placer.pixel(x as i64, y as i64);
+            let mat = placer.pixel(x as i64, y as i64, 0);
             let col = egui::Rgba::from_srgba_unmultiplied(
                 mat.color.r,
                 mat.color.g,
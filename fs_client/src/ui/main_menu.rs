@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use fs_common::game::{
     self,
     common::{
+        lang::Lang,
         world::{WorldMeta, WorldTreeNode},
         FileHelper,
     },
@@ -26,6 +27,7 @@ pub enum MainMenuAction {
     Quit,
     LoadWorld(PathBuf),
     LoadRandomSeed,
+    SetLanguage(String),
 }
 
 impl MainMenu {
@@ -80,14 +82,19 @@ impl MainMenu {
         None
     }
 
-    pub fn render(&mut self, egui_ctx: &egui::Context, file_helper: &FileHelper) {
-        egui::Window::new("Main Menu")
+    pub fn render(&mut self, egui_ctx: &egui::Context, file_helper: &FileHelper, lang: &Lang) {
+        // the title changes with the language, so the id needs to be fixed
+        egui::Window::new(lang.translate("menu.main.title"))
+            .id(egui::Id::new("main_menu"))
             .resizable(false)
             .show(egui_ctx, |ui| {
                 let mut new_state = None;
                 match &self.state {
                     MainMenuState::Main => {
-                        if ui.button("Singleplayer").clicked() {
+                        if ui
+                            .button(lang.translate("menu.main.singleplayer"))
+                            .clicked()
+                        {
                             let worlds = game::common::world::World::<ClientChunk>::find_files(
                                 file_helper.game_path("saves/"),
                             )
@@ -102,15 +109,32 @@ impl MainMenu {
 
                             new_state = Some(MainMenuState::WorldSelect { context: metas });
                         }
-                        if ui.button("Random Seed").clicked() {
+                        if ui.button(lang.translate("menu.main.random_seed")).clicked() {
                             self.action_queue.push(MainMenuAction::LoadRandomSeed);
                         }
-                        if ui.button("Quit").clicked() {
+                        if ui.button(lang.translate("menu.main.quit")).clicked() {
                             self.action_queue.push(MainMenuAction::Quit);
                         }
+
+                        let current = lang
+                            .available()
+                            .iter()
+                            .find(|(code, _)| code == lang.language())
+                            .map_or(lang.language(), |(_, name)| name);
+                        egui::ComboBox::from_label(lang.translate("menu.main.language"))
+                            .selected_text(current)
+                            .show_ui(ui, |ui| {
+                                for (code, name) in lang.available() {
+                                    if ui.selectable_label(code == lang.language(), name).clicked()
+                                    {
+                                        self.action_queue
+                                            .push(MainMenuAction::SetLanguage(code.clone()));
+                                    }
+                                }
+                            });
                     },
                     MainMenuState::WorldSelect { context } => {
-                        if ui.button("Back").clicked() {
+                        if ui.button(lang.translate("menu.main.back")).clicked() {
                             new_state = Some(MainMenuState::Main);
                         }
                         if let Some(choice) = Self::draw_worlds(context, ui) {
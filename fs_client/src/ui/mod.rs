@@ -3,7 +3,7 @@ pub mod draw;
 mod main_menu;
 pub mod registries;
 
-use fs_common::game::common::{world::entity::Player, Registries};
+use fs_common::game::common::{lang::Lang, world::entity::Player, Registries};
 pub use main_menu::*;
 
 use self::{clipboard::ClipboardUI, draw::DrawUI, registries::RegistriesUI};
@@ -16,6 +16,7 @@ pub struct DebugUIs {
 
 pub struct DebugUIsContext<'a> {
     pub registries: &'a Registries,
+    pub lang: &'a Lang,
     pub local_player: &'a mut Player,
 }
 
@@ -43,35 +43,48 @@ impl RegistriesUI {
         CollapsingState::load_with_default_open(egui_ctx, id.with("collapsing"), false)
             .store(egui_ctx);
 
-        egui::Window::new("Registries")
+        let lang = ctx.lang;
+        egui::Window::new(lang.translate("debug.registries.title"))
             .id(id)
             .resizable(false)
             .show(egui_ctx, |ui| {
                 ui.horizontal_wrapped(|ui| {
-                    ui.selectable_value(&mut self.cur_tab, Tab::Material, "Material");
-                    ui.selectable_value(&mut self.cur_tab, Tab::MaterialPlacer, "MaterialPlacer");
-                    ui.selectable_value(&mut self.cur_tab, Tab::StructurePiece, "StructurePiece");
-                    ui.selectable_value(&mut self.cur_tab, Tab::StructurePool, "StructurePool");
-                    ui.selectable_value(
-                        &mut self.cur_tab,
-                        Tab::ConfiguredStructure,
-                        "ConfiguredStructure",
-                    );
-                    ui.selectable_value(&mut self.cur_tab, Tab::StructureSet, "StructureSet");
+                    for (tab, key) in [
+                        (Tab::Material, "debug.registries.tab.material"),
+                        (Tab::MaterialPlacer, "debug.registries.tab.material_placer"),
+                        (Tab::StructurePiece, "debug.registries.tab.structure_piece"),
+                        (Tab::StructurePool, "debug.registries.tab.structure_pool"),
+                        (
+                            Tab::ConfiguredStructure,
+                            "debug.registries.tab.configured_structure",
+                        ),
+                        (Tab::StructureSet, "debug.registries.tab.structure_set"),
+                    ] {
+                        ui.selectable_value(&mut self.cur_tab, tab, lang.translate(key));
+                    }
                 });
 
                 match self.cur_tab {
                     Tab::Material => {
                         for (id, mat) in &ctx.registries.materials {
                             ui.collapsing(format!("{id}"), |ui| {
-                                ui.label(format!("display_name = {}", mat.display_name));
+                                ui.label(format!(
+                                    "display_name = {} ({})",
+                                    mat.display_name,
+                                    lang.translate(&mat.display_name)
+                                ));
+                                ui.label(format!("tags = {:?}", mat.tags));
                             });
                         }
                     },
                     Tab::MaterialPlacer => {
                         for (id, placer) in &ctx.registries.material_placers {
                             ui.collapsing(format!("{id}"), |ui| {
-                                ui.label(format!("display_name = {}", placer.meta.display_name));
+                                ui.label(format!(
+                                    "display_name = {} ({})",
+                                    placer.meta.display_name,
+                                    lang.translate(&placer.meta.display_name)
+                                ));
                             });
                         }
                     },
@@ -152,15 +165,24 @@ impl RegistriesUI {
                                     });
 
                                 for (i, (p, c)) in piece.child_nodes.iter().enumerate() {
-                                    ui.collapsing(format!("connection #{i}"), |ui| {
-                                        ui.label(format!("pos = ({}, {})", p.x, p.y));
-                                        ui.label(format!("pool = {:?}", c.pool));
-                                        ui.label(format!(
-                                            "depth_override = {:?}",
-                                            c.depth_override
-                                        ));
-                                        ui.label(format!("block_in_dirs = {:?}", c.block_in_dirs));
-                                    });
+                                    ui.collapsing(
+                                        format!(
+                                            "{} #{i}",
+                                            lang.translate("debug.registries.connection")
+                                        ),
+                                        |ui| {
+                                            ui.label(format!("pos = ({}, {})", p.x, p.y));
+                                            ui.label(format!("pool = {:?}", c.pool));
+                                            ui.label(format!(
+                                                "depth_override = {:?}",
+                                                c.depth_override
+                                            ));
+                                            ui.label(format!(
+                                                "block_in_dirs = {:?}",
+                                                c.block_in_dirs
+                                            ));
+                                        },
+                                    );
                                 }
                             });
                         }
@@ -17,7 +17,7 @@ use fs_common::game::common::{
         material::{color::Color, MaterialInstance},
         mesh::{self, Mesh},
         tile_entity::{TileEntity, TileEntityCommon},
-        ChunkRigidBodyState, ChunkState, SidedChunk, CHUNK_SIZE, LIGHT_SCALE,
+        ChunkRigidBodyState, ChunkState, SidedChunk, CHUNK_SIZE, COLLISION_TILES, LIGHT_SCALE,
     },
     FileHelper, Rect, Settings,
 };
@@ -33,8 +33,10 @@ use super::chunk_data::tile_entity::TileEntityClient;
 pub struct ClientChunk {
     pub data: CommonChunkData<Self>,
     pub graphics: Box<ChunkGraphics>,
-    pub mesh: Option<Mesh>,
-    pub tris: Option<Vec<Vec<mesh::Tri>>>,
+    /// Unsimplified mesh of each collision tile
+    pub mesh: Option<Vec<Mesh>>,
+    /// Triangulated simplified mesh of each collision tile
+    pub tris: Option<Vec<Vec<Vec<mesh::Tri>>>>,
 }
 
 impl SidedChunkData for ClientChunk {
@@ -89,6 +91,14 @@ impl Chunk for ClientChunk {
         self.data.dirty_rect = rect;
     }
 
+    fn integrity_rect(&self) -> Option<Rect<i32>> {
+        self.data.integrity_rect
+    }
+
+    fn set_integrity_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.integrity_rect = rect;
+    }
+
     fn refresh(&mut self) {
         for pos in ChunkLocalPosition::iter() {
             let i: ChunkLocalIndex = pos.into();
@@ -230,33 +240,36 @@ impl Chunk for ClientChunk {
         self.graphics.lighting_dirty = true;
     }
 
-    fn generate_mesh(&mut self) -> Result<(), String> {
-        if self.data.pixels.is_none() {
-            return Err("generate_mesh failed: self.data.pixels is None".to_owned());
-        }
-
-        let vs: Vec<f64> = mesh::pixels_to_valuemap(self.data.pixels.as_ref().unwrap().as_ref());
-
-        let generated =
-            mesh::generate_mesh_with_simplified(&vs, u32::from(CHUNK_SIZE), u32::from(CHUNK_SIZE));
-
-        if let Ok(r) = generated {
-            self.mesh = Some(r.0);
-            self.data.mesh_simplified = Some(r.1);
-        } else {
-            self.mesh = None;
-            self.data.mesh_simplified = None;
+    fn generate_mesh_tiles(&mut self, tiles: &[usize]) -> Result<(), String> {
+        let generated = self.data.generate_mesh_tiles(tiles)?;
+
+        let simplified = self.data.mesh_simplified.as_ref().unwrap();
+        let meshes = self
+            .mesh
+            .get_or_insert_with(|| vec![vec![]; COLLISION_TILES]);
+        let tris = self
+            .tris
+            .get_or_insert_with(|| vec![vec![]; COLLISION_TILES]);
+        for (tile, mesh) in generated {
+            tris[tile] = mesh::triangulate(&simplified[tile]);
+            meshes[tile] = mesh;
         }
 
-        self.tris = self.data.mesh_simplified.as_ref().map(mesh::triangulate);
-
         Ok(())
     }
 
-    fn mesh_loops(&self) -> &Option<Mesh> {
+    fn mesh_loops(&self) -> &Option<Vec<Mesh>> {
         &self.data.mesh_simplified
     }
 
+    fn mesh_rect(&self) -> Option<Rect<i32>> {
+        self.data.mesh_rect
+    }
+
+    fn set_mesh_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.mesh_rect = rect;
+    }
+
     fn rigidbody(&self) -> &Option<ChunkRigidBodyState> {
         &self.data.rigidbody
     }
@@ -679,6 +692,7 @@ impl ClientChunk {
 
                 let lines = f
                     .iter()
+                    .flatten()
                     .enumerate()
                     .flat_map(|(j, f)| {
                         let c = colors[j % colors.len()];
@@ -725,6 +739,7 @@ impl ClientChunk {
 
                 let lines = f
                     .iter()
+                    .flatten()
                     .enumerate()
                     .flat_map(|(j, f)| {
                         let c = colors[j % colors.len()];
@@ -755,7 +770,7 @@ impl ClientChunk {
             if let Some(t) = &self.tris {
                 let mut tris = vec![];
 
-                for part in t {
+                for part in t.iter().flatten() {
                     for tri in part {
                         let (x1, y1) = tri.0;
                         let (x2, y2) = tri.1;
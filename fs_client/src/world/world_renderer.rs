@@ -20,17 +20,19 @@ use fs_common::game::common::{
 };
 
 use crate::{
-    render::{drawing::RenderTarget, rigidbody::FSRigidBodyExt},
+    render::{drawing::RenderTarget, rigidbody::RigidBodyTextures},
     Client,
 };
 
 use super::{chunk_data::tile_entity::ClientTileEntityExt, ClientChunk, ClientWorld};
 
-pub struct WorldRenderer {}
+pub struct WorldRenderer {
+    rigidbody_textures: RigidBodyTextures,
+}
 
 impl WorldRenderer {
     pub fn new() -> Self {
-        Self {}
+        Self { rigidbody_textures: RigidBodyTextures::default() }
     }
 
     #[allow(clippy::unused_self)]
@@ -143,6 +145,9 @@ impl WorldRenderer {
         // draw rigidbodies
         self.draw_rigidbodies(world, target);
 
+        // draw ropes
+        self.draw_ropes(world, target, &ctx);
+
         // draw physics debug
         if ctx.settings.debug && ctx.settings.physics_dbg_draw {
             self.draw_physics_debug(world, target, &ctx);
@@ -737,11 +742,12 @@ impl WorldRenderer {
         profiling::scope!("draw_rigidbodies");
         target.transform.push();
         target.transform.scale(PHYSICS_SCALE, PHYSICS_SCALE);
+        self.rigidbody_textures.retain(&world.rigidbodies);
         for rb in &mut world.rigidbodies {
-            rb.update_image(target);
+            let img = self.rigidbody_textures.update(rb, target);
 
             if let Some(body) = rb.get_body(&world.physics) {
-                if let Some(img) = &rb.image {
+                if let Some(img) = img {
                     let (rx, ry) = (
                         body.position().translation.vector[0],
                         body.position().translation.vector[1],
@@ -772,6 +778,53 @@ impl WorldRenderer {
         target.transform.pop();
     }
 
+    fn draw_ropes(
+        &mut self,
+        world: &mut World<ClientChunk>,
+        target: &mut RenderTarget,
+        ctx: &RenderContext,
+    ) {
+        profiling::scope!("draw_ropes");
+        for rope in &world.ropes {
+            let points = rope.points(&world.physics);
+            if points.len() < 2 {
+                continue;
+            }
+
+            let params = DrawParameters {
+                polygon_mode: PolygonMode::Line,
+                line_width: Some(rope.width * ctx.client.camera_scale as f32),
+                blend: Blend::alpha_blending(),
+                ..Default::default()
+            };
+
+            if rope.chain {
+                // alternate link colors so the links are visible
+                let dark = Color::rgba(
+                    rope.color.r / 2,
+                    rope.color.g / 2,
+                    rope.color.b / 2,
+                    rope.color.a,
+                );
+                let links = points
+                    .windows(2)
+                    .enumerate()
+                    .map(|(i, w)| {
+                        (
+                            (w[0].x, w[0].y),
+                            (w[1].x, w[1].y),
+                            if i % 2 == 0 { rope.color } else { dark },
+                        )
+                    })
+                    .collect();
+                target.lines(links, params);
+            } else {
+                let strip = points.iter().map(|p| ((p.x, p.y), rope.color)).collect();
+                target.line_strip(strip, params);
+            }
+        }
+    }
+
     fn draw_physics_debug(
         &mut self,
         world: &mut World<ClientChunk>,
@@ -0,0 +1,204 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use zip::ZipArchive;
+
+use super::DataPack;
+
+/// Polls the modification times of every file under some directories, and of some zip files, to detect changes.
+pub struct AssetWatcher {
+    roots: Vec<PathBuf>,
+    interval: Duration,
+    last_poll: Instant,
+    modified: HashMap<PathBuf, SystemTime>,
+    /// Modification time and entries of each watched zip
+    zips: HashMap<PathBuf, (Option<SystemTime>, Vec<PathBuf>)>,
+    changed_zips: Vec<PathBuf>,
+}
+
+impl AssetWatcher {
+    pub fn new(roots: Vec<PathBuf>, interval: Duration) -> Self {
+        let mut modified = HashMap::new();
+        for root in &roots {
+            scan(root, &mut modified);
+        }
+
+        Self {
+            roots,
+            interval,
+            last_poll: Instant::now(),
+            modified,
+            zips: HashMap::new(),
+            changed_zips: Vec::new(),
+        }
+    }
+
+    /// Watches every pack in `packs`.
+    /// A changed zip pack counts as all of its entries changing, see [`AssetWatcher::take_changed_zips`].
+    pub fn for_packs(packs: &[DataPack], interval: Duration) -> Self {
+        let mut watcher = Self::new(
+            packs
+                .iter()
+                .filter_map(|pack| match pack {
+                    DataPack::Dir(path) => Some(path.clone()),
+                    DataPack::Zip { .. } => None,
+                })
+                .collect(),
+            interval,
+        );
+        for pack in packs {
+            if let DataPack::Zip { path, .. } = pack {
+                watcher
+                    .zips
+                    .insert(path.clone(), (modified_time(path), zip_entries(path)));
+            }
+        }
+
+        watcher
+    }
+
+    /// The zip files that changed in the previous polls, which have to be reopened (see [`FileHelper::reopen_pack`](super::FileHelper::reopen_pack)).
+    pub fn take_changed_zips(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.changed_zips)
+    }
+
+    /// Returns the files (relative to their watched dir or zip) that were added, modified, or removed since the last poll.
+    /// Only actually checks the filesystem once per `interval`, otherwise returns nothing.
+    #[profiling::function]
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        if self.last_poll.elapsed() < self.interval {
+            return Vec::new();
+        }
+        self.last_poll = Instant::now();
+
+        let mut modified = HashMap::with_capacity(self.modified.len());
+        for root in &self.roots {
+            scan(root, &mut modified);
+        }
+
+        let mut changed: Vec<PathBuf> = modified
+            .iter()
+            .filter(|(path, time)| self.modified.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.modified
+                    .keys()
+                    .filter(|path| !modified.contains_key(*path))
+                    .cloned(),
+            )
+            .filter_map(|path| {
+                self.roots
+                    .iter()
+                    .find_map(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+            })
+            .collect();
+
+        for (path, (time, entries)) in &mut self.zips {
+            let new_time = modified_time(path);
+            if new_time != *time {
+                let new_entries = zip_entries(path);
+                changed.append(entries);
+                changed.extend(new_entries.iter().cloned());
+                *time = new_time;
+                *entries = new_entries;
+                self.changed_zips.push(path.clone());
+            }
+        }
+
+        changed.sort();
+        changed.dedup();
+
+        self.modified = modified;
+
+        changed
+    }
+}
+
+fn scan(dir: &Path, out: &mut HashMap<PathBuf, SystemTime>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            scan(&path, out);
+        } else if let Ok(time) = entry.metadata().and_then(|m| m.modified()) {
+            out.insert(path, time);
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn zip_entries(path: &Path) -> Vec<PathBuf> {
+    File::open(path)
+        .ok()
+        .and_then(|file| ZipArchive::new(BufReader::new(file)).ok())
+        .map(|archive| {
+            archive
+                .file_names()
+                .filter(|name| !name.ends_with('/'))
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::file_helper::test_dir;
+
+    fn touch(path: &Path) {
+        // writes in quick succession can get the same timestamp
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+    }
+
+    #[test]
+    fn poll_reports_changed_files() {
+        let dir = test_dir("asset_watcher_changes");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.ron"), "a").unwrap();
+        fs::write(dir.join("sub/b.ron"), "b").unwrap();
+
+        let mut watcher = AssetWatcher::new(vec![dir.clone()], Duration::ZERO);
+        assert!(watcher.poll().is_empty());
+
+        touch(&dir.join("a.ron"));
+        fs::write(dir.join("sub/c.ron"), "c").unwrap();
+        fs::remove_file(dir.join("sub/b.ron")).unwrap();
+        assert_eq!(
+            watcher.poll(),
+            vec![
+                PathBuf::from("a.ron"),
+                PathBuf::from("sub/b.ron"),
+                PathBuf::from("sub/c.ron")
+            ]
+        );
+        assert!(watcher.poll().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn poll_waits_for_interval() {
+        let dir = test_dir("asset_watcher_interval");
+        fs::write(dir.join("a.ron"), "a").unwrap();
+
+        let mut watcher = AssetWatcher::new(vec![dir.clone()], Duration::from_hours(1));
+        touch(&dir.join("a.ron"));
+        assert!(watcher.poll().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
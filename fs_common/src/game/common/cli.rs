@@ -43,6 +43,14 @@ pub struct CLArgs {
     )]
     pub assets_dir: PathBuf,
 
+    #[arg(
+        long = "data-pack",
+        value_name = "PATH",
+        action = clap::ArgAction::Append,
+        help = "Add a data pack (directory or zip) on top of the assets, can be repeated. Later packs override earlier ones"
+    )]
+    pub data_packs: Vec<PathBuf>,
+
     #[command(subcommand)]
     pub subcommand: Option<CLSubcommand>,
 }
@@ -58,6 +66,61 @@ pub enum CLSubcommand {
             help = "The port to run the server on"
         )]
         port: u16,
+        #[arg(
+            long,
+            action,
+            value_name = "PATH",
+            help = "The world_info.toml of a world to load, with its data packs"
+        )]
+        world: Option<PathBuf>,
+    },
+    #[command(about = "Load all registries, check their cross-references, and exit")]
+    ValidateAssets,
+    #[command(
+        about = "Generate an area of a world without a window and save it as PNGs (color, biomes, structures)"
+    )]
+    WorldgenPreview {
+        #[arg(long, action, allow_negative_numbers = true, default_value = "0")]
+        seed: i32,
+        #[arg(
+            long,
+            action,
+            allow_negative_numbers = true,
+            default_value = "-8",
+            help = "Left edge of the area, in chunks"
+        )]
+        x: i32,
+        #[arg(
+            long,
+            action,
+            allow_negative_numbers = true,
+            default_value = "-2",
+            help = "Top edge of the area, in chunks"
+        )]
+        y: i32,
+        #[arg(
+            long,
+            action,
+            default_value = "16",
+            help = "Width of the area, in chunks"
+        )]
+        width: u16,
+        #[arg(
+            long,
+            action,
+            default_value = "8",
+            help = "Height of the area, in chunks"
+        )]
+        height: u16,
+        #[arg(
+            long,
+            short,
+            value_name = "PATH",
+            action,
+            default_value = "./worldgen_preview/",
+            help = "Directory to write the images to"
+        )]
+        out: PathBuf,
     },
 }
 
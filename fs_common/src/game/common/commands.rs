@@ -1,4 +1,4 @@
-use clap::{ArgMatches, Command};
+use clap::{value_parser, Arg, ArgMatches, Command};
 
 pub struct CommandHandler {
     commands: Command,
@@ -18,7 +18,73 @@ impl CommandHandler {
                         .aliases(["exit", "quit", "stop"])
                         .about("Exit the game"),
                 )
-                .subcommand(Command::new("save").about("Save the game")),
+                .subcommand(Command::new("save").about("Save the game"))
+                .subcommand(Command::new("reload").about("Reload assets and registries"))
+                .subcommand(
+                    Command::new("locate")
+                        .about("Find the nearest chunk where a structure set or configured structure generates")
+                        .arg(Arg::new("structure").required(true))
+                        .arg(
+                            Arg::new("radius")
+                                .help("Max distance to search in chunks")
+                                .value_parser(value_parser!(u32))
+                                .default_value("256"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("explode")
+                        .about("Cause an explosion at a position, or at the first player")
+                        .arg(
+                            Arg::new("radius")
+                                .help("Radius in pixels")
+                                .value_parser(value_parser!(f32))
+                                .default_value("30"),
+                        )
+                        .arg(
+                            Arg::new("power")
+                                .help("Strength at the center, compared against material blast resistance")
+                                .value_parser(value_parser!(f32))
+                                .default_value("10"),
+                        )
+                        .arg(
+                            Arg::new("x")
+                                .value_parser(value_parser!(f64))
+                                .allow_hyphen_values(true)
+                                .requires("y"),
+                        )
+                        .arg(
+                            Arg::new("y")
+                                .value_parser(value_parser!(f64))
+                                .allow_hyphen_values(true),
+                        ),
+                )
+                .subcommand(
+                    Command::new("rope")
+                        .about("Hang a rope between two positions, attached to whatever is solid there")
+                        .args(["x1", "y1", "x2", "y2"].map(|name| {
+                            Arg::new(name)
+                                .required(true)
+                                .value_parser(value_parser!(f32))
+                                .allow_hyphen_values(true)
+                        }))
+                        .arg(
+                            Arg::new("length")
+                                .help("Length in pixels, defaults to the distance between the positions")
+                                .value_parser(value_parser!(f32)),
+                        )
+                        .arg(
+                            Arg::new("chain")
+                                .long("chain")
+                                .help("Make a chain instead")
+                                .action(clap::ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("loose")
+                                .long("loose")
+                                .help("Only attach the first position")
+                                .action(clap::ArgAction::SetTrue),
+                        ),
+                ),
         }
     }
 
@@ -1,32 +1,188 @@
 use std::{
-    fs,
-    path::{Path, PathBuf},
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
 };
 
+use zip::ZipArchive;
+
+/// A source of asset files, either a directory or a zip file.
+pub enum DataPack {
+    Dir(PathBuf),
+    Zip {
+        path: PathBuf,
+        archive: Mutex<ZipArchive<BufReader<File>>>,
+    },
+}
+
+impl DataPack {
+    /// Opens `path` as a zip pack if it has a `.zip` extension, otherwise as a directory pack.
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+        {
+            let file = File::open(&path)
+                .map_err(|e| format!("Failed to open data pack {}: {e}", path.display()))?;
+            let archive = ZipArchive::new(BufReader::new(file))
+                .map_err(|e| format!("Failed to open data pack {}: {e}", path.display()))?;
+            Ok(Self::Zip { path, archive: Mutex::new(archive) })
+        } else {
+            Ok(Self::Dir(path))
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Dir(path) | Self::Zip { path, .. } => path,
+        }
+    }
+
+    /// Returns `None` if this pack doesn't contain `path`.
+    fn read(&self, path: &Path) -> Option<Result<Vec<u8>, String>> {
+        match self {
+            Self::Dir(dir) => {
+                let full = dir.join(path);
+                full.is_file().then(|| {
+                    fs::read(&full).map_err(|e| format!("Failed to read {}: {e}", full.display()))
+                })
+            },
+            Self::Zip { path: zip_path, archive } => {
+                let mut archive = archive.lock().unwrap();
+                let mut file = archive.by_name(&zip_entry_name(path)).ok()?;
+                let mut buf = Vec::with_capacity(file.size() as usize);
+                Some(file.read_to_end(&mut buf).map(|_| buf).map_err(|e| {
+                    format!(
+                        "Failed to read {} from {}: {e}",
+                        path.display(),
+                        zip_path.display()
+                    )
+                }))
+            },
+        }
+    }
+
+    /// The files directly inside `dir`, relative to the root of the pack.
+    fn files_in_dir(&self, dir: &Path) -> Vec<PathBuf> {
+        match self {
+            Self::Dir(root) => fs::read_dir(root.join(dir))
+                .into_iter()
+                .flat_map(|rd| {
+                    rd.flatten()
+                        .filter(|entry| entry.path().is_file())
+                        .map(|entry| dir.join(entry.file_name()))
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+            Self::Zip { archive, .. } => {
+                let archive = archive.lock().unwrap();
+                archive
+                    .file_names()
+                    .filter(|name| !name.ends_with('/'))
+                    .map(PathBuf::from)
+                    .filter(|p| p.parent() == Some(dir))
+                    .collect()
+            },
+        }
+    }
+}
+
+// zip entries always use '/' regardless of platform
+fn zip_entry_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Asset paths are relative to the root of a data pack.
+/// Packs are layered in order, so a file in a later pack overrides the same file in earlier ones.
 pub struct FileHelper {
     game_dir: PathBuf,
-    asset_dir: PathBuf,
+    base_packs: Vec<PathBuf>,
+    packs: Vec<DataPack>,
 }
 
 impl FileHelper {
-    pub fn new(game_dir: PathBuf, asset_dir: PathBuf) -> Self {
-        Self { game_dir, asset_dir }
+    pub fn new(game_dir: PathBuf, base_packs: Vec<PathBuf>) -> Result<Self, String> {
+        let packs = base_packs
+            .iter()
+            .map(|p| DataPack::open(p.clone()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { game_dir, base_packs, packs })
     }
 
     pub fn game_path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
         self.game_dir.join(path)
     }
 
-    pub fn asset_path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
-        self.asset_dir.join(path)
+    pub fn packs(&self) -> &[DataPack] {
+        &self.packs
+    }
+
+    /// A new `FileHelper` with `extra_packs` layered on top of the base packs, eg. the ones enabled for a world.
+    /// `extra_packs` are relative to the `datapacks` folder in the game dir.
+    pub fn with_extra_packs(&self, extra_packs: &[PathBuf]) -> Result<Self, String> {
+        let mut packs = self
+            .base_packs
+            .iter()
+            .map(|p| DataPack::open(p.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        for p in extra_packs {
+            let path = self.game_path("datapacks").join(p);
+            if !path.exists() {
+                return Err(format!("Missing data pack: {}", path.display()));
+            }
+            packs.push(DataPack::open(path)?);
+        }
+
+        Ok(Self {
+            game_dir: self.game_dir.clone(),
+            base_packs: self.base_packs.clone(),
+            packs,
+        })
+    }
+
+    /// Opens the zip pack at `path` again, eg. after it was changed on disk.
+    pub fn reopen_pack(&mut self, path: &Path) -> Result<(), String> {
+        for pack in &mut self.packs {
+            if matches!(pack, DataPack::Zip { .. }) && pack.path() == path {
+                *pack = DataPack::open(path.to_path_buf())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads an asset file from the last pack that contains it, with the path included in the error message.
+    pub fn read_asset<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>, String> {
+        let path = path.as_ref();
+        self.packs
+            .iter()
+            .rev()
+            .find_map(|pack| pack.read(path))
+            .unwrap_or_else(|| Err(format!("Missing asset: {}", path.display())))
+    }
+
+    pub fn read_asset_to_string<P: AsRef<Path>>(&self, path: P) -> Result<String, String> {
+        let path = path.as_ref();
+        String::from_utf8(self.read_asset(path)?)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
     }
 
+    /// The files directly inside `path` in any pack.
     pub fn files_in_dir<P: AsRef<Path>>(&self, path: P) -> Box<dyn Iterator<Item = PathBuf>> {
-        Box::new(
-            fs::read_dir(self.asset_path(path))
-                .into_iter()
-                .flat_map(|dir| dir.flatten().map(|entry| entry.path()).collect::<Vec<_>>()),
-        )
+        let files: BTreeSet<PathBuf> = self
+            .packs
+            .iter()
+            .flat_map(|pack| pack.files_in_dir(path.as_ref()))
+            .collect();
+        Box::new(files.into_iter())
     }
 
     pub fn files_in_dir_with_ext<'a, P: AsRef<Path>>(
@@ -40,3 +196,12 @@ impl FileHelper {
         }))
     }
 }
+
+/// An empty directory for a test to write files into, unique to `name` and this test run
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fs_test_{name}_{}", std::process::id()));
+    let _ignore = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
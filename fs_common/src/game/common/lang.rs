@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::FileHelper;
+
+pub const DEFAULT_LANGUAGE: &str = "en_us";
+pub const LANG_DIR: &str = "lang";
+
+/// A language file, `lang/<code>.ron`
+#[derive(Debug, Deserialize)]
+struct Language {
+    /// The name of the language, in that language
+    name: String,
+    /// The language to use for keys missing from this one.
+    /// If `None`, falls back to [`DEFAULT_LANGUAGE`]
+    #[serde(default)]
+    fallback: Option<String>,
+    translations: HashMap<String, String>,
+}
+
+pub struct Lang {
+    language: String,
+    /// Translations for the selected language first, then each of its fallbacks in order
+    chain: Vec<HashMap<String, String>>,
+    /// (code, name) of every language in the lang dir
+    available: Vec<(String, String)>,
+}
+
+impl Lang {
+    /// No translations, every key translates to itself
+    pub fn empty() -> Self {
+        Self {
+            language: DEFAULT_LANGUAGE.to_owned(),
+            chain: Vec::new(),
+            available: Vec::new(),
+        }
+    }
+
+    pub fn load(language: &str, file_helper: &FileHelper) -> Result<Self, String> {
+        let mut chain = Vec::new();
+        let mut visited = Vec::new();
+
+        let mut next = Some(language.to_owned());
+        while let Some(code) = next.take() {
+            if visited.contains(&code) {
+                return Err(format!(
+                    "Language fallback loop: {} -> {code}",
+                    visited.join(" -> ")
+                ));
+            }
+
+            let file = load_file(&code, file_helper)?;
+            next = file
+                .fallback
+                .or_else(|| (code != DEFAULT_LANGUAGE).then(|| DEFAULT_LANGUAGE.to_owned()));
+            chain.push(file.translations);
+            visited.push(code);
+        }
+
+        let mut available = Vec::new();
+        for path in file_helper.files_in_dir_with_ext(LANG_DIR, "ron") {
+            let code = path.file_stem().unwrap().to_string_lossy().to_string();
+            match load_file(&code, file_helper) {
+                Ok(file) => available.push((code, file.name)),
+                Err(e) => log::warn!("{e}"),
+            }
+        }
+        available.sort();
+
+        Ok(Self { language: language.to_owned(), chain, available })
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// (code, name) of every language that can be loaded
+    pub fn available(&self) -> &[(String, String)] {
+        &self.available
+    }
+
+    /// If `key` is in the selected language or any of its fallbacks
+    pub fn contains(&self, key: &str) -> bool {
+        self.chain
+            .iter()
+            .any(|translations| translations.contains_key(key))
+    }
+
+    /// Looks up `key` in the selected language, then each fallback.
+    /// If no language has it, returns `key` itself.
+    pub fn translate<'a>(&'a self, key: &'a str) -> &'a str {
+        self.chain
+            .iter()
+            .find_map(|translations| translations.get(key))
+            .map_or(key, String::as_str)
+    }
+}
+
+fn load_file(code: &str, file_helper: &FileHelper) -> Result<Language, String> {
+    let path = format!("{LANG_DIR}/{code}.ron");
+    let bytes = file_helper.read_asset(&path)?;
+    ron::de::from_bytes(&bytes).map_err(|e| format!("Failed to parse {path}: {e}"))
+}
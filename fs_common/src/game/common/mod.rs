@@ -1,8 +1,10 @@
 pub mod networking;
 pub mod world;
 
+pub mod asset_watcher;
 pub mod cli;
 pub mod hashmap_ext;
+pub mod lang;
 mod registries;
 pub mod registry;
 mod settings;
@@ -1,22 +1,32 @@
+use std::path::{Path, PathBuf};
+
 use super::{
     world::{
         gen::{
-            biome::{self, BiomeRegistry},
+            biome::{self, BiomeRegistry, MaterialPlacerIDOrMaterialInstance},
             structure::{
-                self, configured_structure::ConfiguredStructureRegistry,
-                piece::StructurePieceRegistry, pool::StructurePoolRegistry,
+                self,
+                configured_structure::{ConfiguredStructurePlacer, ConfiguredStructureRegistry},
+                piece::StructurePieceRegistry,
+                pool::StructurePoolRegistry,
                 set::StructureSetRegistry,
             },
         },
         material::{
             self,
-            placer::{self, MaterialPlacerRegistry},
+            placer::{self, data::MATERIAL_PLACER_DATA_DIR, MaterialPlacerRegistry},
             MaterialRegistry,
         },
     },
     FileHelper,
 };
 
+const MATERIAL_PLACERS_DIR: &str = "texture/material";
+const STRUCTURE_PIECES_DIR: &str = "data/structure/piece";
+const STRUCTURE_POOLS_DIR: &str = "data/structure/pool";
+const STRUCTURE_SETS_DIR: &str = "data/structure/set";
+
+#[derive(Clone)]
 pub struct Registries {
     pub materials: MaterialRegistry,
     pub material_placers: MaterialPlacerRegistry,
@@ -29,17 +39,124 @@ pub struct Registries {
 
 impl Registries {
     pub fn init(file_helper: &FileHelper) -> Self {
-        Self {
+        Self::try_init(file_helper).expect("Failed to load registries")
+    }
+
+    pub fn try_init(file_helper: &FileHelper) -> Result<Self, String> {
+        Ok(Self {
             materials: material::init_material_types(),
-            material_placers: placer::init_material_placers(file_helper),
-            structure_pieces: structure::piece::init_structure_pieces(file_helper),
-            structure_pools: structure::pool::init_structure_pools(file_helper),
+            material_placers: placer::init_material_placers(file_helper)?,
+            structure_pieces: structure::piece::init_structure_pieces(file_helper)?,
+            structure_pools: structure::pool::init_structure_pools(file_helper)?,
             configured_structures: structure::configured_structure::init_configured_structures(
                 file_helper,
             ),
-            structure_sets: structure::set::init_structure_sets(file_helper),
+            structure_sets: structure::set::init_structure_sets(file_helper)?,
             biomes: biome::init_biomes(file_helper),
+        })
+    }
+
+    /// Rebuilds the registries loaded from any of the `changed` asset paths (relative to the assets dir).
+    /// The rest are shared with `self`.
+    /// If anything fails to load, returns the error and `self` should be kept as-is.
+    pub fn reload(&self, file_helper: &FileHelper, changed: &[PathBuf]) -> Result<Self, String> {
+        let touched = |dir: &str| changed.iter().any(|p| p.starts_with(dir));
+
+        let mut new = self.clone();
+        if touched(MATERIAL_PLACERS_DIR) || touched(MATERIAL_PLACER_DATA_DIR) {
+            new.material_placers = placer::init_material_placers(file_helper)?;
+        }
+        if touched(STRUCTURE_PIECES_DIR) {
+            new.structure_pieces = structure::piece::init_structure_pieces(file_helper)?;
+        }
+        if touched(STRUCTURE_POOLS_DIR) {
+            new.structure_pools = structure::pool::init_structure_pools(file_helper)?;
         }
+        if touched(STRUCTURE_SETS_DIR) {
+            new.structure_sets = structure::set::init_structure_sets(file_helper)?;
+        }
+
+        Ok(new)
+    }
+
+    /// If a change to the asset at `path` (relative to the assets dir) affects any registry.
+    pub fn is_registry_asset(path: &Path) -> bool {
+        [
+            MATERIAL_PLACERS_DIR,
+            MATERIAL_PLACER_DATA_DIR,
+            STRUCTURE_PIECES_DIR,
+            STRUCTURE_POOLS_DIR,
+            STRUCTURE_SETS_DIR,
+        ]
+        .iter()
+        .any(|dir| path.starts_with(dir))
+    }
+
+    /// Checks every reference from one registry into another.
+    /// Returns a readable description of each broken reference, sorted.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for (id, pool) in &self.structure_pools {
+            for piece in &pool.pool {
+                if self.structure_pieces.get(piece).is_none() {
+                    errors.push(format!(
+                        "structure pool \"{id}\": unknown structure piece \"{piece}\""
+                    ));
+                }
+            }
+        }
+
+        for (id, piece) in &self.structure_pieces {
+            for (placement, config) in &piece.child_nodes {
+                for pool in std::iter::once(&config.pool).chain(&config.fallback_pool) {
+                    if self.structure_pools.get(pool).is_none() {
+                        errors.push(format!(
+                            "structure piece \"{id}\": node at ({}, {}) references unknown structure pool \"{pool}\"",
+                            placement.x, placement.y
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (id, configured) in &self.configured_structures {
+            for e in configured.validate(self) {
+                errors.push(format!("configured structure \"{id}\": {e}"));
+            }
+        }
+
+        for (id, set) in &self.structure_sets {
+            for structure in &set.structures {
+                if self.configured_structures.get(structure).is_none() {
+                    errors.push(format!(
+                        "structure set \"{id}\": unknown configured structure \"{structure}\""
+                    ));
+                }
+            }
+
+            if let Some(exclusion) = &set.exclusion {
+                if self.structure_sets.get(&exclusion.other_set).is_none() {
+                    errors.push(format!(
+                        "structure set \"{id}\": exclusion references unknown structure set \"{}\"",
+                        exclusion.other_set
+                    ));
+                }
+            }
+        }
+
+        for (id, biome) in &self.biomes {
+            if let MaterialPlacerIDOrMaterialInstance::MaterialPlacer(placer) = &biome.base_placer {
+                if self.material_placers.get(placer).is_none() {
+                    errors.push(format!(
+                        "biome \"{id}\": unknown material placer \"{placer}\""
+                    ));
+                }
+            }
+        }
+
+        errors.sort();
+        errors
     }
 
     pub fn empty() -> Self {
@@ -54,3 +171,81 @@ impl Registries {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use crate::game::common::file_helper::test_dir;
+
+    const POOL: &str = "data/structure/pool/test_pool.ron";
+
+    /// The base assets with an empty pack on top for the test to write into
+    fn file_helper(name: &str) -> (FileHelper, PathBuf) {
+        let pack = test_dir(name);
+        let file_helper = FileHelper::new(
+            "../gamedir/".into(),
+            vec!["../gamedir/assets/".into(), pack.clone()],
+        )
+        .unwrap();
+        (file_helper, pack)
+    }
+
+    fn write_pool(pack: &Path, contents: &str) {
+        let path = pack.join(POOL);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn reload_rebuilds_changed_registries() {
+        let (file_helper, pack) = file_helper("registries_reload");
+        let registries = Registries::try_init(&file_helper).unwrap();
+        assert!(registries.structure_pools.get("test_pool").is_none());
+
+        write_pool(&pack, "StructurePool(pool: [])");
+
+        // unrelated changes don't reload pools
+        let reloaded = registries
+            .reload(&file_helper, &[PathBuf::from("lang/en_us.lang")])
+            .unwrap();
+        assert!(reloaded.structure_pools.get("test_pool").is_none());
+
+        let reloaded = registries
+            .reload(&file_helper, &[PathBuf::from(POOL)])
+            .unwrap();
+        assert!(reloaded.structure_pools.get("test_pool").is_some());
+        assert!(reloaded.structure_pools.get("torch").is_some());
+
+        fs::remove_dir_all(pack).unwrap();
+    }
+
+    #[test]
+    fn reload_reports_broken_file() {
+        let (file_helper, pack) = file_helper("registries_broken");
+        let registries = Registries::try_init(&file_helper).unwrap();
+
+        write_pool(&pack, "StructurePool(pool: [");
+        let err = registries
+            .reload(&file_helper, &[PathBuf::from(POOL)])
+            .err()
+            .unwrap();
+        assert!(err.contains("test_pool.ron"), "{err}");
+
+        fs::remove_dir_all(pack).unwrap();
+    }
+
+    #[test]
+    fn registry_assets() {
+        assert!(Registries::is_registry_asset(Path::new(POOL)));
+        assert!(Registries::is_registry_asset(Path::new(
+            "data/structure/piece/room.ase"
+        )));
+        assert!(!Registries::is_registry_asset(Path::new("lang/en_us.lang")));
+        assert!(!Registries::is_registry_asset(Path::new(
+            "shaders/chunk.frag"
+        )));
+    }
+}
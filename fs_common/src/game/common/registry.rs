@@ -79,18 +79,22 @@ impl<S: Into<String>, T> From<S> for RegistryID<T> {
     }
 }
 
+/// The backing map is shared, so cloning a `Registry` is cheap.
+/// This lets a reload rebuild only the registries that changed and reuse the rest.
 pub struct Registry<V> {
-    map: HashMap<RegistryID<V>, V, ahash::RandomState>,
+    map: Arc<HashMap<RegistryID<V>, V, ahash::RandomState>>,
 }
 
 impl<V> Registry<V> {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self { map: HashMap::default() }
+        Self { map: Arc::default() }
     }
 
     pub fn register(&mut self, key: impl Into<RegistryID<V>>, value: V) {
-        self.map.insert(key.into(), value);
+        Arc::get_mut(&mut self.map)
+            .expect("Tried to register into a Registry that is already shared")
+            .insert(key.into(), value);
     }
 
     #[inline]
@@ -103,6 +107,12 @@ impl<V> Registry<V> {
     }
 }
 
+impl<V> Clone for Registry<V> {
+    fn clone(&self) -> Self {
+        Self { map: self.map.clone() }
+    }
+}
+
 impl<'a, V> IntoIterator for &'a Registry<V> {
     type Item = (&'a RegistryID<V>, &'a V);
     type IntoIter = hash_map::Iter<'a, RegistryID<V>, V>;
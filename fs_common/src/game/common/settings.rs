@@ -1,4 +1,6 @@
-use super::{registry::RegistryID, world::gen::structure::set::StructureSet};
+use super::{
+    lang::DEFAULT_LANGUAGE, registry::RegistryID, world::gen::structure::set::StructureSet,
+};
 
 pub struct Settings {
     pub debug: bool,
@@ -32,6 +34,7 @@ pub struct Settings {
     pub fullscreen_type: usize,
     pub vsync: bool,
     pub minimize_on_lost_focus: bool,
+    pub language: String,
 
     // simulation
     pub tick: bool,
@@ -42,7 +45,13 @@ pub struct Settings {
     pub load_chunks: bool,
     pub simulate_chunks: bool,
     pub simulate_particles: bool,
+    pub structural_integrity: bool,
+    pub fracture_rigidbodies: bool,
+    pub settle_rigidbodies: bool,
     pub pause_on_lost_focus: bool,
+
+    // assets
+    pub hot_reload_assets: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -97,6 +106,7 @@ impl Default for Settings {
             fullscreen_type: 0,
             vsync: false,
             minimize_on_lost_focus: false,
+            language: DEFAULT_LANGUAGE.to_owned(),
 
             tick: true,
             tick_speed: 30,
@@ -106,7 +116,12 @@ impl Default for Settings {
             load_chunks: true,
             simulate_chunks: true,
             simulate_particles: true,
+            structural_integrity: true,
+            fracture_rigidbodies: true,
+            settle_rigidbodies: true,
             pause_on_lost_focus: false,
+
+            hot_reload_assets: true,
         }
     }
 }
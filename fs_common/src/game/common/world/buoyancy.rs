@@ -0,0 +1,232 @@
+use rapier2d::{
+    na::{Point2, Vector2},
+    prelude::RigidBody,
+};
+
+use crate::game::common::{registry::RegistryID, Registries};
+
+use super::{
+    chunk_access::FSChunkAccess,
+    chunk_handler::ChunkHandler,
+    material::{Material, PhysicsType},
+    physics::PHYSICS_SCALE,
+    rigidbody::FSRigidBody,
+    Chunk,
+};
+
+/// Area of one pixel in physics units, rigidbodies have `density * PIXEL_AREA` mass per pixel
+const PIXEL_AREA: f32 = 1.0 / (PHYSICS_SCALE * PHYSICS_SCALE);
+
+/// How deep a rigidbody is in liquid, collected while it is filled into the world.
+///
+/// Liquid touching the body's edge marks the surface, every pixel of the body below it counts as submerged.
+/// Submerged pixels are pushed up by the weight of the liquid they displace,
+///   and slowed down by its [`drag`](super::material::Material::drag).
+#[derive(Debug, Clone)]
+pub struct Submersion {
+    /// Highest world y position (in pixels) where the body touches liquid
+    surface: f32,
+    liquid: Option<RegistryID<Material>>,
+}
+
+impl Submersion {
+    pub fn new() -> Self {
+        Self { surface: f32::INFINITY, liquid: None }
+    }
+
+    /// Checks for liquid at the world position of a body pixel,
+    ///   and around it if `edge` (the pixel is on the outside of the body).
+    pub fn sample<C: Chunk>(
+        &mut self,
+        chunk_handler: &ChunkHandler<C>,
+        x: f32,
+        y: f32,
+        edge: bool,
+    ) {
+        let (x, y) = (x as i64, y as i64);
+        let offsets: &[(i64, i64)] = if edge {
+            &[(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+        } else {
+            &[(0, 0)]
+        };
+
+        for (dx, dy) in offsets {
+            if let Ok(m) = chunk_handler.pixel(x + dx, y + dy) {
+                if m.physics == PhysicsType::Liquid {
+                    let y = (y + dy) as f32;
+                    if self.liquid.is_none() || y < self.surface {
+                        self.surface = y;
+                        self.liquid = Some(m.material_id.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces the buoyancy and drag on `body` from last tick with the ones from the liquid it's in now.
+    /// Other forces on the body are left alone.
+    /// Only wakes the body up if the forces changed noticeably, so floating bodies can still sleep.
+    pub fn apply(
+        &self,
+        rb: &mut FSRigidBody,
+        body: &mut RigidBody,
+        gravity: Vector2<f32>,
+        registries: &Registries,
+    ) {
+        let mut new = LiquidForce::default();
+
+        if let Some(liquid) = self
+            .liquid
+            .as_ref()
+            .and_then(|id| registries.materials.get(id))
+        {
+            let (s, c) = body.rotation().angle().sin_cos();
+            let pos_x = body.translation().x * PHYSICS_SCALE;
+            let pos_y = body.translation().y * PHYSICS_SCALE;
+            let com = *body.center_of_mass();
+
+            for rb_y in 0..rb.height {
+                for rb_x in 0..rb.width {
+                    if rb.pixels[usize::from(rb_x) + usize::from(rb_y) * usize::from(rb.width)]
+                        .physics
+                        == PhysicsType::Air
+                    {
+                        continue;
+                    }
+
+                    let tx = f32::from(rb_x) * c - f32::from(rb_y) * s + pos_x;
+                    let ty = f32::from(rb_x) * s + f32::from(rb_y) * c + pos_y;
+                    if ty.floor() < self.surface {
+                        continue;
+                    }
+
+                    let point = Point2::new(tx / PHYSICS_SCALE, ty / PHYSICS_SCALE);
+                    let velocity = body.velocity_at_point(&point);
+                    let force = -gravity * liquid.density * PIXEL_AREA
+                        - velocity * liquid.drag * PIXEL_AREA;
+                    new.force += force;
+                    new.torque += (point - com).perp(&force);
+                }
+            }
+        }
+
+        let old = std::mem::replace(&mut rb.liquid_force, new);
+        body.add_force(new.force - old.force, false);
+        body.add_torque(new.torque - old.torque, false);
+
+        if (new.force - old.force).norm() > body.mass() * gravity.norm() * 0.01 {
+            body.wake_up(true);
+        }
+    }
+}
+
+/// Buoyancy and drag that [`Submersion::apply`] added to a body, so it can take them back off next tick
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiquidForce {
+    pub force: Vector2<f32>,
+    pub torque: f32,
+}
+
+impl Default for Submersion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// If the pixel at `x`, `y` in `rb` has air or the edge of the body next to it
+pub fn is_edge(rb: &FSRigidBody, x: u16, y: u16) -> bool {
+    x == 0
+        || y == 0
+        || x + 1 >= rb.width
+        || y + 1 >= rb.height
+        || [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .any(|(x, y)| {
+                rb.pixels[usize::from(x) + usize::from(y) * usize::from(rb.width)].physics
+                    == PhysicsType::Air
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        material::{self, MaterialInstance},
+        physics::Physics,
+        test_chunk,
+    };
+
+    #[test]
+    fn sample_finds_surface() {
+        let mut ch = test_chunk::chunk_handler([(0, 0)]);
+        for y in 50..100 {
+            for x in 0..100 {
+                ch.set_pixel(x, y, test_chunk::water()).unwrap();
+            }
+        }
+
+        let mut submersion = Submersion::new();
+        submersion.sample(&ch, 10.5, 48.5, true);
+        assert!(submersion.liquid.is_none());
+
+        submersion.sample(&ch, 10.5, 49.5, false);
+        assert!(submersion.liquid.is_none());
+        submersion.sample(&ch, 10.5, 49.5, true);
+        assert_eq!(submersion.liquid, Some(material::WATER.clone()));
+        assert!((submersion.surface - 50.0).abs() < f32::EPSILON);
+
+        submersion.sample(&ch, 10.5, 70.5, true);
+        assert!((submersion.surface - 50.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn apply_replaces_only_its_own_force() {
+        let registries = test_chunk::registries();
+        let mut physics = Physics::new();
+        let gravity = physics.gravity;
+        let mut rb = test_chunk::stone_body(10, 10, (0.0, 0.0), &mut physics);
+        let other = Vector2::new(3.0, 0.0);
+        rb.get_body_mut(&mut physics)
+            .unwrap()
+            .add_force(other, false);
+
+        let submerged = Submersion {
+            surface: f32::NEG_INFINITY,
+            liquid: Some(material::WATER.clone()),
+        };
+        for _ in 0..3 {
+            let body = physics.bodies.get_mut(rb.body.unwrap()).unwrap();
+            submerged.apply(&mut rb, body, gravity, &registries);
+        }
+
+        let body = rb.get_body(&physics).unwrap();
+        let up = -rb.liquid_force.force.y;
+        let water = registries.materials.get(&*material::WATER).unwrap();
+        let expected = gravity.y * water.density * PIXEL_AREA * 100.0;
+        assert!((up - expected).abs() < 1e-4);
+        // stone is denser than water, so it still sinks
+        assert!(up < body.mass() * gravity.y);
+        assert!((body.user_force() - other - rb.liquid_force.force).norm() < 1e-4);
+
+        // out of the liquid again
+        let body = physics.bodies.get_mut(rb.body.unwrap()).unwrap();
+        Submersion::new().apply(&mut rb, body, gravity, &registries);
+        let body = rb.get_body(&physics).unwrap();
+        assert!((body.user_force() - other).norm() < 1e-4);
+        assert!(body.user_torque().abs() < 1e-4);
+        assert!(rb.liquid_force.force.norm() < f32::EPSILON);
+    }
+
+    #[test]
+    fn edges() {
+        let mut pixels = vec![test_chunk::stone(); 5 * 5];
+        pixels[2 + 2 * 5] = MaterialInstance::air();
+        let rb = FSRigidBody::from_pixels(pixels, 5, 5).unwrap();
+
+        assert!(is_edge(&rb, 0, 3));
+        assert!(is_edge(&rb, 4, 4));
+        assert!(is_edge(&rb, 2, 1));
+        assert!(!is_edge(&rb, 1, 1));
+    }
+}
@@ -18,6 +18,11 @@ pub const CHUNK_AREA: usize = CHUNK_SIZE as usize * CHUNK_SIZE as usize;
 // must be a factor of CHUNK_SIZE
 // also (CHUNK_SIZE / LIGHT_SCALE)^2 must be <= 1024 for compute shader (and local_size needs to be set to CHUNK_SIZE / LIGHT_SCALE in the shader)
 pub const LIGHT_SCALE: u8 = 4;
+// must be a factor of CHUNK_SIZE
+/// Size of the tiles a chunk's collision mesh is split into, so changing pixels only re-meshes the tiles they are in
+pub const COLLISION_TILE_SIZE: u16 = 20;
+pub const COLLISION_TILES: usize =
+    (CHUNK_SIZE / COLLISION_TILE_SIZE) as usize * (CHUNK_SIZE / COLLISION_TILE_SIZE) as usize;
 
 pub trait Chunk {
     fn new_empty(chunk_x: i32, chunk_y: i32) -> Self
@@ -33,6 +38,10 @@ pub trait Chunk {
     fn dirty_rect(&self) -> Option<Rect<i32>>;
     fn set_dirty_rect(&mut self, rect: Option<Rect<i32>>);
 
+    /// Area where solid pixels were removed since the last structural integrity check
+    fn integrity_rect(&self) -> Option<Rect<i32>>;
+    fn set_integrity_rect(&mut self, rect: Option<Rect<i32>>);
+
     fn set_pixels(&mut self, pixels: Box<[MaterialInstance; CHUNK_AREA]>);
     fn pixels_mut(&mut self) -> &mut Option<Box<[MaterialInstance; CHUNK_AREA]>>;
     fn pixels(&self) -> &Option<Box<[MaterialInstance; CHUNK_AREA]>>;
@@ -48,9 +57,18 @@ pub trait Chunk {
     fn background_colors_mut(&mut self) -> &mut [Color; CHUNK_AREA];
     fn background_colors(&self) -> &[Color; CHUNK_AREA];
 
-    fn generate_mesh(&mut self) -> Result<(), String>;
+    /// Regenerates the collision mesh of every tile
+    fn generate_mesh(&mut self) -> Result<(), String> {
+        self.generate_mesh_tiles(&(0..COLLISION_TILES).collect::<Vec<_>>())
+    }
+    /// Regenerates the collision mesh of `tiles` (see [`collision_tiles_in`]), or of every tile if there is no mesh yet
+    fn generate_mesh_tiles(&mut self, tiles: &[usize]) -> Result<(), String>;
     // fn get_tris(&self) -> &Option<Vec<Vec<((f64, f64), (f64, f64), (f64, f64))>>>;
-    fn mesh_loops(&self) -> &Option<Mesh>;
+    /// One mesh per collision tile, in chunk local pixels
+    fn mesh_loops(&self) -> &Option<Vec<Mesh>>;
+    /// Area where pixels became or stopped being solid since the collision mesh was last updated
+    fn mesh_rect(&self) -> Option<Rect<i32>>;
+    fn set_mesh_rect(&mut self, rect: Option<Rect<i32>>);
     fn rigidbody(&self) -> &Option<ChunkRigidBodyState>;
     fn rigidbody_mut(&mut self) -> &mut Option<ChunkRigidBodyState>;
     fn set_rigidbody(&mut self, body: Option<ChunkRigidBodyState>);
@@ -280,6 +298,66 @@ mod tests {
             }
         }
     }
+
+    fn tiles_in(x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<usize> {
+        collision_tiles_in(Rect::new(x1, y1, x2, y2)).collect()
+    }
+
+    #[test]
+    fn collision_tiles_in_rect() {
+        assert_eq!(tiles_in(0, 0, 1, 1), vec![0]);
+        assert_eq!(tiles_in(0, 20, 1, 21), vec![5]);
+        assert_eq!(tiles_in(99, 99, 100, 100), vec![24]);
+
+        // the right and bottom edges are exclusive
+        assert_eq!(tiles_in(0, 0, 20, 20), vec![0]);
+        assert_eq!(tiles_in(19, 0, 21, 1), vec![0, 1]);
+        assert_eq!(tiles_in(20, 0, 21, 1), vec![1]);
+        assert_eq!(tiles_in(19, 19, 21, 21), vec![0, 1, 5, 6]);
+
+        assert_eq!(
+            tiles_in(0, 0, CHUNK_SIZE.into(), CHUNK_SIZE.into()),
+            (0..COLLISION_TILES).collect::<Vec<_>>()
+        );
+
+        // parts outside of the chunk are clamped to the edge tiles
+        assert_eq!(tiles_in(-5, -5, 1, 1), vec![0]);
+        assert_eq!(tiles_in(99, 99, 150, 150), vec![24]);
+        assert_eq!(tiles_in(-50, 50, 0, 51), vec![10]);
+    }
+
+    #[test]
+    fn collision_tile_pos_corners() {
+        assert_eq!(collision_tile_pos(0), (0, 0));
+        assert_eq!(collision_tile_pos(4), (80, 0));
+        assert_eq!(collision_tile_pos(5), (0, 20));
+        assert_eq!(collision_tile_pos(COLLISION_TILES - 1), (80, 80));
+
+        for tile in 0..COLLISION_TILES {
+            let (x, y) = collision_tile_pos(tile);
+            let rect = Rect::new_wh(x, y, COLLISION_TILE_SIZE, COLLISION_TILE_SIZE);
+            assert_eq!(collision_tiles_in(rect).collect::<Vec<_>>(), vec![tile]);
+        }
+    }
+}
+
+/// Indices of the collision tiles overlapping `rect` (in chunk local pixels)
+pub fn collision_tiles_in(rect: Rect<i32>) -> impl Iterator<Item = usize> {
+    let tiles = i32::from(CHUNK_SIZE / COLLISION_TILE_SIZE);
+    let tile = |v: i32| (v.div_euclid(i32::from(COLLISION_TILE_SIZE))).clamp(0, tiles - 1);
+    let (x1, x2) = (tile(rect.left()), tile(rect.right() - 1));
+    let (y1, y2) = (tile(rect.top()), tile(rect.bottom() - 1));
+    (y1..=y2).flat_map(move |y| (x1..=x2).map(move |x| (x + y * tiles) as usize))
+}
+
+/// Top left corner of a collision tile, in chunk local pixels
+#[inline]
+pub const fn collision_tile_pos(tile: usize) -> (u16, u16) {
+    let tiles = (CHUNK_SIZE / COLLISION_TILE_SIZE) as usize;
+    (
+        (tile % tiles) as u16 * COLLISION_TILE_SIZE,
+        (tile / tiles) as u16 * COLLISION_TILE_SIZE,
+    )
 }
 
 // #[profiling::function]
@@ -1,8 +1,11 @@
 use crate::game::common::Rect;
 
 use super::{
-    chunk_index::ChunkLocalIndex, material::MaterialInstance, mesh::Mesh, tile_entity::TileEntity,
-    ChunkRigidBodyState, ChunkState, CHUNK_AREA, CHUNK_SIZE,
+    chunk_index::{ChunkLocalIndex, ChunkLocalPosition},
+    material::{MaterialInstance, PhysicsType},
+    mesh::{self, Mesh},
+    tile_entity::TileEntity,
+    ChunkRigidBodyState, ChunkState, CHUNK_AREA, CHUNK_SIZE, COLLISION_TILES,
 };
 
 pub struct CommonChunkData<S: SidedChunkData> {
@@ -13,8 +16,13 @@ pub struct CommonChunkData<S: SidedChunkData> {
     pub light: Option<Box<[[f32; 3]; CHUNK_AREA]>>,
     pub background: Option<Box<[MaterialInstance; CHUNK_AREA]>>,
     pub dirty_rect: Option<Rect<i32>>,
+    /// Area where solid pixels were removed since the last structural integrity check
+    pub integrity_rect: Option<Rect<i32>>,
+    /// Area where pixels became or stopped being solid since the collision mesh was last updated
+    pub mesh_rect: Option<Rect<i32>>,
     pub rigidbody: Option<ChunkRigidBodyState>,
-    pub mesh_simplified: Option<Mesh>,
+    /// One mesh per collision tile
+    pub mesh_simplified: Option<Vec<Mesh>>,
     pub tile_entities: Vec<TileEntity<S::TileEntityData>>,
 }
 
@@ -32,6 +40,8 @@ impl<S: SidedChunkData> CommonChunkData<S> {
             light: None,
             background: None,
             dirty_rect: None,
+            integrity_rect: None,
+            mesh_rect: None,
             rigidbody: None,
             mesh_simplified: None,
             tile_entities: vec![],
@@ -47,7 +57,16 @@ impl<S: SidedChunkData> CommonChunkData<S> {
         if let Some(px) = &mut self.pixels {
             (cb)(&mat)?;
 
-            px[pos.into()] = mat;
+            let i: ChunkLocalIndex = pos.into();
+            let removed_solid = Self::removes_solid(&px[i], &mat);
+            let changed_solid = Self::changes_solid(&px[i], &mat);
+            px[i] = mat;
+            if removed_solid {
+                self.mark_integrity(i);
+            }
+            if changed_solid {
+                self.mark_mesh(i);
+            }
 
             self.dirty_rect = Some(Rect::new_wh(0, 0, CHUNK_SIZE, CHUNK_SIZE));
 
@@ -60,7 +79,17 @@ impl<S: SidedChunkData> CommonChunkData<S> {
     /// # Safety
     /// Assumes the chunk is loaded (unchecked). Use [`Self::set`] if this is not known.
     pub unsafe fn set_unchecked(&mut self, pos: impl Into<ChunkLocalIndex>, mat: MaterialInstance) {
-        self.pixels.as_mut().unwrap_unchecked()[pos.into()] = mat;
+        let i: ChunkLocalIndex = pos.into();
+        let px = &mut self.pixels.as_mut().unwrap_unchecked()[i];
+        let removed_solid = Self::removes_solid(px, &mat);
+        let changed_solid = Self::changes_solid(px, &mat);
+        *px = mat;
+        if removed_solid {
+            self.mark_integrity(i);
+        }
+        if changed_solid {
+            self.mark_mesh(i);
+        }
 
         self.dirty_rect = Some(Rect::new_wh(0, 0, CHUNK_SIZE, CHUNK_SIZE));
     }
@@ -94,7 +123,15 @@ impl<S: SidedChunkData> CommonChunkData<S> {
             let px = unsafe { px.get_unchecked_mut(*i) };
             if let Some(mat) = (cb)(px) {
                 (chunk_cb)(&mat)?;
+                let removed_solid = Self::removes_solid(px, &mat);
+                let changed_solid = Self::changes_solid(px, &mat);
                 *px = mat;
+                if removed_solid {
+                    self.mark_integrity(i);
+                }
+                if changed_solid {
+                    self.mark_mesh(i);
+                }
 
                 self.dirty_rect = Some(Rect::new_wh(0, 0, CHUNK_SIZE, CHUNK_SIZE));
 
@@ -107,6 +144,60 @@ impl<S: SidedChunkData> CommonChunkData<S> {
         }
     }
 
+    #[inline]
+    fn removes_solid(old: &MaterialInstance, new: &MaterialInstance) -> bool {
+        old.physics == PhysicsType::Solid && new.physics != PhysicsType::Solid
+    }
+
+    #[inline]
+    fn changes_solid(old: &MaterialInstance, new: &MaterialInstance) -> bool {
+        (old.physics == PhysicsType::Solid) != (new.physics == PhysicsType::Solid)
+    }
+
+    fn mark_integrity(&mut self, i: ChunkLocalIndex) {
+        let pos: ChunkLocalPosition = i.into();
+        let px = Rect::new_wh(i32::from(pos.x()), i32::from(pos.y()), 1, 1);
+        self.integrity_rect = Some(self.integrity_rect.map_or(px, |r| r.union(px)));
+    }
+
+    fn mark_mesh(&mut self, i: ChunkLocalIndex) {
+        let pos: ChunkLocalPosition = i.into();
+        let px = Rect::new_wh(i32::from(pos.x()), i32::from(pos.y()), 1, 1);
+        self.mesh_rect = Some(self.mesh_rect.map_or(px, |r| r.union(px)));
+    }
+
+    /// Regenerates the simplified mesh of `tiles`, or of every tile if there is no mesh yet.
+    /// Returns the unsimplified meshes of the tiles that were regenerated.
+    pub fn generate_mesh_tiles(&mut self, tiles: &[usize]) -> Result<Vec<(usize, Mesh)>, String> {
+        let Some(pixels) = &self.pixels else {
+            return Err("generate_mesh failed: self.data.pixels is None".to_owned());
+        };
+
+        let all: Vec<usize>;
+        let tiles = if self.mesh_simplified.is_none() {
+            all = (0..COLLISION_TILES).collect();
+            &all
+        } else {
+            tiles
+        };
+        if tiles.len() == COLLISION_TILES {
+            self.mesh_rect = None;
+        }
+
+        let meshes = self
+            .mesh_simplified
+            .get_or_insert_with(|| vec![vec![]; COLLISION_TILES]);
+        let mut unsimplified = Vec::with_capacity(tiles.len());
+        for &tile in tiles {
+            let (mesh, simplified) =
+                mesh::generate_tile_mesh(pixels.as_ref(), tile).unwrap_or_default();
+            meshes[tile] = simplified;
+            unsimplified.push((tile, mesh));
+        }
+
+        Ok(unsimplified)
+    }
+
     pub fn set_light(
         &mut self,
         pos: impl Into<ChunkLocalIndex>,
@@ -3,7 +3,6 @@ use std::{cell::UnsafeCell, fmt::Debug, path::PathBuf, sync::Arc};
 use asefile::AsepriteFile;
 use chunksystem::{ChunkKey, ChunkManager, ChunkQuery};
 use futures::channel::oneshot::Receiver;
-use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use specs::{Join, ReadStorage, RunNow, WorldExt};
@@ -11,8 +10,14 @@ use specs::{Join, ReadStorage, RunNow, WorldExt};
 use crate::game::common::{
     hashmap_ext::HashMapExt,
     world::{
-        chunk_index, chunk_update_order,
-        gen::{populator::ChunkContext, structure::UpdateStructureNodes, GenBuffers, GenContext},
+        chunk_update_order,
+        explosion::PendingExplosions,
+        gen::{
+            populator::ChunkContext,
+            rng::{chunk_rng, feature_salt},
+            structure::UpdateStructureNodes,
+            GenBuffers, GenContext,
+        },
         material::buf::MaterialRect,
         particle::{Particle, ParticleSystem},
         pixel_to_chunk_pos,
@@ -211,8 +216,10 @@ where
             if let Some(to_load) = self.load_queue.pop() {
                 let c = self.load_chunk(to_load.0, to_load.1);
                 if to_load == (0, 0) {
-                    let ase = AsepriteFile::read_file(
-                        &ctx.file_helper.asset_path("data/tile_entity/test/test.ase"),
+                    let ase = AsepriteFile::read(
+                        &ctx.file_helper
+                            .read_asset("data/tile_entity/test/test.ase")
+                            .unwrap()[..],
                     )
                     .unwrap();
                     c.add_tile_entity(TileEntityCommon {
@@ -701,14 +708,10 @@ where
                                 if cur_stage + 1 == 1 {
                                     let mut chunk_ctx =
                                         ChunkContext::<1, C>::new(&mut chunks_data).unwrap();
-                                    let mut rng = StdRng::seed_from_u64(
-                                        ctx.seed as u64
-                                            + u64::from(chunk_index(
-                                                chunk_ctx.center_chunk().0,
-                                                chunk_ctx.center_chunk().1,
-                                            )),
-                                    );
+                                    let center = chunk_ctx.center_chunk();
                                     for feat in self.generator.features() {
+                                        let mut rng =
+                                            chunk_rng(ctx.seed, center, feature_salt(feat.id()));
                                         feat.generate(
                                             &mut chunk_ctx,
                                             ctx.seed,
@@ -857,12 +860,11 @@ where
                                         dirty_rect,
                                     }
                                 })
-                            })
                         })
-                        .into_iter()
-                        .collect::<Option<Vec<_>>>()
-                        .map(|v| v.try_into().unwrap())
-                    else {
+                    })
+                    .into_iter()
+                    .collect::<Option<Vec<_>>>()
+                    .map(|v| v.try_into().unwrap()) else {
                         continue;
                     };
 
@@ -977,6 +979,7 @@ where
 
     fn tick_tile_entities(&mut self, ctx: &mut ChunkTickContext) {
         profiling::scope!("tick_tile_entities");
+        let mut explosions = vec![];
         self.manager.query_each(|mut q| {
             q.for_each_with(
                 |ch| ch.sided_tile_entities_removable(),
@@ -986,10 +989,15 @@ where
                         registries: ctx.registries,
                         file_helper: ctx.file_helper,
                         chunks,
+                        explosions: &mut explosions,
                     });
                 },
             );
         });
+        ctx.world
+            .write_resource::<PendingExplosions>()
+            .0
+            .append(&mut explosions);
     }
 }
 
@@ -205,6 +205,7 @@ bitflags! {
         const ENTITY    = 0b0000_0000_0000_0001;
         const WORLD     = 0b0000_0000_0000_0010;
         const RIGIDBODY = 0b0000_0000_0000_0100;
+        const ROPE      = 0b0000_0000_0000_1000;
         const PLAYER    = Self::ENTITY.bits;
     }
 }
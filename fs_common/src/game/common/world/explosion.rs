@@ -0,0 +1,195 @@
+use rapier2d::na::Vector2;
+use specs::{Join, ReadStorage, WriteStorage};
+
+use super::{
+    entity::PhysicsEntity,
+    material::MaterialInstance,
+    particle::Particle,
+    physics::{Physics, PHYSICS_SCALE},
+    Position, Velocity,
+};
+
+/// Speed of particles in pixels per tick, for each point of strength left after breaking the pixel
+const PARTICLE_SPEED: f64 = 0.5;
+/// Change in rigidbody velocity in physics units per second, for each point of strength
+const BODY_PUSH: f32 = 2.0;
+/// Change in entity velocity in pixels per tick, for each point of strength
+const ENTITY_PUSH: f64 = 0.5;
+/// Largest radius in pixels, explosions check every pixel in range
+pub const MAX_RADIUS: f32 = 250.0;
+
+/// An explosion centered on a world pixel position.
+///
+/// Its strength falls off linearly from `power` at the center to 0 at `radius`.
+/// See [`World::explode`](super::World::explode).
+#[derive(Debug, Clone, Copy)]
+pub struct Explosion {
+    pub x: f64,
+    pub y: f64,
+    pub radius: f32,
+    pub power: f32,
+}
+
+/// Explosions waiting to be applied by [`World::tick`](super::World::tick),
+///   for code that can't access the [`World`](super::World) directly, like tile entities
+#[derive(Debug, Default)]
+pub struct PendingExplosions(pub Vec<Explosion>);
+
+impl Explosion {
+    /// `radius` is clamped to [`MAX_RADIUS`]
+    pub fn new(x: f64, y: f64, radius: f32, power: f32) -> Self {
+        let radius = if radius.is_nan() {
+            0.0
+        } else {
+            radius.clamp(0.0, MAX_RADIUS)
+        };
+        Self { x, y, radius, power }
+    }
+
+    /// Strength of the explosion at a world position, 0 outside of `radius`
+    pub fn strength_at(&self, x: f64, y: f64) -> f32 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+
+        let dist = (x - self.x).hypot(y - self.y) as f32;
+        (self.power * (1.0 - dist / self.radius)).max(0.0)
+    }
+
+    /// Unit vector pointing away from the center, or up at the center itself
+    fn direction(&self, x: f64, y: f64) -> (f64, f64) {
+        let (dx, dy) = (x - self.x, y - self.y);
+        let len = dx.hypot(dy);
+        if len < f64::EPSILON {
+            (0.0, -1.0)
+        } else {
+            (dx / len, dy / len)
+        }
+    }
+
+    /// A particle flying outwards from a pixel that was blown away, `force` is the strength left over after breaking it
+    pub fn particle(
+        &self,
+        material: MaterialInstance,
+        x: f64,
+        y: f64,
+        force: f32,
+        rng: &fastrand::Rng,
+    ) -> Particle {
+        let (dx, dy) = self.direction(x, y);
+        let speed = f64::from(force) * PARTICLE_SPEED * (0.5 + rng.f64());
+        Particle::new(
+            material,
+            Position { x, y },
+            Velocity {
+                x: dx * speed + (rng.f64() - 0.5),
+                y: dy * speed + (rng.f64() - 0.5),
+            },
+        )
+    }
+
+    /// Pushes every dynamic rigidbody in range away from the center
+    pub fn push_bodies(&self, physics: &mut Physics) {
+        for (_, body) in physics.bodies.iter_mut() {
+            if !body.is_dynamic() {
+                continue;
+            }
+
+            let com = body.center_of_mass() * PHYSICS_SCALE;
+            let (x, y) = (f64::from(com.x), f64::from(com.y));
+            let strength = self.strength_at(x, y);
+            if strength <= 0.0 {
+                continue;
+            }
+
+            let (dx, dy) = self.direction(x, y);
+            let impulse = Vector2::new(dx as f32, dy as f32) * strength * BODY_PUSH * body.mass();
+            body.apply_impulse(impulse, true);
+        }
+    }
+
+    /// Pushes every [`PhysicsEntity`] in range away from the center
+    pub fn push_entities(&self, ecs: &specs::World) {
+        let (physics_entities, positions, mut velocities) = ecs.system_data::<(
+            ReadStorage<PhysicsEntity>,
+            ReadStorage<Position>,
+            WriteStorage<Velocity>,
+        )>();
+
+        for (_, pos, vel) in (&physics_entities, &positions, &mut velocities).join() {
+            let strength = self.strength_at(pos.x, pos.y);
+            if strength <= 0.0 {
+                continue;
+            }
+
+            let (dx, dy) = self.direction(pos.x, pos.y);
+            vel.x += dx * f64::from(strength) * ENTITY_PUSH;
+            vel.y += dy * f64::from(strength) * ENTITY_PUSH;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use specs::WorldExt;
+
+    use crate::game::common::world::{
+        chunk_access::FSChunkAccess,
+        material::PhysicsType,
+        particle::ParticleSystem,
+        test_chunk::{self, TestChunk},
+        World,
+    };
+
+    #[test]
+    fn strength_falls_off() {
+        let explosion = Explosion::new(10.0, 10.0, 20.0, 8.0);
+
+        assert!((explosion.strength_at(10.0, 10.0) - 8.0).abs() < f32::EPSILON);
+        assert!((explosion.strength_at(20.0, 10.0) - 4.0).abs() < f32::EPSILON);
+        assert!(explosion.strength_at(30.0, 10.0).abs() < f32::EPSILON);
+        assert!(explosion.strength_at(40.0, 40.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn radius_is_clamped() {
+        for radius in [f32::INFINITY, 1e12, f32::NAN, -5.0] {
+            let explosion = Explosion::new(0.0, 0.0, radius, 10.0);
+            assert!((0.0..=MAX_RADIUS).contains(&explosion.radius));
+        }
+    }
+
+    #[test]
+    fn explode_breaks_terrain() {
+        let registries = test_chunk::registries();
+        let mut world = test_chunk::world([(0, 0)]);
+        for y in 0..100 {
+            for x in 0..100 {
+                world
+                    .chunk_handler
+                    .set_pixel(x, y, test_chunk::stone())
+                    .unwrap();
+            }
+        }
+
+        world.explode(50.0, 50.0, 10.0, 100.0, &registries);
+
+        let physics_at =
+            |world: &World<TestChunk>, x, y| world.chunk_handler.pixel(x, y).unwrap().physics;
+        assert_eq!(physics_at(&world, 50, 50), PhysicsType::Air);
+        assert_eq!(physics_at(&world, 55, 50), PhysicsType::Air);
+        assert_eq!(physics_at(&world, 62, 50), PhysicsType::Solid);
+        assert!(!world
+            .ecs
+            .read_resource::<ParticleSystem>()
+            .active
+            .is_empty());
+
+        // huge or broken input is clamped instead of looping over every pixel in range
+        world.explode(50.0, 50.0, f32::INFINITY, 100.0, &registries);
+        world.explode(f64::NAN, 50.0, 10.0, 100.0, &registries);
+        assert_eq!(physics_at(&world, 0, 0), PhysicsType::Air);
+    }
+}
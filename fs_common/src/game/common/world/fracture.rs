@@ -0,0 +1,319 @@
+use std::f32::consts::TAU;
+
+use ahash::AHashMap;
+use rapier2d::{
+    na::{Isometry2, Point2, Vector2},
+    prelude::{Collider, ColliderHandle, RigidBody, RigidBodyHandle},
+};
+
+use crate::game::common::Registries;
+
+use super::{
+    material::{MaterialInstance, PhysicsType},
+    particle::Particle,
+    physics::{Physics, PHYSICS_SCALE},
+    rigidbody::FSRigidBody,
+    Position, Velocity,
+};
+
+/// Contact force per unit of mass below which rigidbodies don't report impacts at all.
+/// Should be lower than every material's [`fracture_strength`](super::material::Material::fracture_strength).
+pub const MIN_IMPACT: f32 = 100.0;
+
+/// Breaks rigidbodies that hit something harder than their materials' [`fracture_strength`](super::material::Material::fracture_strength).
+///
+/// The body's pixels are split along cracks spreading out from the impact point.
+/// Each fragment becomes a new body moving along with the old one, or particles if it is small enough.
+#[derive(Debug, Clone)]
+pub struct RigidBodyFracture {
+    /// Fragments with at most this many pixels become particles
+    pub particle_size: usize,
+    /// Number of fragments when an impact is just strong enough, stronger impacts make more
+    pub min_fragments: usize,
+    pub max_fragments: usize,
+}
+
+impl RigidBodyFracture {
+    pub fn new() -> Self {
+        Self {
+            particle_size: 6,
+            min_fragments: 2,
+            max_fragments: 8,
+        }
+    }
+
+    #[profiling::function]
+    pub fn update(
+        &self,
+        rigidbodies: &mut Vec<FSRigidBody>,
+        physics: &mut Physics,
+        particles: &mut Vec<Particle>,
+        registries: &Registries,
+    ) {
+        // strongest impact on each body since the last update
+        let mut impacts: AHashMap<RigidBodyHandle, (f32, Option<Point2<f32>>)> = AHashMap::new();
+        while let Ok(event) = physics.contact_force_events.try_recv() {
+            for collider in [event.collider1, event.collider2] {
+                let Some(handle) = physics.colliders.get(collider).and_then(Collider::parent)
+                else {
+                    continue;
+                };
+                let Some(body) = physics.bodies.get(handle) else {
+                    continue;
+                };
+                if !body.is_dynamic() || body.mass() <= 0.0 {
+                    continue;
+                }
+
+                let impact = event.total_force_magnitude / body.mass();
+                if impacts.get(&handle).is_none_or(|(i, _)| impact > *i) {
+                    let point = contact_point(physics, event.collider1, event.collider2);
+                    impacts.insert(handle, (impact, point));
+                }
+            }
+        }
+
+        if impacts.is_empty() {
+            return;
+        }
+
+        let rng = fastrand::Rng::new();
+        let mut new_bodies = vec![];
+        rigidbodies.retain_mut(|rb| {
+            let Some(&(impact, point)) = rb.body.and_then(|b| impacts.get(&b)) else {
+                return true;
+            };
+
+            let strength = rb
+                .pixels
+                .iter()
+                .filter(|p| p.physics != PhysicsType::Air)
+                .map(|p| {
+                    registries
+                        .materials
+                        .get(&p.material_id)
+                        .map_or(f32::INFINITY, |m| m.fracture_strength)
+                })
+                .fold(f32::INFINITY, f32::min);
+            let size = rb
+                .pixels
+                .iter()
+                .filter(|p| p.physics != PhysicsType::Air)
+                .count();
+            if impact < strength || size <= self.particle_size * self.min_fragments {
+                return true;
+            }
+
+            self.fracture(
+                rb,
+                impact / strength,
+                point,
+                physics,
+                &rng,
+                &mut new_bodies,
+                particles,
+                registries,
+            );
+            false
+        });
+        rigidbodies.append(&mut new_bodies);
+    }
+
+    /// Removes `rb`'s body and adds its fragments to `bodies` and `particles`.
+    /// `severity` is how many times stronger the impact was than the body's strength.
+    #[allow(clippy::too_many_arguments)]
+    fn fracture(
+        &self,
+        rb: &mut FSRigidBody,
+        severity: f32,
+        point: Option<Point2<f32>>,
+        physics: &mut Physics,
+        rng: &fastrand::Rng,
+        bodies: &mut Vec<FSRigidBody>,
+        particles: &mut Vec<Particle>,
+        registries: &Registries,
+    ) {
+        let Some(old) = physics.remove_rigidbody(rb.body.take().unwrap()) else {
+            return;
+        };
+        let iso = *old.position();
+        let w = usize::from(rb.width);
+        let h = usize::from(rb.height);
+
+        // in pixels relative to the body
+        let impact = point.map_or_else(
+            || Point2::new(w as f32 / 2.0, h as f32 / 2.0),
+            |p| iso.inverse_transform_point(&p) * PHYSICS_SCALE,
+        );
+
+        // crack centers crowd around the impact, so the pieces there are smaller
+        let count = ((severity.sqrt() * self.min_fragments as f32) as usize)
+            .clamp(self.min_fragments, self.max_fragments);
+        let spread = w.max(h) as f32;
+        let centers: Vec<Point2<f32>> = (0..count)
+            .map(|_| {
+                let angle = rng.f32() * TAU;
+                let dist = rng.f32().powi(2) * spread;
+                impact + Vector2::new(angle.cos(), angle.sin()) * dist
+            })
+            .collect();
+
+        let mut fragments = vec![vec![MaterialInstance::air(); w * h]; count];
+        for (i, px) in rb.pixels.iter().enumerate() {
+            if px.physics == PhysicsType::Air {
+                continue;
+            }
+
+            let p = Point2::new((i % w) as f32, (i / w) as f32);
+            let nearest = centers
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    (*a - p).norm_squared().total_cmp(&(*b - p).norm_squared())
+                })
+                .unwrap()
+                .0;
+            fragments[nearest][i] = px.clone();
+        }
+
+        for pixels in fragments {
+            let filled: Vec<usize> = (0..pixels.len())
+                .filter(|&i| pixels[i].physics != PhysicsType::Air)
+                .collect();
+            if filled.is_empty() {
+                continue;
+            }
+
+            if filled.len() > self.particle_size {
+                let made = FSRigidBody::make_bodies(
+                    &pixels,
+                    rb.width,
+                    rb.height,
+                    physics,
+                    (iso.translation.x, iso.translation.y),
+                    registries,
+                );
+                if let Ok(mut made) = made {
+                    if !made.is_empty() {
+                        let center = filled
+                            .iter()
+                            .map(|&i| Vector2::new((i % w) as f32, (i / w) as f32))
+                            .sum::<Vector2<f32>>()
+                            / filled.len() as f32
+                            / PHYSICS_SCALE;
+                        let vel = old.velocity_at_point(&(iso * Point2::from(center)));
+
+                        for body in &mut made {
+                            body.settle = rb.settle;
+                            let body = body.get_body_mut(physics).unwrap();
+                            body.set_position(iso, true);
+                            body.set_linvel(vel, true);
+                            body.set_angvel(old.angvel(), true);
+                        }
+                        bodies.append(&mut made);
+                        continue;
+                    }
+                }
+            }
+
+            // too small for a body, or a body couldn't be made from it
+            for i in filled {
+                particles.push(pixel_particle(
+                    pixels[i].clone(),
+                    (i % w, i / w),
+                    &iso,
+                    &old,
+                ));
+            }
+        }
+    }
+}
+
+impl Default for RigidBodyFracture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The world position of a contact between two colliders, if they're still touching
+fn contact_point(
+    physics: &Physics,
+    collider1: ColliderHandle,
+    collider2: ColliderHandle,
+) -> Option<Point2<f32>> {
+    physics
+        .narrow_phase
+        .contact_pair(collider1, collider2)?
+        .manifolds
+        .iter()
+        .flat_map(|m| &m.data.solver_contacts)
+        .next()
+        .map(|c| c.point)
+}
+
+fn pixel_particle(
+    material: MaterialInstance,
+    (x, y): (usize, usize),
+    iso: &Isometry2<f32>,
+    body: &RigidBody,
+) -> Particle {
+    let world = iso * Point2::new(x as f32 / PHYSICS_SCALE, y as f32 / PHYSICS_SCALE);
+    let vel = body.velocity_at_point(&world);
+    Particle::new(
+        material,
+        Position {
+            x: f64::from(world.x * PHYSICS_SCALE),
+            y: f64::from(world.y * PHYSICS_SCALE),
+        },
+        Velocity {
+            x: f64::from(vel.x * 0.1),
+            y: f64::from(vel.y * 0.1),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::test_chunk;
+
+    fn solid_count(pixels: &[MaterialInstance]) -> usize {
+        pixels
+            .iter()
+            .filter(|p| p.physics != PhysicsType::Air)
+            .count()
+    }
+
+    #[test]
+    fn fracture_keeps_every_pixel() {
+        let registries = test_chunk::registries();
+        let mut physics = Physics::new();
+        let fracture = RigidBodyFracture::new();
+
+        for severity in [1.0, 4.0, 100.0] {
+            let mut rb = test_chunk::stone_body(20, 20, (1.0, 2.0), &mut physics);
+            let pixels = rb.pixels.clone();
+            let old = rb.body.unwrap();
+
+            let mut bodies = vec![];
+            let mut particles = vec![];
+            fracture.fracture(
+                &mut rb,
+                severity,
+                None,
+                &mut physics,
+                &fastrand::Rng::with_seed(7),
+                &mut bodies,
+                &mut particles,
+                &registries,
+            );
+
+            assert!(physics.bodies.get(old).is_none());
+            assert!(bodies.len() + particles.len() >= fracture.min_fragments);
+            let fragment_pixels =
+                bodies.iter().map(|b| solid_count(&b.pixels)).sum::<usize>() + particles.len();
+            assert_eq!(fragment_pixels, solid_count(&pixels));
+        }
+    }
+}
@@ -1,12 +1,15 @@
 pub mod placement;
 
+use std::ops::Range;
+
+use bracket_noise::prelude::{FastNoise, NoiseType};
 use simdnoise::NoiseBuilder;
 
 use crate::game::common::{
     registry::{Registry, RegistryID},
     world::{
         material::{
-            placer::{self, MaterialPlacer, MaterialPlacerSampler},
+            placer::{self, pixel_random, MaterialPlacer, MaterialPlacerSampler},
             MaterialInstance,
         },
         pixel_to_chunk_pos, CHUNK_SIZE,
@@ -48,14 +51,105 @@ impl From<MaterialInstance> for MaterialPlacerIDOrMaterialInstance {
 
 pub struct Biome {
     pub placement: BiomePlacementParameter,
+    /// The world y values this biome can be placed at
+    pub depth: Range<i64>,
     pub base_placer: MaterialPlacerIDOrMaterialInstance,
 }
 
+/// [`Biome::depth`] for biomes that can be placed at any depth
+pub const ANY_DEPTH: Range<i64> = i64::MIN..i64::MAX;
+
+/// How much a pixel belongs to the biomes around it, see [`BiomeRegistry::biome_weights_block`]
+#[derive(Clone, Copy)]
+pub struct BiomeWeights<'a> {
+    /// The nearest biome and its weight, always at least 0.5
+    pub primary: ((&'a RegistryID<Biome>, &'a Biome), f32),
+    /// The nearest different biome and its weight, if the pixel is in the transition between them
+    pub secondary: Option<((&'a RegistryID<Biome>, &'a Biome), f32)>,
+}
+
+impl BiomeWeights<'_> {
+    /// Total weight of the biomes that match `predicate`, from 0 to 1
+    pub fn weight_where(&self, predicate: impl Fn(&RegistryID<Biome>, &Biome) -> bool) -> f32 {
+        [Some(self.primary), self.secondary]
+            .into_iter()
+            .flatten()
+            .filter(|((id, biome), _)| predicate(id, biome))
+            .map(|(_, w)| w)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BiomeBlendMode {
+    /// Each pixel picks a biome randomly, based on the weights
+    Dither,
+    /// The border follows noise with this frequency, so the biomes mix in patches
+    Noise { frequency: f32 },
+}
+
+/// How base placers mix between neighboring biomes
+#[derive(Debug, Clone, Copy)]
+pub struct BiomeTransition {
+    /// Width of the transition in pixels, 0 for hard borders
+    pub width: f32,
+    pub mode: BiomeBlendMode,
+}
+
+impl BiomeTransition {
+    pub const NONE: Self = Self { width: 0.0, mode: BiomeBlendMode::Dither };
+}
+
+/// Picks a single biome per pixel from [`BiomeWeights`] according to a [`BiomeTransition`]
+pub struct BiomeBlender {
+    mode: BiomeBlendMode,
+    seed: i32,
+    noise: FastNoise,
+}
+
+impl BiomeBlender {
+    pub fn new(transition: BiomeTransition, seed: i32) -> Self {
+        let mut noise = FastNoise::seeded(seed as u64 + 20);
+        noise.set_noise_type(NoiseType::Simplex);
+        if let BiomeBlendMode::Noise { frequency } = transition.mode {
+            noise.set_frequency(frequency);
+        }
+
+        Self { mode: transition.mode, seed, noise }
+    }
+
+    pub fn pick<'a>(
+        &self,
+        weights: &BiomeWeights<'a>,
+        x: i64,
+        y: i64,
+    ) -> (&'a RegistryID<Biome>, &'a Biome) {
+        let Some((secondary, weight)) = weights.secondary else {
+            return weights.primary.0;
+        };
+
+        let v = match self.mode {
+            BiomeBlendMode::Dither => pixel_random(x, y, self.seed as u64),
+            BiomeBlendMode::Noise { .. } => self.noise.get_noise(x as f32, y as f32) * 0.5 + 0.5,
+        };
+
+        if v < weight {
+            secondary
+        } else {
+            weights.primary.0
+        }
+    }
+}
+
 pub type BiomeRegistry = Registry<Biome>;
 
 impl BiomeRegistry {
-    pub fn nearest(&self, test: BiomePlacementParameter) -> (&RegistryID<Biome>, &Biome) {
+    /// The biome closest to `test` out of the ones that can be placed at `y`.
+    /// If none can be placed at `y`, picks from every biome.
+    pub fn nearest(&self, test: BiomePlacementParameter, y: i64) -> (&RegistryID<Biome>, &Biome) {
+        let any_at_depth = self.into_iter().any(|(_, b)| b.depth.contains(&y));
         self.into_iter()
+            .filter(|(_, b)| !any_at_depth || b.depth.contains(&y))
             .min_by(|(_, a), (_, b)| {
                 test.dist_sq(&a.placement)
                     .partial_cmp(&test.dist_sq(&b.placement))
@@ -77,6 +171,45 @@ impl BiomeRegistry {
         y: i64,
         seed: i32,
     ) -> Vec<(&RegistryID<Biome>, &Biome)> {
+        self.map_block::<W, H, _>(x, y, seed, |points| {
+            points.iter().min_by_key(|(dist_sq, _)| *dist_sq).unwrap().1
+        })
+    }
+
+    pub fn biome_weights_at(
+        &self,
+        x: i64,
+        y: i64,
+        seed: i32,
+        transition_width: f32,
+    ) -> BiomeWeights<'_> {
+        self.biome_weights_block::<1, 1>(x, y, seed, transition_width)
+            .pop()
+            .unwrap()
+    }
+
+    /// Like [`biome_block`](Self::biome_block), but includes how much each pixel belongs to
+    ///   the neighboring biome if it's within `transition_width` pixels of a border.
+    ///
+    /// The returned `Vec` will always have size `W * H`.
+    pub fn biome_weights_block<const W: u16, const H: u16>(
+        &self,
+        x: i64,
+        y: i64,
+        seed: i32,
+        transition_width: f32,
+    ) -> Vec<BiomeWeights<'_>> {
+        self.map_block::<W, H, _>(x, y, seed, |points| biome_weights(points, transition_width))
+    }
+
+    /// Calls `f` for each pixel in the block with every nearby biome point's (squared distance, biome).
+    fn map_block<'a, const W: u16, const H: u16, T>(
+        &'a self,
+        x: i64,
+        y: i64,
+        seed: i32,
+        f: impl Fn(&[(i64, (&'a RegistryID<Biome>, &'a Biome))]) -> T,
+    ) -> Vec<T> {
         let (chunk_x, chunk_y) = pixel_to_chunk_pos(x, y);
 
         let (center_biome_point_x, center_biome_point_y) = nearest_biome_point_to(
@@ -108,7 +241,7 @@ impl BiomeRegistry {
 
                 (
                     (disp_x, disp_y),
-                    self.nearest(biome_params_at(*x, *y, seed)),
+                    self.nearest(biome_params_at(*x, *y, seed), *y),
                 )
             })
             .collect::<Vec<_>>();
@@ -137,41 +270,50 @@ impl BiomeRegistry {
             .generate()
             .0;
 
+        let mut points = Vec::with_capacity(vals.len());
         (0..(W * H) as usize)
             .map(|i| {
                 let rel_x = i % (W as usize);
                 let rel_y = i / (W as usize);
 
-                let biome = vals
-                    .iter()
-                    .min_by(|((x1, y1), _v1), ((x2, y2), _v2)| unsafe {
-                        let ox1 = ofs_x_1.get_unchecked(i) * 1000.0;
-                        let ox2 = ofs_x_2.get_unchecked(i) * 500.0;
-                        let oy1 = ofs_y_1.get_unchecked(i) * 1000.0;
-                        let oy2 = ofs_y_2.get_unchecked(i) * 500.0;
-
-                        let ox = rel_x as i64 + x + (ox1 + ox2) as i64;
-                        let oy = rel_y as i64 + y + (oy1 + oy2) as i64;
-
-                        let dx1 = x1 - ox;
-                        let dy1 = y1 - oy;
-                        let d1 = dx1 * dx1 + dy1 * dy1;
+                let ox = rel_x as i64 + x + (ofs_x_1[i] * 1000.0 + ofs_x_2[i] * 500.0) as i64;
+                let oy = rel_y as i64 + y + (ofs_y_1[i] * 1000.0 + ofs_y_2[i] * 500.0) as i64;
 
-                        let dx2 = x2 - ox;
-                        let dy2 = y2 - oy;
-                        let d2 = dx2 * dx2 + dy2 * dy2;
+                points.clear();
+                points.extend(vals.iter().map(|((px, py), biome)| {
+                    let dx = px - ox;
+                    let dy = py - oy;
+                    (dx * dx + dy * dy, *biome)
+                }));
 
-                        d1.cmp(&d2)
-                    })
-                    .unwrap()
-                    .1;
-
-                biome
+                f(&points)
             })
             .collect()
     }
 }
 
+/// Weights for a pixel from every nearby biome point's (squared distance, biome), see [`BiomeWeights`]
+fn biome_weights<'a>(
+    points: &[(i64, (&'a RegistryID<Biome>, &'a Biome))],
+    transition_width: f32,
+) -> BiomeWeights<'a> {
+    let &(nearest_dist_sq, nearest) = points.iter().min_by_key(|(dist_sq, _)| *dist_sq).unwrap();
+    let secondary = points
+        .iter()
+        .filter(|(_, (id, _))| *id != nearest.0)
+        .min_by_key(|(dist_sq, _)| *dist_sq)
+        .and_then(|&(dist_sq, biome)| {
+            // roughly twice the distance to the border
+            let gap = (dist_sq as f32).sqrt() - (nearest_dist_sq as f32).sqrt();
+            (gap < transition_width).then(|| (biome, 0.5 - 0.5 * gap / transition_width))
+        });
+
+    BiomeWeights {
+        primary: (nearest, 1.0 - secondary.map_or(0.0, |(_, w)| w)),
+        secondary,
+    }
+}
+
 pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
     let mut registry = BiomeRegistry::new();
 
@@ -179,6 +321,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "main",
         Biome {
             placement: [0.5, 0.5, 0.5].into(),
+            depth: ANY_DEPTH,
             base_placer: placer::SMOOTH_STONE.clone().into(),
         },
     );
@@ -187,6 +330,8 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "dirt",
         Biome {
             placement: [0.0, 0.0, 0.0].into(),
+            // only near the surface
+            depth: i64::MIN..600,
             base_placer: placer::SMOOTH_DIRT.clone().into(),
         },
     );
@@ -195,6 +340,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "red",
         Biome {
             placement: [0.75, 0.0, 0.0].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_red").into(),
         },
     );
@@ -202,6 +348,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "green",
         Biome {
             placement: [0.0, 0.75, 0.0].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_green").into(),
         },
     );
@@ -209,6 +356,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "blue",
         Biome {
             placement: [0.0, 0.0, 0.75].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_blue").into(),
         },
     );
@@ -216,6 +364,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "cyan",
         Biome {
             placement: [0.25, 1.0, 1.0].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_cyan").into(),
         },
     );
@@ -223,6 +372,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "magenta",
         Biome {
             placement: [1.0, 0.25, 1.0].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_magenta").into(),
         },
     );
@@ -230,6 +380,7 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "yellow",
         Biome {
             placement: [1.0, 1.0, 0.25].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_yellow").into(),
         },
     );
@@ -237,9 +388,140 @@ pub fn init_biomes(_file_helper: &FileHelper) -> BiomeRegistry {
         "white",
         Biome {
             placement: [1.0, 1.0, 1.0].into(),
+            depth: ANY_DEPTH,
             base_placer: RegistryID::<MaterialPlacer>::from("test_white").into(),
         },
     );
 
     registry
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::test_chunk;
+
+    fn biome() -> Biome {
+        Biome {
+            placement: [0.5, 0.5, 0.5].into(),
+            depth: ANY_DEPTH,
+            base_placer: test_chunk::stone().into(),
+        }
+    }
+
+    #[test]
+    fn weights_across_border() {
+        let (a, b) = (RegistryID::from("a"), RegistryID::from("b"));
+        let (biome_a, biome_b) = (biome(), biome());
+        let width = 20.0;
+
+        // biome points at x = 0 and x = 100, border at x = 50
+        let weights: Vec<f32> = (0..=100_i64)
+            .map(|x| {
+                let points = [
+                    (x * x, (&a, &biome_a)),
+                    ((100 - x) * (100 - x), (&b, &biome_b)),
+                ];
+                let weights = biome_weights(&points, width);
+
+                let total = weights.weight_where(|_, _| true);
+                assert!((total - 1.0).abs() < 1e-6, "weights at {x} sum to {total}");
+                assert!(weights.primary.1 >= 0.5);
+
+                let weight_a = weights.weight_where(|id, _| *id == a);
+                let weight_b = weights.weight_where(|id, _| *id == b);
+                assert!((weight_a + weight_b - 1.0).abs() < 1e-6);
+                weight_a
+            })
+            .collect();
+
+        // `gap` is twice the distance to the border, so the transition is `width / 2` either side
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+        assert!(close(weights[0], 1.0));
+        assert!(close(weights[40], 1.0));
+        assert!(close(weights[50], 0.5));
+        assert!(close(weights[60], 0.0));
+        assert!(close(weights[100], 0.0));
+        assert!(weights.windows(2).all(|w| w[0] >= w[1]), "{weights:?}");
+        assert!(weights.windows(2).any(|w| w[0] > w[1] && w[0] < 1.0));
+    }
+
+    #[test]
+    fn registry_weights() {
+        let biomes = test_chunk::asset_registries().biomes;
+        let weights = biomes.biome_weights_block::<CHUNK_SIZE, CHUNK_SIZE>(0, 200, 5, 48.0);
+
+        for w in &weights {
+            assert!((w.weight_where(|_, _| true) - 1.0).abs() < 1e-6);
+        }
+        assert!(weights.iter().any(|w| w.secondary.is_some()));
+
+        // same seed, same weights
+        let summary = |weights: &[BiomeWeights]| {
+            weights
+                .iter()
+                .map(|w| {
+                    (
+                        w.primary.0 .0.clone(),
+                        w.primary.1,
+                        w.secondary.map(|s| s.1),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(&weights),
+            summary(&biomes.biome_weights_block::<CHUNK_SIZE, CHUNK_SIZE>(0, 200, 5, 48.0))
+        );
+        // the primary biome is the one `biome_block` picks
+        let block = biomes.biome_block::<CHUNK_SIZE, CHUNK_SIZE>(0, 200, 5);
+        assert!(weights
+            .iter()
+            .zip(&block)
+            .all(|(w, (id, _))| w.primary.0 .0 == *id));
+    }
+
+    #[test]
+    fn blender_is_deterministic() {
+        let (a, b) = (RegistryID::from("a"), RegistryID::from("b"));
+        let (biome_a, biome_b) = (biome(), biome());
+        let weights = BiomeWeights {
+            primary: ((&a, &biome_a), 0.7),
+            secondary: Some(((&b, &biome_b), 0.3)),
+        };
+
+        for mode in [
+            BiomeBlendMode::Dither,
+            BiomeBlendMode::Noise { frequency: 0.05 },
+        ] {
+            let transition = BiomeTransition { width: 48.0, mode };
+            let picks = |seed| {
+                let blender = BiomeBlender::new(transition, seed);
+                (0..100_i64)
+                    .flat_map(|y| (0..100_i64).map(move |x| (x, y)))
+                    .map(|(x, y)| blender.pick(&weights, x, y).0 == &b)
+                    .collect::<Vec<_>>()
+            };
+
+            let first = picks(1);
+            assert_eq!(first, picks(1), "{mode:?}");
+            assert_ne!(first, picks(2), "{mode:?}");
+
+            // roughly follows the weights
+            let secondary = first.iter().filter(|&&p| p).count() as f32 / first.len() as f32;
+            assert!(
+                (0.15..0.45).contains(&secondary),
+                "{mode:?} picked b {secondary}"
+            );
+        }
+
+        // no secondary biome, always the primary
+        let blender = BiomeBlender::new(
+            BiomeTransition { width: 48.0, mode: BiomeBlendMode::Dither },
+            1,
+        );
+        let weights = BiomeWeights { primary: ((&a, &biome_a), 1.0), secondary: None };
+        assert!((0..100).all(|x| blender.pick(&weights, x, 0).0 == &a));
+    }
+}
@@ -16,6 +16,7 @@ use rand::Rng;
 use crate::game::common::world::CHUNK_SIZE;
 
 use super::{
+    biome::{BiomeBlendMode, BiomeBlender, BiomeTransition},
     feature::{
         features::{
             blob::Blob, configured_structure::ConfiguredStructureFeature,
@@ -39,6 +40,7 @@ use super::{
 pub struct BiomeTestGenerator<C: Chunk> {
     populators: PopulatorList<C>,
     features: Vec<PlacedFeature<C>>,
+    pub biome_transition: BiomeTransition,
 }
 
 impl<C: Chunk + 'static> BiomeTestGenerator<C> {
@@ -46,96 +48,108 @@ impl<C: Chunk + 'static> BiomeTestGenerator<C> {
     pub fn new() -> Self {
         let mut populators = PopulatorList::new();
 
-        populators.add(CavePopulator);
-        populators.add(SpawnPopulator);
-
-        populators.add(PlaceAbovePopulator {
-            add_surface_height: 1,
-            replace_surface_depth: 2,
-            searching_for: |m| m.material_id == *material::SMOOTH_DIRT,
-            replace: |_mat, x, y, registries| {
-                Some(
-                    registries
-                        .material_placers
-                        .get(&*placer::TEST_GRASS)
-                        .unwrap()
-                        .pixel(x, y),
-                )
-            },
-        });
+        populators.add("cave", CavePopulator);
+        populators.add("spawn", SpawnPopulator);
 
-        populators.add(StalactitePopulator {
-            searching_for: |m| m.material_id == *material::SMOOTH_STONE,
-            replace: |mat, x, y, registries| {
-                if mat.material_id == *material::AIR {
+        populators.add(
+            "grass",
+            PlaceAbovePopulator {
+                add_surface_height: 1,
+                replace_surface_depth: 2,
+                searching_for: |m| m.material_id == *material::SMOOTH_DIRT,
+                replace: |_mat, x, y, seed, registries| {
                     Some(
                         registries
                             .material_placers
-                            .get(&*placer::SMOOTH_STONE)
+                            .get(&*placer::TEST_GRASS)
                             .unwrap()
-                            .pixel(x, y),
+                            .pixel(x, y, seed),
                     )
-                } else {
-                    None
-                }
+                },
             },
-        });
+        );
 
-        populators.add(NearbyReplacePopulator {
-            radius: 10,
-            searching_for: |m| m.material_id == *material::AIR,
-            replace: |mat, x, y, registries| {
-                if mat.material_id == *material::SMOOTH_STONE {
-                    Some(
-                        registries
-                            .material_placers
-                            .get(&*placer::FADED_COBBLE_STONE)
-                            .unwrap()
-                            .pixel(x, y),
-                    )
-                } else if mat.material_id == *material::SMOOTH_DIRT {
-                    Some(
-                        registries
-                            .material_placers
-                            .get(&*placer::FADED_COBBLE_DIRT)
-                            .unwrap()
-                            .pixel(x, y),
-                    )
-                } else {
-                    None
-                }
+        populators.add(
+            "stalactite",
+            StalactitePopulator {
+                searching_for: |m| m.material_id == *material::SMOOTH_STONE,
+                replace: |mat, x, y, seed, registries| {
+                    if mat.material_id == *material::AIR {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::SMOOTH_STONE)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else {
+                        None
+                    }
+                },
             },
-        });
-
-        populators.add(NearbyReplacePopulator {
-            radius: 6,
-            searching_for: |m| m.material_id == *material::AIR,
-            replace: |mat, x, y, registries| {
-                if mat.material_id == *material::SMOOTH_STONE
-                    || mat.material_id == *material::FADED_COBBLE_STONE
-                {
-                    Some(
-                        registries
-                            .material_placers
-                            .get(&*placer::COBBLE_STONE)
-                            .unwrap()
-                            .pixel(x, y),
-                    )
-                } else if mat.material_id == *material::SMOOTH_DIRT
-                    || mat.material_id == *material::FADED_COBBLE_DIRT
-                {
-                    Some(
-                        registries
-                            .material_placers
-                            .get(&*placer::COBBLE_DIRT)
-                            .unwrap()
-                            .pixel(x, y),
-                    )
-                } else {
-                    None
-                }
+        );
+
+        populators.add(
+            "faded_cobble_edges",
+            NearbyReplacePopulator {
+                radius: 10,
+                searching_for: |m| m.material_id == *material::AIR,
+                replace: |mat, x, y, seed, registries| {
+                    if mat.material_id == *material::SMOOTH_STONE {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::FADED_COBBLE_STONE)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else if mat.material_id == *material::SMOOTH_DIRT {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::FADED_COBBLE_DIRT)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else {
+                        None
+                    }
+                },
+            },
+        );
+
+        populators.add(
+            "cobble_edges",
+            NearbyReplacePopulator {
+                radius: 6,
+                searching_for: |m| m.material_id == *material::AIR,
+                replace: |mat, x, y, seed, registries| {
+                    if mat.material_id == *material::SMOOTH_STONE
+                        || mat.material_id == *material::FADED_COBBLE_STONE
+                    {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::COBBLE_STONE)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else if mat.material_id == *material::SMOOTH_DIRT
+                        || mat.material_id == *material::FADED_COBBLE_DIRT
+                    {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::COBBLE_DIRT)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else {
+                        None
+                    }
+                },
             },
-        });
+        );
 
         let features = vec![
             // PlacedFeature::new(SinglePixel::new(placer::TEST_PLACER_2.clone()))
@@ -143,33 +157,42 @@ impl<C: Chunk + 'static> BiomeTestGenerator<C> {
             //     .placement(RandomOffset::chunk())
             //     .placement(MaterialMatch::physics(PhysicsType::Solid))
             //     .placement(BiomeMatch::only("main")),
-            PlacedFeature::new(Blob::new(
-                placer::SMOOTH_DIRT.clone(),
-                Arc::new(|rng| rng.gen_range(16..64)),
-                Arc::new(|m| m.physics == PhysicsType::Solid),
-                false,
-            ))
+            PlacedFeature::new(
+                "dirt_blob",
+                Blob::new(
+                    placer::SMOOTH_DIRT.clone(),
+                    Arc::new(|rng| rng.gen_range(16..64)),
+                    Arc::new(|m| m.physics == PhysicsType::Solid),
+                    false,
+                ),
+            )
             .placement(Chance(0.25))
             .placement(Count::range(0..=2))
             .placement(RandomOffset::chunk())
             .placement(MaterialMatch::material(material::SMOOTH_STONE.clone())),
-            PlacedFeature::new(Blob::new(
-                placer::TEST_PLACER_2.clone(),
-                Arc::new(|rng| rng.gen_range(10..32)),
-                Arc::new(|m| m.physics == PhysicsType::Solid),
-                true,
-            ))
+            PlacedFeature::new(
+                "test_placer_blob",
+                Blob::new(
+                    placer::TEST_PLACER_2.clone(),
+                    Arc::new(|rng| rng.gen_range(10..32)),
+                    Arc::new(|m| m.physics == PhysicsType::Solid),
+                    true,
+                ),
+            )
             .placement(Chance(0.5))
             .placement(Count::range(0..=2))
             .placement(RandomOffset::chunk())
             .placement(MaterialMatch::physics(PhysicsType::Solid))
             .placement(BiomeMatch::only("main")),
-            PlacedFeature::new(ConfiguredStructureFeature::new("yellow_thing".into()))
-                .placement(Count::range(0..=2))
-                .placement(RandomOffset::chunk())
-                .placement(MaterialMatch::physics(PhysicsType::Solid))
-                .placement(BiomeMatch::only("yellow")),
-            PlacedFeature::new(ConfiguredStructureFeature::new("torch".into()))
+            PlacedFeature::new(
+                "yellow_thing",
+                ConfiguredStructureFeature::new("yellow_thing".into()),
+            )
+            .placement(Count::range(0..=2))
+            .placement(RandomOffset::chunk())
+            .placement(MaterialMatch::physics(PhysicsType::Solid))
+            .placement(BiomeMatch::only("yellow")),
+            PlacedFeature::new("torch", ConfiguredStructureFeature::new("torch".into()))
                 .placement(Chance(0.5))
                 .placement(Spread {
                     count: 3,
@@ -184,10 +207,14 @@ impl<C: Chunk + 'static> BiomeTestGenerator<C> {
                     x: 0..1,
                     y: -10..0,
                 }),
-            PlacedFeature::new(TestStructure),
+            PlacedFeature::new("structure_sets", TestStructure),
         ];
 
-        Self { populators, features }
+        Self {
+            populators,
+            features,
+            biome_transition: BiomeTransition { width: 32.0, mode: BiomeBlendMode::Dither },
+        }
     }
 }
 
@@ -205,24 +232,35 @@ impl<C: Chunk + Send + Sync> WorldGenerator<C> for BiomeTestGenerator<C> {
         let chunk_pixel_y = chunk_pos.1 as i64 * CHUNK_SIZE as i64;
 
         // `biome_block` always returns Vec with size W*H, but this cannot be expressed until `generic_const_exprs` is stable
-        let Ok(biomes): Result<[_; CHUNK_AREA], _> = ctx.registries.biomes.biome_block::<CHUNK_SIZE, CHUNK_SIZE>(
-            chunk_pixel_x,
-            chunk_pixel_y,
-            ctx.seed,
-        ).try_into() else { unreachable!() };
+        let Ok(biomes): Result<[_; CHUNK_AREA], _> = ctx
+            .registries
+            .biomes
+            .biome_weights_block::<CHUNK_SIZE, CHUNK_SIZE>(
+                chunk_pixel_x,
+                chunk_pixel_y,
+                ctx.seed,
+                self.biome_transition.width,
+            )
+            .try_into()
+        else {
+            unreachable!()
+        };
+        let blender = BiomeBlender::new(self.biome_transition, ctx.seed);
 
         {
             profiling::scope!("loop");
             for p in ChunkLocalPosition::iter() {
                 let i: ChunkLocalIndex = p.into();
-                let biome = biomes[i].1;
+                let wx = chunk_pixel_x + p.x() as i64;
+                let wy = chunk_pixel_y + p.y() as i64;
+                let biome = blender.pick(&biomes[i], wx, wy).1;
 
                 buf.set_pixel(
                     i,
                     biome
                         .base_placer
                         .as_placer(ctx.registries)
-                        .pixel(chunk_pixel_x + p.x() as i64, chunk_pixel_y + p.y() as i64),
+                        .pixel(wx, wy, ctx.seed),
                 );
 
                 buf.set_bg(
@@ -230,7 +268,7 @@ impl<C: Chunk + Send + Sync> WorldGenerator<C> for BiomeTestGenerator<C> {
                     biome
                         .base_placer
                         .as_placer(ctx.registries)
-                        .pixel(chunk_pixel_x + p.x() as i64, chunk_pixel_y + p.y() as i64),
+                        .pixel(wx, wy, ctx.seed),
                 );
             }
         }
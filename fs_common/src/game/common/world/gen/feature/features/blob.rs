@@ -112,6 +112,7 @@ impl<C: Chunk> ConfiguredFeature<C> for Blob {
                             let mat = placer.pixel(
                                 i64::from(chunk_pixel_x) + i64::from(x),
                                 i64::from(chunk_pixel_y) + i64::from(y),
+                                seed,
                             );
 
                             chunks.set(x, y, mat).unwrap();
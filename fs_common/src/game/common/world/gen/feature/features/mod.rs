@@ -1,4 +1,5 @@
 pub mod blob;
 pub mod configured_structure;
+pub mod ore;
 pub mod simple;
 pub mod test_structure;
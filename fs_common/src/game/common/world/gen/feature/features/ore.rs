@@ -0,0 +1,366 @@
+use std::{f32::consts::TAU, fmt::Debug, ops::Range, sync::Arc};
+
+use bracket_noise::prelude::{FastNoise, NoiseType};
+use rand::Rng;
+
+use crate::game::common::{
+    registry::RegistryID,
+    world::{
+        gen::{
+            biome::Biome,
+            feature::{ConfiguredFeature, ProviderFn},
+            populator::ChunkContext,
+        },
+        material::{
+            placer::{MaterialPlacer, MaterialPlacerSampler},
+            MaterialInstance, PhysicsType,
+        },
+        Chunk, CHUNK_SIZE,
+    },
+    Registries,
+};
+
+/// Settings shared by every [`Ore`] shape.
+pub struct OreConfig {
+    pub placer_id: RegistryID<MaterialPlacer>,
+    /// Meaning depends on the shape, see [`OreShape`]
+    pub size: Arc<ProviderFn<u16>>,
+    /// The world y values the ore can start at
+    pub depth: Range<i64>,
+    /// Only replaces materials with any of these tags.
+    /// If empty, replaces any solid material.
+    pub host_tags: Vec<String>,
+    /// Chance to generate each time the feature is placed
+    pub frequency: f32,
+    /// Overrides `frequency` in specific biomes
+    pub biome_frequency: Vec<(RegistryID<Biome>, f32)>,
+}
+
+impl OreConfig {
+    pub fn new(placer_id: RegistryID<MaterialPlacer>, size: Arc<ProviderFn<u16>>) -> Self {
+        Self {
+            placer_id,
+            size,
+            depth: i64::MIN..i64::MAX,
+            host_tags: vec![],
+            frequency: 1.0,
+            biome_frequency: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn depth(mut self, depth: Range<i64>) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    #[must_use]
+    pub fn host_tag(mut self, tag: impl Into<String>) -> Self {
+        self.host_tags.push(tag.into());
+        self
+    }
+
+    #[must_use]
+    pub fn frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    #[must_use]
+    pub fn biome_frequency(mut self, biome: impl Into<RegistryID<Biome>>, frequency: f32) -> Self {
+        self.biome_frequency.push((biome.into(), frequency));
+        self
+    }
+
+    fn frequency_at(&self, x: i64, y: i64, seed: i32, registries: &Registries) -> f32 {
+        if self.biome_frequency.is_empty() {
+            return self.frequency;
+        }
+
+        let (biome_id, _) = registries.biomes.biome_at(x, y, seed);
+        self.biome_frequency
+            .iter()
+            .find(|(id, _)| id == biome_id)
+            .map_or(self.frequency, |(_, f)| *f)
+    }
+
+    fn can_replace(&self, m: &MaterialInstance, registries: &Registries) -> bool {
+        if self.host_tags.is_empty() {
+            return m.physics == PhysicsType::Solid;
+        }
+
+        registries
+            .materials
+            .get(&m.material_id)
+            .is_some_and(|mat| self.host_tags.iter().any(|t| mat.has_tag(t)))
+    }
+}
+
+pub enum OreShape {
+    /// A winding line following noise, `size` is the length
+    Vein { thickness: f32 },
+    /// Small clumps scattered around the position, `size` is the number of clumps
+    Cluster { spread: u16 },
+    /// One large patch, `size` is the radius (at most [`CHUNK_SIZE`] so it fits in the neighboring chunks).
+    /// `density` is the fraction of the area that is ore.
+    Deposit { density: f32 },
+}
+
+pub struct Ore {
+    config: OreConfig,
+    shape: OreShape,
+}
+
+impl Ore {
+    pub fn new(config: OreConfig, shape: OreShape) -> Self {
+        Self { config, shape }
+    }
+
+    pub fn vein(config: OreConfig, thickness: f32) -> Self {
+        Self::new(config, OreShape::Vein { thickness })
+    }
+
+    pub fn cluster(config: OreConfig, spread: u16) -> Self {
+        Self::new(config, OreShape::Cluster { spread })
+    }
+
+    pub fn deposit(config: OreConfig, density: f32) -> Self {
+        Self::new(config, OreShape::Deposit { density })
+    }
+}
+
+impl Debug for Ore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ore")
+            .field("placer_id", &self.config.placer_id)
+            .finish()
+    }
+}
+
+impl<C: Chunk> ConfiguredFeature<C> for Ore {
+    fn try_place(
+        &self,
+        chunks: &mut ChunkContext<1, C>,
+        pos: (i32, i32),
+        seed: i32,
+        rng: &mut dyn rand::RngCore,
+        registries: &Registries,
+        _ecs: &mut specs::World,
+    ) {
+        let (chunk_x, chunk_y) = chunks.center_chunk();
+        let chunk_pixel_x = i64::from(chunk_x) * i64::from(CHUNK_SIZE);
+        let chunk_pixel_y = i64::from(chunk_y) * i64::from(CHUNK_SIZE);
+        let world_x = chunk_pixel_x + i64::from(pos.0);
+        let world_y = chunk_pixel_y + i64::from(pos.1);
+
+        if !self.config.depth.contains(&world_y) {
+            return;
+        }
+
+        if rng.gen_range(0.0..1.0) >= self.config.frequency_at(world_x, world_y, seed, registries) {
+            return;
+        }
+
+        let placer = registries
+            .material_placers
+            .get(&self.config.placer_id)
+            .unwrap();
+        let size = (self.config.size)(rng);
+
+        // `ChunkContext` doesn't bounds check outside the 3x3 area
+        let in_bounds = -i32::from(CHUNK_SIZE)..i32::from(CHUNK_SIZE) * 2;
+        let mut place = |x: i32, y: i32| {
+            if !in_bounds.contains(&x) || !in_bounds.contains(&y) {
+                return;
+            }
+
+            if let Ok(m) = chunks.get(x, y) {
+                if self.config.can_replace(m, registries) {
+                    let mat = placer.pixel(
+                        chunk_pixel_x + i64::from(x),
+                        chunk_pixel_y + i64::from(y),
+                        seed,
+                    );
+                    chunks.set(x, y, mat).unwrap();
+                }
+            }
+        };
+
+        match self.shape {
+            OreShape::Vein { thickness } => {
+                let mut noise = FastNoise::seeded(rng.gen());
+                noise.set_noise_type(NoiseType::Simplex);
+                noise.set_frequency(0.02);
+
+                let start_angle = rng.gen_range(0.0..TAU);
+                let (mut x, mut y) = (pos.0 as f32, pos.1 as f32);
+                for i in 0..size {
+                    let angle = start_angle + noise.get_noise(x, y) * TAU;
+                    x += angle.cos();
+                    y += angle.sin();
+
+                    // taper towards the ends
+                    let t = f32::from(i) / f32::from(size.max(1));
+                    let r = thickness * (1.0 - (t * 2.0 - 1.0).powi(4)).max(0.25) / 2.0;
+                    let ri = r.ceil() as i32;
+                    for dx in -ri..=ri {
+                        for dy in -ri..=ri {
+                            if (dx * dx + dy * dy) as f32 <= r * r {
+                                place(x as i32 + dx, y as i32 + dy);
+                            }
+                        }
+                    }
+                }
+            },
+            OreShape::Cluster { spread } => {
+                let spread = i32::from(spread);
+                for _ in 0..size {
+                    let cx = pos.0 + rng.gen_range(-spread..=spread);
+                    let cy = pos.1 + rng.gen_range(-spread..=spread);
+                    let r: i32 = rng.gen_range(1..=3);
+                    for dx in -r..=r {
+                        for dy in -r..=r {
+                            if dx * dx + dy * dy <= r * r && rng.gen_bool(0.8) {
+                                place(cx + dx, cy + dy);
+                            }
+                        }
+                    }
+                }
+            },
+            OreShape::Deposit { density } => {
+                let radius = i32::from(size.min(CHUNK_SIZE));
+
+                let mut noise = FastNoise::seeded(rng.gen());
+                noise.set_noise_type(NoiseType::Simplex);
+                noise.set_frequency(0.08);
+
+                // simplex noise is roughly uniform in -1..1
+                let threshold = 1.0 - density.clamp(0.0, 1.0) * 2.0;
+                for dx in -radius..=radius {
+                    for dy in -radius..=radius {
+                        let dist = ((dx * dx + dy * dy) as f32).sqrt() / radius as f32;
+                        if dist > 1.0 {
+                            continue;
+                        }
+
+                        // denser in the middle
+                        let n = noise.get_noise((pos.0 + dx) as f32, (pos.1 + dy) as f32);
+                        if n + (1.0 - dist) * 0.5 > threshold + 0.25 {
+                            place(pos.0 + dx, pos.1 + dy);
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        chunk_index::ChunkLocalPosition,
+        gen::rng::chunk_rng,
+        material::{self, color::Color, placer::MaterialPlacerMeta},
+        test_chunk::{self, TestChunk},
+    };
+
+    /// Stone left of x = 50 and dirt right of it in the center chunk, air everywhere else
+    fn terrain(x: i64, y: i64) -> MaterialInstance {
+        if !(0..i64::from(CHUNK_SIZE)).contains(&x) || !(0..i64::from(CHUNK_SIZE)).contains(&y) {
+            MaterialInstance::air()
+        } else if x < 50 {
+            test_chunk::stone()
+        } else {
+            material::SMOOTH_DIRT.instance(PhysicsType::Solid, Color::rgb(0x80, 0x50, 0x20))
+        }
+    }
+
+    fn registries() -> Registries {
+        let mut registries = test_chunk::registries();
+        registries.material_placers.register(
+            "ore",
+            MaterialPlacer {
+                meta: MaterialPlacerMeta { display_name: String::new() },
+                sampler: Arc::new(
+                    material::GOLD_ORE.instance(PhysicsType::Solid, Color::rgb(0xff, 0xd0, 0)),
+                ),
+            },
+        );
+        registries
+    }
+
+    /// Places a dense deposit of radius 40 at `pos` in the center chunk, returns the resulting chunks
+    fn place(config: OreConfig, pos: (i32, i32)) -> Vec<TestChunk> {
+        let mut chunks = test_chunk::chunk_area((0, 0), 1, terrain);
+        let mut refs: Vec<&mut TestChunk> = chunks.iter_mut().collect();
+        let mut ctx = ChunkContext::<1, TestChunk>::new(&mut refs).unwrap();
+        Ore::deposit(config, 1.0).try_place(
+            &mut ctx,
+            pos,
+            1,
+            &mut chunk_rng(1, (0, 0), 0),
+            &registries(),
+            &mut specs::World::empty(),
+        );
+        chunks
+    }
+
+    fn config() -> OreConfig {
+        OreConfig::new("ore".into(), Arc::new(|_| 40))
+    }
+
+    /// The world positions of ore in `chunks`, and every other pixel checked against [`terrain`]
+    fn ore_pixels(chunks: &[TestChunk]) -> Vec<(i64, i64)> {
+        let mut ore = vec![];
+        for chunk in chunks {
+            for p in ChunkLocalPosition::iter() {
+                let x = i64::from(chunk.chunk_x()) * i64::from(CHUNK_SIZE) + i64::from(p.x());
+                let y = i64::from(chunk.chunk_y()) * i64::from(CHUNK_SIZE) + i64::from(p.y());
+                let m = chunk.pixel(p).unwrap();
+                if m.material_id == *material::GOLD_ORE {
+                    ore.push((x, y));
+                } else {
+                    assert_eq!(m.material_id, terrain(x, y).material_id);
+                }
+            }
+        }
+        ore
+    }
+
+    #[test]
+    fn host_tags_filter_replaced_cells() {
+        // without tags any solid is replaced, but never air
+        let ore = ore_pixels(&place(config(), (50, 50)));
+        assert!(ore.iter().any(|&(x, _)| x < 50));
+        assert!(ore.iter().any(|&(x, _)| x >= 50));
+        assert!(ore
+            .iter()
+            .all(|&(x, y)| terrain(x, y).physics == PhysicsType::Solid));
+
+        let ore = ore_pixels(&place(config().host_tag(material::TAG_STONE), (50, 50)));
+        assert!(!ore.is_empty());
+        assert!(ore.iter().all(|&(x, _)| x < 50));
+
+        let ore = ore_pixels(&place(config().host_tag(material::TAG_DIRT), (50, 50)));
+        assert!(!ore.is_empty());
+        assert!(ore.iter().all(|&(x, _)| x >= 50));
+
+        let ore = ore_pixels(&place(config().host_tag(material::TAG_LIQUID), (50, 50)));
+        assert!(ore.is_empty());
+    }
+
+    #[test]
+    fn depth_filters_start() {
+        let ore = ore_pixels(&place(config().depth(0..40), (50, 50)));
+        assert!(ore.is_empty());
+
+        // only the start has to be in range, the ore itself can extend past it
+        let ore = ore_pixels(&place(config().depth(0..40), (50, 39)));
+        assert!(ore.iter().any(|&(_, y)| y >= 40));
+
+        let ore = ore_pixels(&place(config().depth(40..i64::MAX), (50, 39)));
+        assert!(ore.is_empty());
+    }
+}
@@ -26,7 +26,7 @@ impl<C: Chunk> ConfiguredFeature<C> for SinglePixel {
         &self,
         chunks: &mut ChunkContext<1, C>,
         pos: (i32, i32),
-        _seed: i32,
+        seed: i32,
         _rng: &mut dyn RngCore,
         registries: &Registries,
         _ecs: &mut specs::World,
@@ -40,6 +40,7 @@ impl<C: Chunk> ConfiguredFeature<C> for SinglePixel {
             .pixel(
                 i64::from(cx * i32::from(CHUNK_SIZE)) + i64::from(pos.0),
                 i64::from(cy * i32::from(CHUNK_SIZE)) + i64::from(pos.1),
+                seed,
             );
         let _: Result<(), _> = chunks.set(pos.0, pos.1, m);
     }
@@ -31,10 +31,10 @@ impl<C: Chunk> ConfiguredFeature<C> for TestStructure {
 
         for (_, v) in &registries.structure_sets {
             if v.should_generate_at((cx, cy), world_seed as _, registries, true) {
-                let configured_structure = registries
-                    .configured_structures
-                    .get(v.sample_structure())
-                    .unwrap();
+                let Some(id) = v.structure_at((cx, cy), world_seed as _) else {
+                    continue;
+                };
+                let configured_structure = registries.configured_structures.get(id).unwrap();
 
                 configured_structure.place(
                     x,
@@ -25,13 +25,27 @@ pub trait ConfiguredFeature<C: Chunk>: Debug {
 
 #[derive(Debug)]
 pub struct PlacedFeature<C: Chunk> {
+    id: &'static str,
     feature: Box<dyn ConfiguredFeature<C> + Send + Sync>,
     placement_mods: Vec<Box<dyn PlacementModifier<C> + Send + Sync>>,
 }
 
 impl<C: Chunk> PlacedFeature<C> {
-    pub fn new(feature: impl ConfiguredFeature<C> + Send + Sync + 'static) -> Self {
-        Self { feature: Box::new(feature), placement_mods: vec![] }
+    /// `id` seeds the feature's rng (see [`feature_salt`](super::rng::feature_salt)),
+    ///   so it must be unique within a generator and shouldn't change once worlds use it.
+    pub fn new(
+        id: &'static str,
+        feature: impl ConfiguredFeature<C> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id,
+            feature: Box::new(feature),
+            placement_mods: vec![],
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        self.id
     }
 
     #[must_use]
@@ -43,6 +57,7 @@ impl<C: Chunk> PlacedFeature<C> {
         self
     }
 
+    /// `rng` should be unique to this feature and chunk, see [`chunk_rng`](super::rng::chunk_rng)
     pub fn generate(
         &self,
         chunks: &mut ChunkContext<1, C>,
@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use rand::Rng;
+
 use crate::game::common::{
     registry::RegistryID,
     world::{
@@ -13,17 +15,27 @@ pub type BiomeMatchFn = dyn Fn(&RegistryID<Biome>, &Biome) -> bool + Send + Sync
 
 pub struct BiomeMatch {
     predicate: Arc<BiomeMatchFn>,
+    /// See [`BiomeMatch::weighted`]
+    transition_width: Option<f32>,
 }
 
 impl BiomeMatch {
     pub fn new(predicate: Arc<BiomeMatchFn>) -> Self {
-        Self { predicate }
+        Self { predicate, transition_width: None }
     }
 
     pub fn only(id: impl Into<RegistryID<Biome>>) -> Self {
         let id = id.into();
         Self::new(Arc::new(move |found_id, _| *found_id == id))
     }
+
+    /// Within `transition_width` pixels of a biome border, pass with a chance equal to the fraction of
+    ///   biome weight that matches, so the feature thins out near borders.
+    #[must_use]
+    pub fn weighted(mut self, transition_width: f32) -> Self {
+        self.transition_width = Some(transition_width);
+        self
+    }
 }
 
 impl std::fmt::Debug for BiomeMatch {
@@ -38,15 +50,24 @@ impl<C: Chunk> PlacementModifier<C> for BiomeMatch {
         chunks: &mut ChunkContext<1, C>,
         pos: (i32, i32),
         seed: i32,
-        _rng: &mut dyn rand::RngCore,
+        rng: &mut dyn rand::RngCore,
         registries: &Registries,
     ) -> Vec<(i32, i32)> {
         let world_x = i64::from(chunks.center_chunk().0) * i64::from(CHUNK_SIZE) + i64::from(pos.0);
         let world_y = i64::from(chunks.center_chunk().1) * i64::from(CHUNK_SIZE) + i64::from(pos.1);
 
-        let (biome_id, biome) = registries.biomes.biome_at(world_x, world_y, seed);
+        let matches = if let Some(transition_width) = self.transition_width {
+            let fraction = registries
+                .biomes
+                .biome_weights_at(world_x, world_y, seed, transition_width)
+                .weight_where(&*self.predicate);
+            rng.gen_range(0.0..1.0) < fraction
+        } else {
+            let (biome_id, biome) = registries.biomes.biome_at(world_x, world_y, seed);
+            (self.predicate)(biome_id, biome)
+        };
 
-        if (self.predicate)(biome_id, biome) {
+        if matches {
             vec![pos]
         } else {
             vec![]
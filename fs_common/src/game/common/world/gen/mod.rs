@@ -2,7 +2,10 @@ pub mod biome;
 pub mod biome_test;
 pub mod feature;
 pub mod populator;
+pub mod preview;
+pub mod rng;
 pub mod structure;
+pub mod surface;
 mod test;
 
 use std::boxed::Box;
@@ -25,9 +28,12 @@ use super::material::color::Color;
 use super::material::MaterialInstance;
 use super::CHUNK_AREA;
 
+/// A populator with the id its rng is seeded with
+pub type NamedPopulator<const S: u8, C> = (&'static str, Box<dyn Populator<S, C> + Send + Sync>);
+
 #[derive(Debug)]
 pub struct PopulatorList<C: Chunk> {
-    /// Invariant: for a given key S, the value must be `Box<Vec<Box<dyn Populator<S, C> + Send + Sync>>>`
+    /// Invariant: for a given key S, the value must be `Box<Vec<NamedPopulator<S, C>>>`
     ///
     /// Afaik this is impossible to express statically
     map: HashMap<u8, Box<dyn Any + Send + Sync>>,
@@ -40,24 +46,29 @@ impl<C: Chunk + 'static> PopulatorList<C> {
         Self { map: HashMap::new(), phantom: PhantomData }
     }
 
-    pub fn add<const S: u8>(&mut self, pop: impl Populator<S, C> + 'static + Send + Sync) {
-        let opt: Option<&mut Vec<Box<dyn Populator<S, C> + Send + Sync>>> = self
+    /// `id` seeds the populator's rng (see [`populator_salt`](rng::populator_salt)),
+    ///   so it must be unique within a stage and shouldn't change once worlds use it.
+    pub fn add<const S: u8>(
+        &mut self,
+        id: &'static str,
+        pop: impl Populator<S, C> + 'static + Send + Sync,
+    ) {
+        let opt: Option<&mut Vec<NamedPopulator<S, C>>> = self
             .map
             .entry(S)
-            .or_insert_with(|| Box::<Vec<Box<dyn Populator<S, C> + Send + Sync>>>::default())
+            .or_insert_with(|| Box::<Vec<NamedPopulator<S, C>>>::default())
             .downcast_mut();
 
         // Safety: this function is the only place where we insert into self.map, so the downcast cannot fail
         let vec = unsafe { opt.unwrap_unchecked() };
 
-        vec.push(Box::new(pop));
+        vec.push((id, Box::new(pop)));
     }
 
-    pub fn get_all<const S: u8>(&self) -> &[Box<dyn Populator<S, C> + Send + Sync>] {
+    pub fn get_all<const S: u8>(&self) -> &[NamedPopulator<S, C>] {
         if let Some(a) = self.map.get(&S) {
             // Safety: this is an invariant of self.map
-            let vec: &Vec<Box<dyn Populator<S, C> + Send + Sync>> =
-                unsafe { a.downcast_ref().unwrap_unchecked() };
+            let vec: &Vec<NamedPopulator<S, C>> = unsafe { a.downcast_ref().unwrap_unchecked() };
             vec
         } else {
             &[]
@@ -72,8 +83,10 @@ impl<C: Chunk + 'static> PopulatorList<C> {
             let &branch = {
                 *lhs => {
                     let mut ctx = ChunkContext::<*lhs, C>::new(chunks).unwrap();
-                    for pop in self.get_all::<*lhs>() {
-                        pop.populate(&mut ctx, seed, registries);
+                    let center = ctx.center_chunk();
+                    for (id, pop) in self.get_all::<*lhs>() {
+                        let mut rng = rng::chunk_rng(seed, center, rng::populator_salt(*lhs, id));
+                        pop.populate(&mut ctx, seed, &mut rng, registries);
                     }
                 }
             };
@@ -0,0 +1,230 @@
+use bracket_noise::prelude::{FastNoise, NoiseType};
+use rand::RngCore;
+
+use crate::game::common::{
+    registry::RegistryID,
+    world::{
+        gen::biome::Biome,
+        material::{
+            placer::{self, MaterialPlacer, MaterialPlacerSampler},
+            PhysicsType,
+        },
+        Chunk, CHUNK_SIZE,
+    },
+    Registries,
+};
+
+use super::{ChunkContext, Populator};
+
+/// Floods some of the open space below a noise driven water table with liquid.
+///
+/// Any air next to (or below) the liquid that isn't going to be flooded itself is sealed with `seal`,
+///   so pools don't drain as soon as neighboring chunks start simulating.
+/// Whether a pixel floods only depends on its world position, so pools that cross chunk borders line up.
+pub struct AquiferPopulator {
+    /// Average world y of the water table, nothing above it floods
+    pub water_table: i64,
+    /// Max distance the water table goes above or below `water_table`
+    pub water_table_amplitude: f32,
+    /// Roughly the fraction of space below the water table that floods
+    pub coverage: f32,
+    /// Below this world y, `lava` is used instead
+    pub lava_level: i64,
+    pub water: RegistryID<MaterialPlacer>,
+    pub lava: RegistryID<MaterialPlacer>,
+    /// Overrides `water` in specific biomes
+    pub biome_liquids: Vec<(RegistryID<Biome>, RegistryID<MaterialPlacer>)>,
+    pub seal: RegistryID<MaterialPlacer>,
+}
+
+impl AquiferPopulator {
+    pub fn new() -> Self {
+        Self {
+            water_table: 400,
+            water_table_amplitude: 150.0,
+            coverage: 0.4,
+            lava_level: 2500,
+            water: placer::WATER.clone(),
+            lava: placer::LAVA.clone(),
+            biome_liquids: vec![],
+            seal: placer::COBBLE_STONE.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn biome_liquid(
+        mut self,
+        biome: impl Into<RegistryID<Biome>>,
+        liquid: impl Into<RegistryID<MaterialPlacer>>,
+    ) -> Self {
+        self.biome_liquids.push((biome.into(), liquid.into()));
+        self
+    }
+
+    fn floods(&self, x: i64, y: i64, table_noise: &FastNoise, mask_noise: &FastNoise) -> bool {
+        let table = self.water_table
+            + (table_noise.get_noise(x as f32, 0.0) * self.water_table_amplitude) as i64;
+
+        // simplex noise is roughly uniform in -1..1
+        y > table && mask_noise.get_noise(x as f32, y as f32) > 1.0 - self.coverage * 2.0
+    }
+}
+
+impl Default for AquiferPopulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Chunk> Populator<1, C> for AquiferPopulator {
+    #[profiling::function]
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<1, C>,
+        seed: i32,
+        _rng: &mut dyn RngCore,
+        registries: &Registries,
+    ) {
+        let (chunk_x, chunk_y) = chunks.center_chunk();
+        let chunk_pixel_x = i64::from(chunk_x) * i64::from(CHUNK_SIZE);
+        let chunk_pixel_y = i64::from(chunk_y) * i64::from(CHUNK_SIZE);
+
+        // the top of the water table can't reach this chunk
+        if chunk_pixel_y + i64::from(CHUNK_SIZE)
+            <= self.water_table - self.water_table_amplitude.abs() as i64
+        {
+            return;
+        }
+
+        let mut table_noise = FastNoise::seeded(seed as u64 + 10);
+        table_noise.set_noise_type(NoiseType::SimplexFractal);
+        table_noise.set_fractal_octaves(2);
+        table_noise.set_frequency(0.002);
+
+        let mut mask_noise = FastNoise::seeded(seed as u64 + 11);
+        mask_noise.set_noise_type(NoiseType::Simplex);
+        mask_noise.set_frequency(0.004);
+
+        let seal = registries.material_placers.get(&self.seal).unwrap();
+
+        // only looked up if something floods
+        let mut biomes: Option<Vec<_>> = None;
+
+        for y in 0..i32::from(CHUNK_SIZE) {
+            for x in 0..i32::from(CHUNK_SIZE) {
+                let wx = chunk_pixel_x + i64::from(x);
+                let wy = chunk_pixel_y + i64::from(y);
+
+                if chunks.get(x, y).unwrap().physics != PhysicsType::Air
+                    || !self.floods(wx, wy, &table_noise, &mask_noise)
+                {
+                    continue;
+                }
+
+                let liquid = if wy >= self.lava_level {
+                    &self.lava
+                } else if self.biome_liquids.is_empty() {
+                    &self.water
+                } else {
+                    let biomes = biomes.get_or_insert_with(|| {
+                        registries.biomes.biome_block::<CHUNK_SIZE, CHUNK_SIZE>(
+                            chunk_pixel_x,
+                            chunk_pixel_y,
+                            seed,
+                        )
+                    });
+                    let (biome_id, _) = biomes[(x + y * i32::from(CHUNK_SIZE)) as usize];
+                    self.biome_liquids
+                        .iter()
+                        .find(|(id, _)| id == biome_id)
+                        .map_or(&self.water, |(_, l)| l)
+                };
+
+                let mat = registries
+                    .material_placers
+                    .get(liquid)
+                    .unwrap()
+                    .pixel(wx, wy, seed);
+                chunks.set(x, y, mat).unwrap();
+
+                // liquid can flow sideways or down, so seal any air there that won't also be flooded
+                // neighbors are at most one pixel outside the center chunk, which is still in the 3x3 area
+                for (dx, dy) in [(-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    let (nwx, nwy) = (wx + i64::from(dx), wy + i64::from(dy));
+                    if chunks.get(nx, ny).unwrap().physics == PhysicsType::Air
+                        && !self.floods(nwx, nwy, &table_noise, &mask_noise)
+                    {
+                        chunks.set(nx, ny, seal.pixel(nwx, nwy, seed)).unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        chunk_index::ChunkLocalPosition,
+        gen::rng::chunk_rng,
+        material::MaterialInstance,
+        test_chunk::{self, TestChunk},
+    };
+
+    /// Stone with a large cave in the center chunk
+    fn terrain(x: i64, y: i64) -> MaterialInstance {
+        let cave = 5..i64::from(CHUNK_SIZE) - 5;
+        if cave.contains(&x) && cave.contains(&y) {
+            MaterialInstance::air()
+        } else {
+            test_chunk::stone()
+        }
+    }
+
+    #[test]
+    fn pools_are_sealed() {
+        let registries = test_chunk::asset_registries();
+        let aquifer = AquiferPopulator {
+            water_table: -1000,
+            water_table_amplitude: 0.0,
+            coverage: 0.5,
+            ..AquiferPopulator::new()
+        };
+
+        let mut chunks = test_chunk::chunk_area((0, 0), 1, terrain);
+        let mut refs: Vec<&mut TestChunk> = chunks.iter_mut().collect();
+        let mut ctx = ChunkContext::<1, TestChunk>::new(&mut refs).unwrap();
+        aquifer.populate(&mut ctx, 3, &mut chunk_rng(3, (0, 0), 0), &registries);
+
+        let physics = |x: i32, y: i32| ctx.get(x, y).unwrap().physics;
+        let mut liquid = 0;
+        let mut air = 0;
+        for p in ChunkLocalPosition::iter() {
+            let (x, y) = (i32::from(p.x()), i32::from(p.y()));
+            match physics(x, y) {
+                PhysicsType::Liquid => {
+                    liquid += 1;
+                    // nowhere for it to flow
+                    for (dx, dy) in [(-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                        assert_ne!(
+                            physics(x + dx, y + dy),
+                            PhysicsType::Air,
+                            "liquid at {x},{y} can flow into {},{}",
+                            x + dx,
+                            y + dy
+                        );
+                    }
+                },
+                PhysicsType::Air => air += 1,
+                _ => {},
+            }
+        }
+
+        // the cave is partly flooded
+        assert!(liquid > 0);
+        assert!(air > 0);
+    }
+}
@@ -1,4 +1,5 @@
 use bracket_noise::prelude::{FastNoise, NoiseType};
+use rand::RngCore;
 use simdnoise::NoiseBuilder;
 
 use crate::game::common::{
@@ -12,7 +13,13 @@ pub struct CavePopulator;
 
 impl<C: Chunk> Populator<0, C> for CavePopulator {
     #[profiling::function]
-    fn populate(&self, chunks: &mut ChunkContext<0, C>, seed: i32, _registries: &Registries) {
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<0, C>,
+        seed: i32,
+        _rng: &mut dyn RngCore,
+        _registries: &Registries,
+    ) {
         let (chunk_x, chunk_y) = chunks.center_chunk();
         let chunk_pixel_x = chunk_x * i32::from(CHUNK_SIZE);
         let chunk_pixel_y = chunk_y * i32::from(CHUNK_SIZE);
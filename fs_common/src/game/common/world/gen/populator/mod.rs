@@ -1,12 +1,16 @@
+pub mod aquifer;
 pub mod cave;
 pub mod nearby_replace;
 pub mod place_above;
 pub mod spawn;
 pub mod stalactite;
 pub mod test;
+pub mod worm;
 
 use std::usize;
 
+use rand::RngCore;
+
 use crate::game::common::{
     world::{chunk_index::ChunkLocalPosition, material::MaterialInstance, Chunk, CHUNK_SIZE},
     Registries,
@@ -14,7 +18,14 @@ use crate::game::common::{
 
 // where S=0 means 1x1, S=1 means 3x3, etc
 pub trait Populator<const S: u8, C: Chunk> {
-    fn populate(&self, chunks: &mut ChunkContext<S, C>, seed: i32, registries: &Registries);
+    /// `rng` is unique to this populator and chunk, see [`chunk_rng`](super::rng::chunk_rng)
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<S, C>,
+        seed: i32,
+        rng: &mut dyn RngCore,
+        registries: &Registries,
+    );
 }
 
 // where S=0 means 1x1, S=1 means 3x3, etc
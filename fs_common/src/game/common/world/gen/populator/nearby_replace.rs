@@ -1,3 +1,5 @@
+use rand::RngCore;
+
 use crate::game::common::{
     world::{material::MaterialInstance, Chunk, CHUNK_SIZE},
     Registries,
@@ -6,7 +8,7 @@ use crate::game::common::{
 use super::{ChunkContext, Populator};
 
 pub struct NearbyReplacePopulator<
-    R: Fn(&MaterialInstance, i64, i64, &Registries) -> Option<MaterialInstance>,
+    R: Fn(&MaterialInstance, i64, i64, i32, &Registries) -> Option<MaterialInstance>,
     S: Fn(&MaterialInstance) -> bool,
 > {
     pub radius: u16,
@@ -15,13 +17,19 @@ pub struct NearbyReplacePopulator<
 }
 
 impl<
-        R: Fn(&MaterialInstance, i64, i64, &Registries) -> Option<MaterialInstance>,
+        R: Fn(&MaterialInstance, i64, i64, i32, &Registries) -> Option<MaterialInstance>,
         S: Fn(&MaterialInstance) -> bool,
         C: Chunk,
     > Populator<1, C> for NearbyReplacePopulator<R, S>
 {
     #[profiling::function]
-    fn populate(&self, chunks: &mut ChunkContext<1, C>, _seed: i32, registries: &Registries) {
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<1, C>,
+        seed: i32,
+        _rng: &mut dyn RngCore,
+        registries: &Registries,
+    ) {
         // the skip_x and skip_y stuff helps avoid a lot of redundant pixel checks
         // otherwise this is basically just brute force
         // for each pixel that matches `searching_for`, scan around it and try to `replace`
@@ -106,6 +114,7 @@ impl<
                                         mat,
                                         cofs_x + i64::from(x + dx),
                                         cofs_y + i64::from(y + dy),
+                                        seed,
                                         registries,
                                     )
                                 });
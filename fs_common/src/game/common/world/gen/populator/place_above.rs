@@ -1,3 +1,5 @@
+use rand::RngCore;
+
 use crate::game::common::{
     world::{
         material::{self, MaterialInstance},
@@ -13,13 +15,19 @@ pub struct PlaceAbovePopulator {
     pub add_surface_height: u8,
     /// Amount to "bury" into the found surface
     pub replace_surface_depth: u8,
-    pub replace: fn(&MaterialInstance, i64, i64, &Registries) -> Option<MaterialInstance>,
+    pub replace: fn(&MaterialInstance, i64, i64, i32, &Registries) -> Option<MaterialInstance>,
     pub searching_for: fn(&MaterialInstance) -> bool,
 }
 
 impl<C: Chunk> Populator<1, C> for PlaceAbovePopulator {
     #[profiling::function]
-    fn populate(&self, chunks: &mut ChunkContext<1, C>, _seed: i32, registries: &Registries) {
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<1, C>,
+        seed: i32,
+        _rng: &mut dyn RngCore,
+        registries: &Registries,
+    ) {
         let cofs_x = i64::from(chunks.center_chunk().0) * i64::from(CHUNK_SIZE);
         let cofs_y = i64::from(chunks.center_chunk().1) * i64::from(CHUNK_SIZE);
 
@@ -38,6 +46,7 @@ impl<C: Chunk> Populator<1, C> for PlaceAbovePopulator {
                             m2,
                             cofs_x + i64::from(x),
                             cofs_y + i64::from(y) + i64::from(dy),
+                            seed,
                             registries,
                         ) {
                             chunks.set(x, y + dy, rep).unwrap();
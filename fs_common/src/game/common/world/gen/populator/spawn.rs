@@ -1,3 +1,5 @@
+use rand::RngCore;
+
 use crate::game::common::{
     world::{
         material::{
@@ -15,7 +17,13 @@ pub struct SpawnPopulator;
 
 impl<C: Chunk> Populator<0, C> for SpawnPopulator {
     #[profiling::function]
-    fn populate(&self, chunks: &mut ChunkContext<0, C>, _seed: i32, registries: &Registries) {
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<0, C>,
+        seed: i32,
+        _rng: &mut dyn RngCore,
+        registries: &Registries,
+    ) {
         let (chunk_x, chunk_y) = chunks.center_chunk();
         let chunk_pixel_x = i64::from(chunk_x) * i64::from(CHUNK_SIZE);
         let chunk_pixel_y = i64::from(chunk_y) * i64::from(CHUNK_SIZE);
@@ -34,7 +42,7 @@ impl<C: Chunk> Populator<0, C> for SpawnPopulator {
                                 .material_placers
                                 .get(&*placer::COBBLE_STONE)
                                 .unwrap()
-                                .pixel(wx, wy),
+                                .pixel(wx, wy, seed),
                         )
                         .unwrap();
                 } else if wx.abs() < 150 && (wy + 32).abs() < 64 {
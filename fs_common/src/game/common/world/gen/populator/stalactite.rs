@@ -1,4 +1,4 @@
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{Rng, RngCore};
 
 use crate::game::common::{
     world::{
@@ -12,14 +12,18 @@ use super::{ChunkContext, Populator};
 
 pub struct StalactitePopulator {
     pub searching_for: fn(&MaterialInstance) -> bool,
-    pub replace: fn(&MaterialInstance, i64, i64, &Registries) -> Option<MaterialInstance>,
+    pub replace: fn(&MaterialInstance, i64, i64, i32, &Registries) -> Option<MaterialInstance>,
 }
 
 impl<C: Chunk> Populator<1, C> for StalactitePopulator {
     #[profiling::function]
-    fn populate(&self, chunks: &mut ChunkContext<1, C>, seed: i32, registries: &Registries) {
-        let mut rng = StdRng::seed_from_u64(seed as u64);
-
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<1, C>,
+        seed: i32,
+        rng: &mut dyn RngCore,
+        registries: &Registries,
+    ) {
         'skip: for _ in 0..1000 {
             let x = rng.gen_range(0..i32::from(CHUNK_SIZE));
             let y = rng.gen_range(0..i32::from(CHUNK_SIZE));
@@ -51,6 +55,7 @@ impl<C: Chunk> Populator<1, C> for StalactitePopulator {
                             (i64::from(chunks.center_chunk().1) * i64::from(CHUNK_SIZE))
                                 + i64::from(y)
                                 + i64::from(dy),
+                            seed,
                             registries,
                         ) {
                             chunks.set(x + dx, y + dy, rep).unwrap();
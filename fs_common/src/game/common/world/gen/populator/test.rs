@@ -1,3 +1,5 @@
+use rand::RngCore;
+
 use crate::game::common::{
     world::{
         material::{self, color::Color, PhysicsType},
@@ -11,7 +13,13 @@ use super::{ChunkContext, Populator};
 pub struct TestPopulator;
 
 impl<const S: u8, C: Chunk> Populator<S, C> for TestPopulator {
-    fn populate(&self, chunks: &mut ChunkContext<S, C>, _seed: i32, _registries: &Registries) {
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<S, C>,
+        _seed: i32,
+        _rng: &mut dyn RngCore,
+        _registries: &Registries,
+    ) {
         for x in 0..i32::from(CHUNK_SIZE) {
             for y in 0..i32::from(CHUNK_SIZE) {
                 let m = chunks.get(x, y).unwrap();
@@ -0,0 +1,292 @@
+use std::{f32::consts::TAU, ops::Range};
+
+use bracket_noise::prelude::{FastNoise, NoiseType};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::common::{
+    registry::RegistryID,
+    world::{
+        gen::{
+            biome::Biome,
+            rng::{chunk_rng, named_salt},
+        },
+        material::MaterialInstance,
+        Chunk, CHUNK_SIZE,
+    },
+    Registries,
+};
+
+use super::{ChunkContext, Populator};
+
+#[derive(Debug, Clone)]
+pub struct WormSettings {
+    /// Chance for each start point in a region to become a tunnel
+    pub frequency: f32,
+    /// Range of the tunnel radius, it varies along the length
+    pub radius: Range<f32>,
+    /// Range of the tunnel length in pixels
+    pub length: Range<u32>,
+}
+
+/// Carves long tunnels that wander across many chunks.
+///
+/// The world is split into square regions of `region_size` chunks, each with `starts_per_region` possible tunnel starts.
+/// Every chunk simulates the tunnels starting in its own and neighboring regions and carves the part that is inside it,
+///   so each tunnel only depends on the seed and its region, not on which chunks have been generated.
+pub struct WormCarverPopulator {
+    /// Size of a region in chunks. Tunnels are cut short so they can't leave the neighboring regions.
+    pub region_size: u16,
+    pub starts_per_region: u16,
+    /// The world y values tunnels can start at
+    pub depth: Range<i64>,
+    /// Max change in direction per pixel traveled, in radians
+    pub turn: f32,
+    pub settings: WormSettings,
+    /// Overrides `settings` for tunnels starting in specific biomes
+    pub biome_settings: Vec<(RegistryID<Biome>, WormSettings)>,
+}
+
+impl WormCarverPopulator {
+    pub fn new(settings: WormSettings) -> Self {
+        Self {
+            region_size: 8,
+            starts_per_region: 4,
+            depth: 150..i64::MAX,
+            turn: 0.08,
+            settings,
+            biome_settings: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn biome_settings(
+        mut self,
+        biome: impl Into<RegistryID<Biome>>,
+        settings: WormSettings,
+    ) -> Self {
+        self.biome_settings.push((biome.into(), settings));
+        self
+    }
+
+    fn settings_at(&self, x: i64, y: i64, seed: i32, registries: &Registries) -> &WormSettings {
+        if self.biome_settings.is_empty() {
+            return &self.settings;
+        }
+
+        let (biome_id, _) = registries.biomes.biome_at(x, y, seed);
+        self.biome_settings
+            .iter()
+            .find(|(id, _)| id == biome_id)
+            .map_or(&self.settings, |(_, s)| s)
+    }
+
+    /// Calls `carve(x, y, radius)` along every tunnel starting in `region`
+    fn tunnels_in_region(
+        &self,
+        region: (i32, i32),
+        seed: i32,
+        registries: &Registries,
+        mut carve: impl FnMut(f32, f32, f32),
+    ) {
+        let region_px = i64::from(self.region_size) * i64::from(CHUNK_SIZE);
+        let mut rng = chunk_rng(seed, region, named_salt("worm_carver"));
+
+        for _ in 0..self.starts_per_region {
+            // always use the same number of values per start so the later starts don't depend on the earlier ones
+            let x = i64::from(region.0) * region_px + rng.gen_range(0..region_px);
+            let y = i64::from(region.1) * region_px + rng.gen_range(0..region_px);
+            let chance: f32 = rng.gen();
+            let worm_seed: u64 = rng.gen();
+
+            if !self.depth.contains(&y) {
+                continue;
+            }
+
+            let settings = self.settings_at(x, y, seed, registries);
+            if chance >= settings.frequency {
+                continue;
+            }
+
+            let mut worm_rng = ChaCha8Rng::seed_from_u64(worm_seed);
+            let mut noise = FastNoise::seeded(worm_seed);
+            noise.set_noise_type(NoiseType::Simplex);
+            noise.set_frequency(0.01);
+
+            let length = if settings.length.is_empty() {
+                settings.length.start
+            } else {
+                worm_rng.gen_range(settings.length.clone())
+            }
+            .min(region_px as u32);
+
+            let (mut px, mut py) = (x as f32, y as f32);
+            // mostly horizontal
+            let mut angle = worm_rng.gen_range(-0.5..0.5)
+                + if worm_rng.gen_bool(0.5) {
+                    0.0
+                } else {
+                    TAU / 2.0
+                };
+            for i in 0..length {
+                let t = i as f32;
+                angle += noise.get_noise(t, 0.0) * self.turn;
+                px += angle.cos();
+                py += angle.sin();
+
+                let r = noise.get_noise(t, 1000.0) * 0.5 + 0.5;
+                let radius =
+                    settings.radius.start + (settings.radius.end - settings.radius.start) * r;
+                carve(px, py, radius);
+            }
+        }
+    }
+}
+
+impl<C: Chunk> Populator<0, C> for WormCarverPopulator {
+    #[profiling::function]
+    fn populate(
+        &self,
+        chunks: &mut ChunkContext<0, C>,
+        seed: i32,
+        _rng: &mut dyn RngCore,
+        registries: &Registries,
+    ) {
+        let (chunk_x, chunk_y) = chunks.center_chunk();
+        let chunk_pixel_x = chunk_x as f32 * f32::from(CHUNK_SIZE);
+        let chunk_pixel_y = chunk_y as f32 * f32::from(CHUNK_SIZE);
+
+        let region_size = i32::from(self.region_size);
+        let region_x = chunk_x.div_euclid(region_size);
+        let region_y = chunk_y.div_euclid(region_size);
+
+        for rx in region_x - 1..=region_x + 1 {
+            for ry in region_y - 1..=region_y + 1 {
+                self.tunnels_in_region((rx, ry), seed, registries, |x, y, radius| {
+                    // relative to this chunk
+                    let x = x - chunk_pixel_x;
+                    let y = y - chunk_pixel_y;
+                    let size = f32::from(CHUNK_SIZE);
+                    if x + radius < 0.0
+                        || y + radius < 0.0
+                        || x - radius >= size
+                        || y - radius >= size
+                    {
+                        return;
+                    }
+
+                    let min_x = (x - radius).floor().max(0.0) as i32;
+                    let max_x = (x + radius).ceil().min(size - 1.0) as i32;
+                    let min_y = (y - radius).floor().max(0.0) as i32;
+                    let max_y = (y + radius).ceil().min(size - 1.0) as i32;
+                    for cx in min_x..=max_x {
+                        for cy in min_y..=max_y {
+                            let dx = cx as f32 - x;
+                            let dy = cy as f32 - y;
+                            if dx * dx + dy * dy <= radius * radius {
+                                chunks.set(cx, cy, MaterialInstance::air()).unwrap();
+                            }
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    use crate::game::common::world::{
+        chunk_index::ChunkLocalPosition,
+        material::PhysicsType,
+        test_chunk::{self, TestChunk},
+    };
+
+    fn carver() -> WormCarverPopulator {
+        WormCarverPopulator {
+            region_size: 2,
+            starts_per_region: 8,
+            depth: i64::MIN..i64::MAX,
+            ..WormCarverPopulator::new(WormSettings {
+                frequency: 1.0,
+                radius: 4.0..8.0,
+                length: 200..400,
+            })
+        }
+    }
+
+    /// Carves each chunk on its own in the given order, returns the world positions of air
+    fn carve(chunks: &[(i32, i32)], seed: i32) -> HashSet<(i64, i64)> {
+        let registries = test_chunk::registries();
+        let mut air = HashSet::new();
+        for &chunk in chunks {
+            let mut area = test_chunk::chunk_area(chunk, 0, |_, _| test_chunk::stone());
+            let mut refs: Vec<&mut TestChunk> = area.iter_mut().collect();
+            let mut ctx = ChunkContext::<0, TestChunk>::new(&mut refs).unwrap();
+            carver().populate(&mut ctx, seed, &mut chunk_rng(seed, chunk, 0), &registries);
+
+            for p in ChunkLocalPosition::iter() {
+                if area[0].pixel(p).unwrap().physics == PhysicsType::Air {
+                    air.insert((
+                        i64::from(chunk.0) * i64::from(CHUNK_SIZE) + i64::from(p.x()),
+                        i64::from(chunk.1) * i64::from(CHUNK_SIZE) + i64::from(p.y()),
+                    ));
+                }
+            }
+        }
+        air
+    }
+
+    #[test]
+    fn tunnels_match_across_chunks() {
+        let seed = 0;
+        let (a, b) = ((4, 1), (5, 1));
+        let border_x = i64::from(b.0) * i64::from(CHUNK_SIZE);
+
+        let ab = carve(&[a, b], seed);
+        assert_eq!(ab, carve(&[b, a], seed));
+
+        // what the tunnels cover, ignoring chunks
+        let region_size = i32::from(carver().region_size);
+        let region = (a.0.div_euclid(region_size), a.1.div_euclid(region_size));
+        assert_eq!(
+            region,
+            (b.0.div_euclid(region_size), b.1.div_euclid(region_size))
+        );
+        let mut circles = vec![];
+        for rx in region.0 - 1..=region.0 + 1 {
+            for ry in region.1 - 1..=region.1 + 1 {
+                carver().tunnels_in_region(
+                    (rx, ry),
+                    seed,
+                    &test_chunk::registries(),
+                    |x, y, radius| circles.push((x, y, radius)),
+                );
+            }
+        }
+        let carved = |x: i64, y: i64| {
+            circles.iter().any(|&(cx, cy, r)| {
+                let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+                dx * dx + dy * dy <= r * r
+            })
+        };
+
+        let rows =
+            i64::from(a.1) * i64::from(CHUNK_SIZE)..i64::from(a.1 + 1) * i64::from(CHUNK_SIZE);
+        let mut crossing = 0;
+        for y in rows {
+            // the last column of `a` and the first of `b`
+            for x in [border_x - 1, border_x] {
+                assert_eq!(ab.contains(&(x, y)), carved(x, y), "at {x},{y}");
+            }
+            if ab.contains(&(border_x - 1, y)) && ab.contains(&(border_x, y)) {
+                crossing += 1;
+            }
+        }
+        assert!(crossing > 0, "no tunnel crosses the border");
+    }
+}
@@ -0,0 +1,332 @@
+use chunksystem::{ChunkKey, ChunkManager, ChunkQuery};
+use image::{Rgba, RgbaImage};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use specs::{Join, RunNow, WorldExt};
+
+use crate::game::common::{
+    hashmap_ext::HashMapExt,
+    registry::RegistryID,
+    world::{
+        self,
+        chunk_index::ChunkLocalPosition,
+        gen::{
+            biome::Biome,
+            populator::ChunkContext,
+            rng::{chunk_rng, feature_salt, named_salt},
+            structure::{StructureNode, UpdateStructureNodes},
+            GenBuffers, GenContext, WorldGenerator,
+        },
+        material::{color::Color, MaterialInstance, PhysicsType},
+        Chunk, ChunkState, CHUNK_AREA, CHUNK_SIZE,
+    },
+    Rect, Registries,
+};
+
+/// Extra chunks around the preview area that are fully generated,
+///   so structures near the edge can still place pieces
+const STRUCTURE_MARGIN: i32 = 2;
+
+/// Max number of times structure nodes are updated, in case a structure keeps growing
+const MAX_STRUCTURE_PASSES: u16 = 256;
+
+const SKY: Rgba<u8> = Rgba([0x10, 0x10, 0x18, 0xff]);
+const STRUCTURE_BOUNDS: Rgba<u8> = Rgba([0xff, 0x20, 0xff, 0xff]);
+
+/// Images of a generated area, one pixel per world pixel
+pub struct WorldgenPreview {
+    /// Pixel colors, with the background shown darker behind air
+    pub color: RgbaImage,
+    /// Each biome in a flat color, see [`biome_color`]
+    pub biomes: RgbaImage,
+    /// Outlines of every generated structure piece over a faded copy of `color`
+    pub structures: RgbaImage,
+    /// The biomes that appear in `biomes`, sorted by id
+    pub biome_legend: Vec<(RegistryID<Biome>, Rgba<u8>)>,
+}
+
+/// Runs `generator` including its populators, features, and structures over `area` (in chunks),
+///   without a [`ChunkHandler`](world::chunk_handler::ChunkHandler), renderer, or physics.
+#[profiling::function]
+pub fn generate_preview<C: Chunk + Send + Sync + 'static>(
+    generator: &dyn WorldGenerator<C>,
+    area: Rect<i32>,
+    seed: i32,
+    registries: &Registries,
+) -> WorldgenPreview {
+    let max_stage = i32::from(generator.max_gen_stage());
+    let populated_area = area.inflated(STRUCTURE_MARGIN);
+    // populating stage `n` needs the chunks within `n` to be at stage `n - 1`
+    let stage_area = |stage: i32| populated_area.inflated((stage + 1..=max_stage).sum());
+    let generated_area = stage_area(0);
+
+    let mut manager = ChunkManager::<C>::new();
+
+    {
+        profiling::scope!("generate");
+        let keys: Vec<ChunkKey> = chunks_in(generated_area).collect();
+        let generated: Vec<_> = keys
+            .into_par_iter()
+            .map(|(chunk_x, chunk_y)| {
+                let mut pixels = Box::new([(); CHUNK_AREA].map(|_| MaterialInstance::air()));
+                let mut colors = Box::new([Color::TRANSPARENT; CHUNK_AREA]);
+                let mut background = Box::new([(); CHUNK_AREA].map(|_| MaterialInstance::air()));
+                let mut background_colors = Box::new([Color::TRANSPARENT; CHUNK_AREA]);
+
+                generator.generate(
+                    (chunk_x, chunk_y),
+                    GenBuffers::new(
+                        &mut pixels,
+                        &mut colors,
+                        &mut background,
+                        &mut background_colors,
+                    ),
+                    GenContext { seed, registries },
+                );
+
+                let mut chunk = C::new_empty(chunk_x, chunk_y);
+                chunk.set_state(ChunkState::Generating(0));
+                chunk.set_pixels(pixels);
+                chunk.set_pixel_colors(colors);
+                chunk.set_background_pixels(background);
+                chunk.set_background_pixel_colors(background_colors);
+                chunk
+            })
+            .collect();
+
+        for chunk in generated {
+            manager.insert((chunk.chunk_x(), chunk.chunk_y()), chunk);
+        }
+    }
+
+    {
+        profiling::scope!("populate stage 0");
+        let pops = generator.populators();
+        let keys = manager.keys();
+        unsafe { manager.raw_mut().get_many_var_mut(&keys) }
+            .unwrap()
+            .into_par_iter()
+            .for_each(|chunk| {
+                pops.populate(0, &mut [&mut chunk.data], seed, registries);
+            });
+    }
+
+    let mut ecs = world::ecs();
+
+    // same order as `ChunkHandler`: every chunk in an area reaches a stage before any go further
+    for stage in 1..=max_stage {
+        profiling::scope!("populate stage");
+        for (chunk_x, chunk_y) in chunks_in(stage_area(stage)) {
+            let keys: Vec<ChunkKey> = (-stage..=stage)
+                .flat_map(|y| (-stage..=stage).map(move |x| (chunk_x + x, chunk_y + y)))
+                .collect();
+            let mut chunks: Vec<&mut C> = unsafe { manager.raw_mut().get_many_var_mut(&keys) }
+                .unwrap()
+                .into_iter()
+                .map(|c| &mut c.data)
+                .collect();
+
+            if stage == 1 {
+                let mut chunk_ctx = ChunkContext::<1, C>::new(&mut chunks).unwrap();
+                for feat in generator.features() {
+                    let mut rng = chunk_rng(seed, (chunk_x, chunk_y), feature_salt(feat.id()));
+                    feat.generate(&mut chunk_ctx, seed, &mut rng, registries, &mut ecs);
+                    ecs.maintain();
+                }
+            }
+
+            generator
+                .populators()
+                .populate(stage as u8, &mut chunks, seed, registries);
+
+            manager
+                .chunk_at_mut((chunk_x, chunk_y))
+                .unwrap()
+                .set_state(ChunkState::Generating(stage as u8));
+        }
+    }
+
+    // structure nodes only generate in chunks that are done
+    for key in chunks_in(populated_area) {
+        manager
+            .chunk_at_mut(key)
+            .unwrap()
+            .set_state(ChunkState::Cached);
+    }
+
+    {
+        profiling::scope!("structures");
+        let registries = std::sync::Arc::new(registries.clone());
+        let mut last_pending = None;
+        for _ in 0..MAX_STRUCTURE_PASSES {
+            UpdateStructureNodes {
+                chunk_handler: &mut manager,
+                registries: registries.clone(),
+            }
+            .run_now(&ecs);
+            ecs.maintain();
+
+            let nodes = ecs.read_storage::<StructureNode>();
+            let pending = (&nodes).join().filter(|n| n.generated.is_none()).count();
+            let total = (&nodes).join().count();
+            if last_pending == Some((pending, total)) {
+                break;
+            }
+            last_pending = Some((pending, total));
+        }
+    }
+
+    let px_rect = Rect::new_wh(
+        i64::from(area.left()) * i64::from(CHUNK_SIZE),
+        i64::from(area.top()) * i64::from(CHUNK_SIZE),
+        i64::from(area.width()) * i64::from(CHUNK_SIZE),
+        i64::from(area.height()) * i64::from(CHUNK_SIZE),
+    );
+    let width = px_rect.width() as u32;
+    let height = px_rect.height() as u32;
+
+    let mut color = RgbaImage::from_pixel(width, height, SKY);
+    let mut biomes = RgbaImage::new(width, height);
+    let mut biome_legend = vec![];
+
+    {
+        profiling::scope!("draw");
+        for (chunk_x, chunk_y) in chunks_in(area) {
+            let chunk = manager.chunk_at((chunk_x, chunk_y)).unwrap();
+            let ox = ((chunk_x - area.left()) * i32::from(CHUNK_SIZE)) as u32;
+            let oy = ((chunk_y - area.top()) * i32::from(CHUNK_SIZE)) as u32;
+
+            let chunk_biomes = registries.biomes.biome_block::<CHUNK_SIZE, CHUNK_SIZE>(
+                i64::from(chunk_x) * i64::from(CHUNK_SIZE),
+                i64::from(chunk_y) * i64::from(CHUNK_SIZE),
+                seed,
+            );
+
+            for p in ChunkLocalPosition::iter() {
+                let (x, y) = (ox + u32::from(p.x()), oy + u32::from(p.y()));
+
+                let fg = chunk.pixel(p).unwrap();
+                let c = if fg.physics == PhysicsType::Air {
+                    let bg = chunk.background(p).unwrap();
+                    (bg.physics != PhysicsType::Air).then(|| darken(bg.color, 0.4))
+                } else {
+                    Some(fg.color)
+                };
+                if let Some(c) = c {
+                    blend(color.get_pixel_mut(x, y), c);
+                }
+
+                let (biome_id, _) =
+                    chunk_biomes[usize::from(p.x()) + usize::from(p.y()) * usize::from(CHUNK_SIZE)];
+                let bc = biome_color(biome_id);
+                biomes.put_pixel(x, y, bc);
+                if !biome_legend.iter().any(|(id, _)| id == biome_id) {
+                    biome_legend.push((biome_id.clone(), bc));
+                }
+            }
+        }
+    }
+    biome_legend.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut structures = color.clone();
+    for p in structures.pixels_mut() {
+        *p = Rgba([p[0] / 2, p[1] / 2, p[2] / 2, 0xff]);
+    }
+    let nodes = ecs.read_storage::<StructureNode>();
+    for node in (&nodes).join() {
+        if let Some(Ok(gen)) = &node.generated {
+            let bounds = gen.bounds;
+            for x in bounds.left()..=bounds.right() {
+                put_world_pixel(&mut structures, &px_rect, x, bounds.top());
+                put_world_pixel(&mut structures, &px_rect, x, bounds.bottom());
+            }
+            for y in bounds.top()..=bounds.bottom() {
+                put_world_pixel(&mut structures, &px_rect, bounds.left(), y);
+                put_world_pixel(&mut structures, &px_rect, bounds.right(), y);
+            }
+        }
+    }
+
+    WorldgenPreview { color, biomes, structures, biome_legend }
+}
+
+/// A stable color for a biome, based only on its id
+pub fn biome_color(id: &RegistryID<Biome>) -> Rgba<u8> {
+    let [r, g, b, ..] = named_salt(&id.to_string()).to_le_bytes();
+    // keep it away from black so it doesn't look like sky
+    Rgba([r | 0x40, g | 0x40, b | 0x40, 0xff])
+}
+
+fn chunks_in(area: Rect<i32>) -> impl Iterator<Item = ChunkKey> {
+    area.range_tb()
+        .flat_map(move |y| area.range_lr().map(move |x| (x, y)))
+}
+
+fn put_world_pixel(img: &mut RgbaImage, px_rect: &Rect<i64>, x: i64, y: i64) {
+    if px_rect.range_lr().contains(&x) && px_rect.range_tb().contains(&y) {
+        img.put_pixel(
+            (x - px_rect.left()) as u32,
+            (y - px_rect.top()) as u32,
+            STRUCTURE_BOUNDS,
+        );
+    }
+}
+
+fn darken(c: Color, factor: f32) -> Color {
+    Color::rgba(
+        (f32::from(c.r) * factor) as u8,
+        (f32::from(c.g) * factor) as u8,
+        (f32::from(c.b) * factor) as u8,
+        c.a,
+    )
+}
+
+fn blend(dst: &mut Rgba<u8>, src: Color) {
+    let a = src.a_f32();
+    let mix = |d: u8, s: u8| (f32::from(d) * (1.0 - a) + f32::from(s) * a) as u8;
+    *dst = Rgba([
+        mix(dst[0], src.r),
+        mix(dst[1], src.g),
+        mix(dst[2], src.b),
+        0xff,
+    ]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        gen::surface::SurfaceGenerator,
+        test_chunk::{self, TestChunk},
+    };
+
+    /// FNV-1a of the color image. Generation changes are expected to change this,
+    ///   after checking the preview still looks right (`fs_main worldgen-preview --seed 1 --x 0 --y 0 --width 2 --height 1`)
+    const COLOR_HASH: u64 = 0x12d7_352a_6223_a47c;
+
+    fn hash(img: &RgbaImage) -> u64 {
+        img.as_raw().iter().fold(0xCBF2_9CE4_8422_2325, |h, b| {
+            (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01B3)
+        })
+    }
+
+    #[test]
+    fn preview_matches_hash() {
+        let registries = test_chunk::asset_registries();
+        let generator = SurfaceGenerator::<TestChunk>::new();
+        let area = Rect::new_wh(0, 0, 2, 1);
+
+        let preview = generate_preview(&generator, area, 1, &registries);
+        assert_eq!(preview.color.dimensions(), (200, 100));
+        assert_eq!(
+            hash(&preview.color),
+            hash(&generate_preview(&generator, area, 1, &registries).color)
+        );
+        assert_eq!(
+            hash(&preview.color),
+            COLOR_HASH,
+            "{:#x}",
+            hash(&preview.color)
+        );
+    }
+}
@@ -13,6 +13,7 @@ use crate::game::common::{
         },
         Position,
     },
+    Registries,
 };
 
 use super::{ConfiguredStructurePlaceContext, ConfiguredStructurePlacer, StructureType};
@@ -47,4 +48,12 @@ impl ConfiguredStructurePlacer for ConfiguredJigsawFeature {
             self.override_dir,
         );
     }
+
+    fn validate(&self, registries: &Registries) -> Vec<String> {
+        if registries.structure_pools.get(&self.start_pool).is_none() {
+            vec![format!("unknown start pool \"{}\"", self.start_pool)]
+        } else {
+            Vec::new()
+        }
+    }
 }
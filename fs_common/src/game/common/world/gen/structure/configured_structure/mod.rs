@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use crate::game::common::{registry::Registry, FileHelper};
+use crate::game::common::{registry::Registry, FileHelper, Registries};
 
 use self::jigsaw_structure::ConfiguredJigsawFeature;
 
@@ -17,6 +17,11 @@ pub struct ConfiguredStructurePlaceContext<'a> {
 
 pub trait ConfiguredStructurePlacer: Debug {
     fn place(&self, x: i64, y: i64, ctx: ConfiguredStructurePlaceContext);
+
+    /// Returns a description of each reference to a missing registry entry.
+    fn validate(&self, _registries: &Registries) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug)]
@@ -28,6 +33,10 @@ impl ConfiguredStructurePlacer for ConfiguredStructure {
     fn place(&self, x: i64, y: i64, ctx: ConfiguredStructurePlaceContext) {
         self.placer.place(x, y, ctx);
     }
+
+    fn validate(&self, registries: &Registries) -> Vec<String> {
+        self.placer.validate(registries)
+    }
 }
 
 impl ConfiguredStructure {
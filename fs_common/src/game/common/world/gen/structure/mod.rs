@@ -105,6 +105,15 @@ impl Direction {
         AngleDiff::Angle180
     }
 
+    #[must_use]
+    pub fn flipped(self, flip: Flip) -> Self {
+        match (flip, self) {
+            (Flip::Horizontal, Direction::Left | Direction::Right)
+            | (Flip::Vertical, Direction::Up | Direction::Down) => self.opposite(),
+            _ => self,
+        }
+    }
+
     pub fn vec(&self) -> (i8, i8) {
         match self {
             Direction::Up => (0, -1),
@@ -156,6 +165,28 @@ impl AngleDiff {
     }
 }
 
+/// Mirroring applied to a structure piece before it is rotated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flip {
+    None,
+    /// Mirrored left to right
+    Horizontal,
+    /// Mirrored top to bottom
+    Vertical,
+}
+
+impl Flip {
+    /// Mirrors a point in a `width` x `height` area
+    #[inline]
+    pub fn flip_point(&self, point: (i64, i64), width: i64, height: i64) -> (i64, i64) {
+        match self {
+            Self::None => point,
+            Self::Horizontal => (width - point.0, point.1),
+            Self::Vertical => (point.0, height - point.1),
+        }
+    }
+}
+
 impl Distribution<Direction> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
         match rng.gen_range(0..=3) {
@@ -277,6 +308,15 @@ impl<'a, H: FSChunkAccess + Send> System<'a> for UpdateStructureNodes<'a, H> {
 
         let mut to_add = vec![];
 
+        // every placed piece of every structure, so carving one piece doesn't cut into another structure
+        let mut placed_bounds = node_storage
+            .join()
+            .filter_map(|n| match &n.generated {
+                Some(Ok(gen)) => Some(gen.bounds),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
         for entity in all {
             let mut node = node_storage.remove(entity).unwrap();
             let mut pos = pos_storage.remove(entity).unwrap();
@@ -319,21 +359,23 @@ impl<'a, H: FSChunkAccess + Send> System<'a> for UpdateStructureNodes<'a, H> {
                 node.generated = Some(Err(()));
 
                 // try every structure in desired pool
-                let mut pool = self
+                let pool = self
                     .registries
                     .structure_pools
                     .get(&node.config.pool)
-                    .expect(format!("Missing structure pool {:?}", node.config.pool).as_str())
-                    .pool
-                    .clone();
+                    .expect(format!("Missing structure pool {:?}", node.config.pool).as_str());
+                let allow_flip = pool.allow_flip;
+                let mut pool = pool.pool.clone();
                 pool.shuffle(&mut node.rng);
 
                 // try placing normal pool
                 if let Some(mut children) = self.place(
                     &pool,
+                    allow_flip,
                     &pos,
                     &mut node,
                     &all_bounds,
+                    &placed_bounds,
                     &root_pos,
                     entity,
                     false,
@@ -341,20 +383,18 @@ impl<'a, H: FSChunkAccess + Send> System<'a> for UpdateStructureNodes<'a, H> {
                     to_add.append(&mut children);
                 } else if let Some(fallback_pool) = &node.config.fallback_pool {
                     // if normal pool failed, try placing fallback pool
-                    let mut fallback_pool = self
-                        .registries
-                        .structure_pools
-                        .get(fallback_pool)
-                        .unwrap()
-                        .pool
-                        .clone();
+                    let fallback_pool = self.registries.structure_pools.get(fallback_pool).unwrap();
+                    let allow_flip = fallback_pool.allow_flip;
+                    let mut fallback_pool = fallback_pool.pool.clone();
                     fallback_pool.shuffle(&mut node.rng);
 
                     if let Some(mut children) = self.place(
                         &fallback_pool,
+                        allow_flip,
                         &pos,
                         &mut node,
                         &all_bounds,
+                        &placed_bounds,
                         &root_pos,
                         entity,
                         true,
@@ -364,6 +404,10 @@ impl<'a, H: FSChunkAccess + Send> System<'a> for UpdateStructureNodes<'a, H> {
                 }
             }
 
+            if let Some(Ok(gen)) = &node.generated {
+                placed_bounds.push(gen.bounds);
+            }
+
             // log::trace!("add {entity:?}");
             node_storage.insert(entity, node).unwrap();
             pos_storage.insert(entity, pos).unwrap();
@@ -392,9 +436,11 @@ impl<H: FSChunkAccess + Send> UpdateStructureNodes<'_, H> {
     fn place(
         &mut self,
         pool: &[RegistryID<StructurePiece>],
+        allow_flip: bool,
         pos: &Position,
         node: &mut StructureNode,
         all_bounds: &[Rect<i64>],
+        placed_bounds: &[Rect<i64>],
         root_pos: &Position,
         entity: Entity,
         ignore_restrictions: bool,
@@ -404,20 +450,28 @@ impl<H: FSChunkAccess + Send> UpdateStructureNodes<'_, H> {
             .iter()
             .map(|k| self.registries.structure_pieces.get(k).unwrap())
         {
-            let mut opts = pool_structure.options((pos.x as i64, pos.y as i64), node.direction);
+            let mut opts =
+                pool_structure.options((pos.x as i64, pos.y as i64), node.direction, allow_flip);
             opts.shuffle(&mut node.rng);
 
             // try every connection in structure
             for o in opts {
                 let (bounds, children, place_fn) = o;
 
+                // the fallback pool is a last resort, so it ignores overlap and the piece's rules
                 let ok = ignore_restrictions
-                    || !all_bounds
+                    || (!all_bounds
                         .iter()
-                        .any(|r| r.inflated(-1).intersects(&bounds));
+                        .any(|r| r.inflated(-1).intersects(&bounds))
+                        && pool_structure
+                            .rules
+                            .check(bounds, all_bounds, self.chunk_handler));
 
                 if ok {
                     place_fn(pool_structure, self.chunk_handler).unwrap();
+                    pool_structure
+                        .rules
+                        .carve(bounds, placed_bounds, self.chunk_handler);
 
                     node.generated = Some(Ok(StructureNodeGenData { bounds }));
 
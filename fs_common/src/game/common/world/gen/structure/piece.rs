@@ -1,5 +1,3 @@
-use std::fs;
-
 use asefile::AsepriteFile;
 use image::{DynamicImage, GenericImageView};
 
@@ -7,8 +5,8 @@ use crate::game::common::{
     registry::{Registry, RegistryID},
     world::{
         chunk_access::FSChunkAccess,
-        gen::structure::AngleDiff,
-        material::{self, buf::MaterialBuf, color::Color, MaterialInstance, PhysicsType},
+        gen::structure::{AngleDiff, Flip},
+        material::{self, buf::MaterialBuf, color::Color, Material, MaterialInstance, PhysicsType},
     },
     FileHelper, Rect,
 };
@@ -19,6 +17,143 @@ use super::{pool::StructurePool, Direction};
 pub struct StructurePiece {
     pub buf: MaterialBuf,
     pub child_nodes: Vec<(StructureNodeLocalPlacement, StructureNodeConfig)>,
+    pub rules: PiecePlacementRules,
+}
+
+/// Conditions on the terrain a [`StructurePiece`] is placed into.
+///
+/// A piece that fails its rules is skipped like one that overlaps another piece,
+///   so the node tries the rest of its pool and then its `fallback_pool`.
+#[derive(Debug, Clone, Default)]
+pub struct PiecePlacementRules {
+    /// Fraction of the pixels in the piece's bounds that must already be solid
+    pub min_solid: f32,
+    /// The piece can't be placed if any of these are within its bounds (or its `carve_gap`)
+    pub forbidden_materials: Vec<RegistryID<Material>>,
+    /// If `true`, some air right outside the piece's bounds (and not part of another piece) is required
+    pub connect_to_cave_air: bool,
+    /// Width of the border around the piece that is cleared to air after it is placed, up to [`MAX_CARVE_GAP`]
+    pub carve_gap: u16,
+}
+
+/// Largest `carve_gap` that is actually carved, so a piece can't clear out a huge area around itself
+pub const MAX_CARVE_GAP: u16 = 8;
+
+impl PiecePlacementRules {
+    #[must_use]
+    pub fn min_solid(mut self, fraction: f32) -> Self {
+        self.min_solid = fraction;
+        self
+    }
+
+    #[must_use]
+    pub fn forbid(mut self, material: impl Into<RegistryID<Material>>) -> Self {
+        self.forbidden_materials.push(material.into());
+        self
+    }
+
+    #[must_use]
+    pub fn connect_to_cave_air(mut self) -> Self {
+        self.connect_to_cave_air = true;
+        self
+    }
+
+    #[must_use]
+    pub fn carve_gap(mut self, gap: u16) -> Self {
+        self.carve_gap = gap;
+        self
+    }
+
+    /// Checks the terrain at `bounds` before placing.
+    /// `other_bounds` are the bounds of the rest of the structure, which don't count as cave air.
+    /// Pixels that aren't loaded are treated as neither solid, forbidden, nor air.
+    pub fn check(
+        &self,
+        bounds: Rect<i64>,
+        other_bounds: &[Rect<i64>],
+        chunk_access: &dyn FSChunkAccess,
+    ) -> bool {
+        if self.min_solid > 0.0 || !self.forbidden_materials.is_empty() {
+            let mut solid = 0;
+            for y in bounds.range_tb() {
+                for x in bounds.range_lr() {
+                    if let Ok(m) = chunk_access.pixel(x, y) {
+                        if self.forbidden_materials.contains(&m.material_id) {
+                            return false;
+                        }
+                        if m.physics == PhysicsType::Solid {
+                            solid += 1;
+                        }
+                    }
+                }
+            }
+
+            let area = (bounds.width() * bounds.height()).max(1);
+            if (solid as f32 / area as f32) < self.min_solid {
+                return false;
+            }
+        }
+
+        if !self.forbidden_materials.is_empty()
+            && border(bounds, self.carve_width()).any(|(x, y)| {
+                chunk_access
+                    .pixel(x, y)
+                    .is_ok_and(|m| self.forbidden_materials.contains(&m.material_id))
+            })
+        {
+            return false;
+        }
+
+        if self.connect_to_cave_air
+            && !border(bounds, 1).any(|(x, y)| {
+                !other_bounds.iter().any(|r| r.contains_point((x, y)))
+                    && chunk_access
+                        .pixel(x, y)
+                        .is_ok_and(|m| m.physics == PhysicsType::Air)
+            })
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Clears the solid pixels in the `carve_gap` around `bounds` to air.
+    /// `other_bounds` should hold every placed piece (from any structure), which are left alone.
+    /// Liquids, sand, etc. in the gap are also left alone so carving doesn't cut off their surface.
+    pub fn carve(
+        &self,
+        bounds: Rect<i64>,
+        other_bounds: &[Rect<i64>],
+        chunk_access: &mut dyn FSChunkAccess,
+    ) {
+        for (x, y) in border(bounds, self.carve_width()) {
+            if other_bounds.iter().any(|r| r.contains_point((x, y))) {
+                continue;
+            }
+
+            // not loaded is fine, the gap is best effort
+            if chunk_access
+                .pixel(x, y)
+                .is_ok_and(|m| m.physics == PhysicsType::Solid)
+            {
+                let _ = chunk_access.set_pixel(x, y, MaterialInstance::air());
+            }
+        }
+    }
+
+    fn carve_width(&self) -> i64 {
+        i64::from(self.carve_gap.min(MAX_CARVE_GAP))
+    }
+}
+
+/// Every pixel within `width` outside of `bounds`
+fn border(bounds: Rect<i64>, width: i64) -> impl Iterator<Item = (i64, i64)> {
+    let outer = bounds.inflated(width);
+    outer
+        .range_tb()
+        .flat_map(move |y| outer.range_lr().map(move |x| (x, y)))
+        .filter(move |(x, y)| !(bounds.range_lr().contains(x) && bounds.range_tb().contains(y)))
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +163,23 @@ pub struct StructureNodeLocalPlacement {
     pub direction_out: Direction,
 }
 
+impl StructureNodeLocalPlacement {
+    /// Mirrors this placement inside a piece of size `width` x `height`
+    #[must_use]
+    pub fn flipped(&self, flip: Flip, width: u16, height: u16) -> Self {
+        let (x, y) = flip.flip_point(
+            (i64::from(self.x), i64::from(self.y)),
+            i64::from(width),
+            i64::from(height),
+        );
+        Self {
+            x: x as u32,
+            y: y as u32,
+            direction_out: self.direction_out.flipped(flip),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StructureNodeGlobalPlacement {
     pub x: i64,
@@ -76,11 +228,20 @@ impl StructureNodeConfig {
 type PlaceFn = dyn Fn(&StructurePiece, &mut dyn FSChunkAccess) -> Result<(), String>;
 
 impl StructurePiece {
+    #[must_use]
+    pub fn rules(mut self, rules: PiecePlacementRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Every way this piece can be attached to a node at `origin` facing `dir_in`.
+    /// If `allow_flip` is `true`, this also includes the horizontally and vertically mirrored piece.
     #[allow(clippy::type_complexity)]
     pub fn options(
         &self,
         origin: (i64, i64),
         dir_in: Direction,
+        allow_flip: bool,
     ) -> Vec<(
         Rect<i64>,
         Vec<(StructureNodeGlobalPlacement, StructureNodeConfig)>,
@@ -100,63 +261,83 @@ impl StructurePiece {
             )
         }
 
-        let mut opts = vec![];
-
-        for i in 0..self.child_nodes.len() {
-            let (placement, config) = &self.child_nodes[i];
+        let flips: &[Flip] = if allow_flip {
+            &[Flip::None, Flip::Horizontal, Flip::Vertical]
+        } else {
+            &[Flip::None]
+        };
 
-            if config
-                .block_in_dirs
-                .as_ref()
-                .map_or(false, |block| block.contains(&dir_in))
-            {
-                continue;
-            }
-
-            let ofs_x = i64::from(placement.x);
-            let ofs_y = i64::from(placement.y);
-            let src = Rect::new_wh(
-                origin.0 - ofs_x,
-                origin.1 - ofs_y,
-                i64::from(self.buf.width),
-                i64::from(self.buf.height),
-            );
-
-            let angle = placement.direction_out.angle(dir_in.opposite());
-
-            // log::debug!("{:?} {:?} {:?}", placement.direction_out, dir_in.opposite(), angle);
-
-            let bounds = rotated(src, origin, angle);
+        let mut opts = vec![];
 
-            let children = self
+        for &flip in flips {
+            let child_nodes: Vec<_> = self
                 .child_nodes
                 .iter()
-                .enumerate()
-                .filter(|(ci, _)| *ci != i)
-                .map(|(_, (ch_placement, config))| {
-                    let src_x = src.x1 + i64::from(ch_placement.x);
-                    let src_y = src.y1 + i64::from(ch_placement.y);
-                    let (dst_x, dst_y) = angle.rotate_point((src_x, src_y), origin);
+                .map(|(placement, config)| {
                     (
-                        StructureNodeGlobalPlacement {
-                            x: dst_x,
-                            y: dst_y,
-                            direction_out: ch_placement.direction_out.rotated(angle),
-                        },
-                        config.clone(),
+                        placement.flipped(flip, self.buf.width, self.buf.height),
+                        config,
                     )
                 })
                 .collect();
 
-            opts.push((
-                bounds,
-                children,
-                Box::new(move |st: &Self, chunk_handler: &mut dyn FSChunkAccess| {
-                    st.buf
-                        .rotated(angle)
-                        .paste(chunk_handler, bounds.left(), bounds.top())
-                }) as Box<PlaceFn>,
-            ));
+            for i in 0..child_nodes.len() {
+                let (placement, config) = &child_nodes[i];
+
+                if config
+                    .block_in_dirs
+                    .as_ref()
+                    .map_or(false, |block| block.contains(&dir_in))
+                {
+                    continue;
+                }
+
+                let ofs_x = i64::from(placement.x);
+                let ofs_y = i64::from(placement.y);
+                let src = Rect::new_wh(
+                    origin.0 - ofs_x,
+                    origin.1 - ofs_y,
+                    i64::from(self.buf.width),
+                    i64::from(self.buf.height),
+                );
+
+                let angle = placement.direction_out.angle(dir_in.opposite());
+
+                // log::debug!("{:?} {:?} {:?}", placement.direction_out, dir_in.opposite(), angle);
+
+                let bounds = rotated(src, origin, angle);
+
+                let children = child_nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(ci, _)| *ci != i)
+                    .map(|(_, (ch_placement, config))| {
+                        let src_x = src.x1 + i64::from(ch_placement.x);
+                        let src_y = src.y1 + i64::from(ch_placement.y);
+                        let (dst_x, dst_y) = angle.rotate_point((src_x, src_y), origin);
+                        (
+                            StructureNodeGlobalPlacement {
+                                x: dst_x,
+                                y: dst_y,
+                                direction_out: ch_placement.direction_out.rotated(angle),
+                            },
+                            (*config).clone(),
+                        )
+                    })
+                    .collect();
+
+                opts.push((
+                    bounds,
+                    children,
+                    Box::new(move |st: &Self, chunk_handler: &mut dyn FSChunkAccess| {
+                        st.buf.flipped(flip).rotated(angle).paste(
+                            chunk_handler,
+                            bounds.left(),
+                            bounds.top(),
+                        )
+                    }) as Box<PlaceFn>,
+                ));
+            }
         }
 
         opts
@@ -168,7 +349,7 @@ impl StructurePiece {
 pub type StructurePieceRegistry = Registry<StructurePiece>;
 
 #[allow(clippy::too_many_lines)]
-pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry {
+pub fn init_structure_pieces(file_helper: &FileHelper) -> Result<StructurePieceRegistry, String> {
     let mut registry = Registry::new();
 
     registry.register(
@@ -198,6 +379,13 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
                     StructureNodeConfig::new("hallways").fallback_pool("end_pieces"),
                 ),
             ],
+        )
+        .rules(
+            PiecePlacementRules::default()
+                .min_solid(0.5)
+                .forbid(material::WATER.clone())
+                .forbid(material::LAVA.clone())
+                .carve_gap(3),
         ),
     );
     registry.register(
@@ -219,6 +407,11 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
                         .fallback_pool("end_pieces"),
                 ),
             ],
+        )
+        .rules(
+            PiecePlacementRules::default()
+                .forbid(material::WATER.clone())
+                .forbid(material::LAVA.clone()),
         ),
     );
 
@@ -246,9 +439,7 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    let ase =
-        AsepriteFile::read_file(&file_helper.asset_path("data/structure/piece/corner/corner.ase"))
-            .unwrap();
+    let ase = read_ase(file_helper, "data/structure/piece/corner/corner.ase")?;
     registry.register(
         "b2",
         load_from_ase(
@@ -270,9 +461,7 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    let ase =
-        AsepriteFile::read_file(&file_helper.asset_path("data/structure/piece/stairs/stairs.ase"))
-            .unwrap();
+    let ase = read_ase(file_helper, "data/structure/piece/stairs/stairs.ase")?;
     registry.register(
         "stairs",
         load_from_ase(
@@ -296,8 +485,9 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    let data = &fs::read(file_helper.asset_path("data/structure/piece/end_carve.png")).unwrap();
-    let img = image::load_from_memory(data).unwrap();
+    let data = &file_helper.read_asset("data/structure/piece/end_carve.png")?;
+    let img = image::load_from_memory(data)
+        .map_err(|e| format!("Failed to load data/structure/piece/end_carve.png: {e}"))?;
     registry.register(
         "end_carve",
         make_test_structure_from_img(
@@ -309,10 +499,10 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    let ase = AsepriteFile::read_file(
-        &file_helper.asset_path("data/structure/piece/yellow_thing/yellow_thing.ase"),
-    )
-    .unwrap();
+    let ase = read_ase(
+        file_helper,
+        "data/structure/piece/yellow_thing/yellow_thing.ase",
+    )?;
     registry.register(
         "yellow_thing",
         load_from_ase(
@@ -324,9 +514,7 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    let ase =
-        AsepriteFile::read_file(&file_helper.asset_path("data/structure/piece/torch/torch.ase"))
-            .unwrap();
+    let ase = read_ase(file_helper, "data/structure/piece/torch/torch.ase")?;
     registry.register(
         "torch",
         load_from_ase(
@@ -338,9 +526,7 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    let ase =
-        AsepriteFile::read_file(&file_helper.asset_path("data/structure/piece/torch/torch2.ase"))
-            .unwrap();
+    let ase = read_ase(file_helper, "data/structure/piece/torch/torch2.ase")?;
     registry.register(
         "torch2",
         load_from_ase(
@@ -352,7 +538,12 @@ pub fn init_structure_pieces(file_helper: &FileHelper) -> StructurePieceRegistry
         ),
     );
 
-    registry
+    Ok(registry)
+}
+
+fn read_ase(file_helper: &FileHelper, path: &str) -> Result<AsepriteFile, String> {
+    AsepriteFile::read(&file_helper.read_asset(path)?[..])
+        .map_err(|e| format!("Failed to load {path}: {e}"))
 }
 
 fn make_test_structure(
@@ -386,7 +577,11 @@ fn make_test_structure(
         }
     }
 
-    StructurePiece { buf, child_nodes }
+    StructurePiece {
+        buf,
+        child_nodes,
+        rules: PiecePlacementRules::default(),
+    }
 }
 
 fn make_test_structure_from_img(
@@ -416,7 +611,11 @@ fn make_test_structure_from_img(
         }
     }
 
-    StructurePiece { buf, child_nodes }
+    StructurePiece {
+        buf,
+        child_nodes,
+        rules: PiecePlacementRules::default(),
+    }
 }
 
 fn load_from_ase(
@@ -424,5 +623,228 @@ fn load_from_ase(
     child_nodes: Vec<(StructureNodeLocalPlacement, StructureNodeConfig)>,
 ) -> StructurePiece {
     let buf = MaterialBuf::load_from_ase(ase);
-    StructurePiece { buf, child_nodes }
+    StructurePiece {
+        buf,
+        child_nodes,
+        rules: PiecePlacementRules::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::test_chunk::{self, stone, water};
+
+    fn filled(mat: &MaterialInstance) -> impl FSChunkAccess {
+        let mut ch = test_chunk::chunk_handler([(0, 0)]);
+        for y in 0..100 {
+            for x in 0..100 {
+                ch.set_pixel(x, y, mat.clone()).unwrap();
+            }
+        }
+        ch
+    }
+
+    #[test]
+    fn border_surrounds_bounds() {
+        let bounds = Rect::new_wh(10, 20, 4, 3);
+        let pixels = border(bounds, 2).collect::<Vec<_>>();
+
+        assert_eq!(pixels.len(), 8 * 7 - 4 * 3);
+        assert!(pixels.iter().all(|&(x, y)| {
+            (8..16).contains(&x)
+                && (18..25).contains(&y)
+                && !(bounds.range_lr().contains(&x) && bounds.range_tb().contains(&y))
+        }));
+        assert_eq!(border(bounds, 0).count(), 0);
+    }
+
+    #[test]
+    fn check_min_solid() {
+        let bounds = Rect::new_wh(40, 40, 10, 10);
+        let rules = PiecePlacementRules::default().min_solid(0.5);
+
+        assert!(rules.check(bounds, &[], &filled(&stone())));
+        assert!(!rules.check(bounds, &[], &filled(&MaterialInstance::air())));
+    }
+
+    #[test]
+    fn check_forbidden_in_gap() {
+        let bounds = Rect::new_wh(40, 40, 10, 10);
+        let rules = PiecePlacementRules::default()
+            .forbid(material::WATER.clone())
+            .carve_gap(3);
+
+        let mut ch = filled(&stone());
+        assert!(rules.check(bounds, &[], &ch));
+
+        ch.set_pixel(52, 45, water()).unwrap();
+        assert!(!rules.check(bounds, &[], &ch));
+
+        // outside the gap
+        let mut ch = filled(&stone());
+        ch.set_pixel(54, 45, water()).unwrap();
+        assert!(rules.check(bounds, &[], &ch));
+    }
+
+    #[test]
+    fn check_cave_air() {
+        let bounds = Rect::new_wh(40, 40, 10, 10);
+        let rules = PiecePlacementRules::default().connect_to_cave_air();
+
+        let mut ch = filled(&stone());
+        assert!(!rules.check(bounds, &[], &ch));
+
+        ch.set_pixel(50, 45, MaterialInstance::air()).unwrap();
+        assert!(rules.check(bounds, &[], &ch));
+
+        // air inside another piece isn't cave air
+        assert!(!rules.check(bounds, &[Rect::new_wh(50, 40, 10, 10)], &ch));
+    }
+
+    #[test]
+    fn carve_clears_solid_gap() {
+        let bounds = Rect::new_wh(40, 40, 10, 10);
+        let rules = PiecePlacementRules::default().carve_gap(2);
+
+        let mut ch = filled(&stone());
+        ch.set_pixel(38, 45, water()).unwrap();
+        let other = Rect::new_wh(45, 50, 10, 10);
+        rules.carve(bounds, &[other], &mut ch);
+
+        for (x, y) in border(bounds, 2) {
+            let m = ch.pixel(x, y).unwrap();
+            if (x, y) == (38, 45) {
+                assert_eq!(m.physics, PhysicsType::Liquid);
+            } else if other.contains_point((x, y)) {
+                assert_eq!(m.physics, PhysicsType::Solid);
+            } else {
+                assert_eq!(m.physics, PhysicsType::Air);
+            }
+        }
+
+        // the piece itself and everything past the gap is untouched
+        assert_eq!(ch.pixel(45, 45).unwrap().physics, PhysicsType::Solid);
+        assert_eq!(ch.pixel(37, 45).unwrap().physics, PhysicsType::Solid);
+        assert_eq!(ch.pixel(52, 45).unwrap().physics, PhysicsType::Solid);
+    }
+
+    #[test]
+    fn carve_gap_is_capped() {
+        let bounds = Rect::new_wh(40, 40, 10, 10);
+        let rules = PiecePlacementRules::default().carve_gap(u16::MAX);
+
+        let mut ch = filled(&stone());
+        rules.carve(bounds, &[], &mut ch);
+
+        let max = i64::from(MAX_CARVE_GAP);
+        assert_eq!(ch.pixel(40 - max, 45).unwrap().physics, PhysicsType::Air);
+        assert_eq!(
+            ch.pixel(40 - max - 1, 45).unwrap().physics,
+            PhysicsType::Solid
+        );
+        assert_eq!(ch.pixel(0, 0).unwrap().physics, PhysicsType::Solid);
+    }
+
+    /// A buffer where every pixel's color encodes its own position
+    fn marked_buf(width: u16, height: u16) -> MaterialBuf {
+        let mut buf = MaterialBuf::of_air(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut m = stone();
+                m.color = Color::rgb_const(x as u8, y as u8, 0);
+                buf.set(x, y, m);
+            }
+        }
+        buf
+    }
+
+    /// The two pixels on the inside of the piece that touch a node on the piece's edge
+    fn inside_pixels(
+        node: &StructureNodeLocalPlacement,
+        width: u16,
+        height: u16,
+    ) -> [(u16, u16); 2] {
+        let (x, y) = (node.x as u16, node.y as u16);
+        match node.direction_out {
+            Direction::Left => {
+                assert_eq!(x, 0);
+                [(0, y - 1), (0, y)]
+            },
+            Direction::Right => {
+                assert_eq!(x, width);
+                [(width - 1, y - 1), (width - 1, y)]
+            },
+            Direction::Up => {
+                assert_eq!(y, 0);
+                [(x - 1, 0), (x, 0)]
+            },
+            Direction::Down => {
+                assert_eq!(y, height);
+                [(x - 1, height - 1), (x, height - 1)]
+            },
+        }
+    }
+
+    #[test]
+    fn flip_round_trips() {
+        let (w, h) = (10, 6);
+        let buf = marked_buf(w, h);
+
+        for flip in [Flip::None, Flip::Horizontal, Flip::Vertical] {
+            assert!(buf.flipped(flip).flipped(flip) == buf);
+            for dir in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                assert_eq!(dir.flipped(flip).flipped(flip), dir);
+            }
+            for point in [(0, 0), (3, 2), (10, 6), (10, 0)] {
+                let flipped = flip.flip_point(point, i64::from(w), i64::from(h));
+                assert!((0..=i64::from(w)).contains(&flipped.0));
+                assert!((0..=i64::from(h)).contains(&flipped.1));
+                assert_eq!(flip.flip_point(flipped, i64::from(w), i64::from(h)), point);
+            }
+        }
+
+        assert!(buf.flipped(Flip::Horizontal) != buf);
+        assert!(buf.flipped(Flip::Vertical) != buf);
+    }
+
+    #[test]
+    fn flipped_nodes_match_flipped_pixels() {
+        let (w, h) = (10, 6);
+        let buf = marked_buf(w, h);
+        let nodes = [
+            StructureNodeLocalPlacement { x: 0, y: 2, direction_out: Direction::Left },
+            StructureNodeLocalPlacement { x: 10, y: 5, direction_out: Direction::Right },
+            StructureNodeLocalPlacement { x: 3, y: 0, direction_out: Direction::Up },
+            StructureNodeLocalPlacement { x: 8, y: 6, direction_out: Direction::Down },
+        ];
+
+        for flip in [Flip::None, Flip::Horizontal, Flip::Vertical] {
+            let flipped_buf = buf.flipped(flip);
+            for node in &nodes {
+                let flipped = node.flipped(flip, w, h);
+
+                // the flipped node is still on the edge it faces, next to the same pixels
+                let marks = |buf: &MaterialBuf, node| {
+                    let mut marks = inside_pixels(node, w, h).map(|(x, y)| {
+                        let c = buf.get(x, y).unwrap().color;
+                        (c.r, c.g)
+                    });
+                    marks.sort_unstable();
+                    marks
+                };
+                assert_eq!(
+                    marks(&buf, node),
+                    marks(&flipped_buf, &flipped),
+                    "{flip:?} {node:?}"
+                );
+            }
+        }
+    }
 }
@@ -1,5 +1,3 @@
-use std::fs;
-
 use serde::Deserialize;
 
 use crate::game::common::{
@@ -12,28 +10,33 @@ use super::piece::StructurePiece;
 #[derive(Debug, Deserialize)]
 pub struct StructurePool {
     pub pool: Vec<RegistryID<StructurePiece>>,
+    /// If `true`, pieces from this pool can also be placed mirrored horizontally or vertically
+    #[serde(default)]
+    pub allow_flip: bool,
 }
 
 impl From<Vec<&str>> for StructurePool {
     fn from(value: Vec<&str>) -> Self {
         Self {
             pool: value.into_iter().map(RegistryID::from).collect(),
+            allow_flip: false,
         }
     }
 }
 
 pub type StructurePoolRegistry = Registry<StructurePool>;
 
-pub fn init_structure_pools(file_helper: &FileHelper) -> StructurePoolRegistry {
+pub fn init_structure_pools(file_helper: &FileHelper) -> Result<StructurePoolRegistry, String> {
     let mut registry = Registry::new();
 
     for path in file_helper.files_in_dir_with_ext("data/structure/pool", "ron") {
         let name = path.file_stem().unwrap().to_string_lossy().to_string();
-        let bytes = fs::read(path).unwrap();
-        let set: StructurePool = ron::de::from_bytes(&bytes).unwrap();
+        let bytes = file_helper.read_asset(&path)?;
+        let pool: StructurePool = ron::de::from_bytes(&bytes)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
 
-        registry.register(name, set);
+        registry.register(name, pool);
     }
 
-    registry
+    Ok(registry)
 }
@@ -1,14 +1,14 @@
 use std::{
     collections::hash_map::DefaultHasher,
-    fs,
     hash::{Hash, Hasher},
 };
 
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use serde::Deserialize;
 
 use crate::game::common::{
     registry::{Registry, RegistryID},
+    world::gen::rng::chunk_rng,
     FileHelper, Registries,
 };
 
@@ -34,18 +34,19 @@ pub struct ExclusionZone {
 
 pub type StructureSetRegistry = Registry<StructureSet>;
 
-pub fn init_structure_sets(file_helper: &FileHelper) -> StructureSetRegistry {
+pub fn init_structure_sets(file_helper: &FileHelper) -> Result<StructureSetRegistry, String> {
     let mut registry = Registry::new();
 
     for path in file_helper.files_in_dir_with_ext("data/structure/set", "ron") {
         let name = path.file_stem().unwrap().to_string_lossy().to_string();
-        let bytes = fs::read(path).unwrap();
-        let set: StructureSet = ron::de::from_bytes(&bytes).unwrap();
+        let bytes = file_helper.read_asset(&path)?;
+        let set: StructureSet = ron::de::from_bytes(&bytes)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
 
         registry.register(name, set);
     }
 
-    registry
+    Ok(registry)
 }
 
 impl StructureSet {
@@ -136,7 +137,143 @@ impl StructureSet {
         (spacing_x * spacing + ofs_x, spacing_y * spacing + ofs_y)
     }
 
-    pub fn sample_structure(&self) -> &RegistryID<ConfiguredStructure> {
-        &self.structures[0]
+    /// The nearest chunk to `chunk` where this set will actually generate, taking `frequency` and `exclusion` into account.
+    /// Only searches up to `max_distance` chunks away.
+    pub fn locate(
+        &self,
+        chunk: (i32, i32),
+        world_seed: u64,
+        registries: &Registries,
+        max_distance: u32,
+    ) -> Option<(i32, i32)> {
+        self.locate_where(chunk, world_seed, registries, max_distance, |_| true)
+    }
+
+    /// Like [`StructureSet::locate`], but only finds starts where this set picks `structure`.
+    pub fn locate_structure(
+        &self,
+        structure: &RegistryID<ConfiguredStructure>,
+        chunk: (i32, i32),
+        world_seed: u64,
+        registries: &Registries,
+        max_distance: u32,
+    ) -> Option<(i32, i32)> {
+        if !self.structures.contains(structure) {
+            return None;
+        }
+
+        self.locate_where(chunk, world_seed, registries, max_distance, |start| {
+            self.structure_at(start, world_seed) == Some(structure)
+        })
+    }
+
+    fn locate_where(
+        &self,
+        chunk: (i32, i32),
+        world_seed: u64,
+        registries: &Registries,
+        max_distance: u32,
+        accept: impl Fn((i32, i32)) -> bool,
+    ) -> Option<(i32, i32)> {
+        let spacing = i32::from(self.spacing.max(1));
+        let cell_x = chunk.0.div_euclid(spacing);
+        let cell_y = chunk.1.div_euclid(spacing);
+        let max_dist_sq = i64::from(max_distance) * i64::from(max_distance);
+        let max_ring = i32::try_from(max_distance / spacing.unsigned_abs())
+            .unwrap_or(i32::MAX)
+            .saturating_add(1);
+
+        let mut best: Option<((i32, i32), i64)> = None;
+        for ring in 0..=max_ring {
+            // each spacing cell has exactly one possible start chunk
+            let cells = (-ring..=ring)
+                .flat_map(|dy| (-ring..=ring).map(move |dx| (dx, dy)))
+                .filter(|(dx, dy)| dx.abs().max(dy.abs()) == ring);
+            for (dx, dy) in cells {
+                let start = self.nearest_start_chunk(
+                    ((cell_x + dx) * spacing, (cell_y + dy) * spacing),
+                    world_seed,
+                );
+                let dist_sq = (i64::from(start.0) - i64::from(chunk.0)).pow(2)
+                    + (i64::from(start.1) - i64::from(chunk.1)).pow(2);
+
+                if dist_sq <= max_dist_sq
+                    && best.is_none_or(|(_, d)| dist_sq < d)
+                    && self.should_generate_at(start, world_seed, registries, true)
+                    && accept(start)
+                {
+                    best = Some((start, dist_sq));
+                }
+            }
+
+            // every cell in the next ring is at least this far away
+            let next_min_dist = i64::from(ring) * i64::from(spacing);
+            if best.is_some_and(|(_, d)| d <= next_min_dist * next_min_dist) {
+                break;
+            }
+        }
+
+        best.map(|(start, _)| start)
+    }
+
+    /// Which of `structures` this set generates at the start chunk `chunk`
+    pub fn structure_at(
+        &self,
+        chunk: (i32, i32),
+        world_seed: u64,
+    ) -> Option<&RegistryID<ConfiguredStructure>> {
+        self.structures
+            .choose(&mut chunk_rng(world_seed as i32, chunk, self.salt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_set() -> StructureSet {
+        StructureSet {
+            structures: vec!["a".into(), "b".into()],
+            frequency: 1.0,
+            exclusion: None,
+            spacing: 4,
+            separation: 1,
+            salt: 1,
+        }
+    }
+
+    #[test]
+    fn locate_every_structure_in_set() {
+        let registries = Registries::empty();
+        let set = test_set();
+
+        for id in &set.structures {
+            let found = set
+                .locate_structure(id, (0, 0), 7, &registries, 64)
+                .expect("structure in set wasn't found");
+            assert!(set.should_generate_at(found, 7, &registries, true));
+            assert_eq!(set.structure_at(found, 7), Some(id));
+        }
+
+        assert_eq!(
+            set.locate_structure(&"c".into(), (0, 0), 7, &registries, 64),
+            None
+        );
+    }
+
+    #[test]
+    fn structure_pick_is_deterministic() {
+        let set = test_set();
+        let other = test_set();
+
+        let picks = |set: &StructureSet, seed| {
+            (-8..8)
+                .flat_map(|y| (-8..8).map(move |x| (x, y)))
+                .map(|c| set.structure_at(c, seed).cloned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(picks(&set, 7), picks(&other, 7));
+        assert_ne!(picks(&set, 7), picks(&set, 8));
     }
 }
@@ -0,0 +1,514 @@
+use std::sync::Arc;
+
+use bracket_noise::prelude::{FastNoise, NoiseType};
+use chunksystem::ChunkKey;
+use rand::Rng;
+
+use crate::game::common::world::{
+    chunk_index::{ChunkLocalIndex, ChunkLocalPosition},
+    material::{
+        self,
+        placer::{self, pixel_random, MaterialPlacerSampler},
+        MaterialInstance, PhysicsType,
+    },
+    Chunk, CHUNK_AREA, CHUNK_SIZE,
+};
+
+use super::{
+    biome::{BiomeBlendMode, BiomeBlender, BiomeTransition},
+    feature::{
+        features::{
+            blob::Blob,
+            configured_structure::ConfiguredStructureFeature,
+            ore::{Ore, OreConfig},
+            test_structure::TestStructure,
+        },
+        placement_mods::{
+            chance::Chance, count::Count, material_match::MaterialMatch,
+            material_match_range::MaterialMatchRange, on_ground::OnGround,
+            random_offset::RandomOffset, spread::Spread,
+        },
+        PlacedFeature,
+    },
+    populator::{
+        aquifer::AquiferPopulator,
+        cave::CavePopulator,
+        nearby_replace::NearbyReplacePopulator,
+        place_above::PlaceAbovePopulator,
+        spawn::SpawnPopulator,
+        stalactite::StalactitePopulator,
+        worm::{WormCarverPopulator, WormSettings},
+    },
+    GenBuffers, GenContext, PopulatorList, WorldGenerator,
+};
+
+/// Terrain with a surface and open sky.
+///
+/// Below the surface is a layer of dirt, then the biome's base placer, then deep rock.
+#[derive(Debug)]
+pub struct SurfaceGenerator<C: Chunk> {
+    populators: PopulatorList<C>,
+    features: Vec<PlacedFeature<C>>,
+    /// Average world y of the surface
+    pub surface_level: i64,
+    /// Max distance the surface goes above or below `surface_level`
+    pub surface_amplitude: f32,
+    /// The surface is flat at `surface_level` around x = 0 and blends into the heightmap over this distance
+    pub spawn_flat_radius: f32,
+    /// Average thickness of the dirt layer, varies by up to half either way
+    pub dirt_depth: i64,
+    /// Depth below the surface where deep rock starts to appear
+    pub deep_rock_depth: i64,
+    /// Distance over which stone dithers into deep rock
+    pub deep_rock_transition: i64,
+    /// How the biomes' base placers mix at borders
+    pub biome_transition: BiomeTransition,
+}
+
+impl<C: Chunk + 'static> SurfaceGenerator<C> {
+    #[allow(clippy::too_many_lines)]
+    pub fn new() -> Self {
+        let mut populators = PopulatorList::new();
+
+        populators.add("cave", CavePopulator);
+        populators.add(
+            "worm_carver",
+            WormCarverPopulator::new(WormSettings {
+                frequency: 0.5,
+                radius: 3.0..7.0,
+                length: 200..600,
+            })
+            .biome_settings(
+                "dirt",
+                WormSettings {
+                    frequency: 0.25,
+                    radius: 2.0..4.0,
+                    length: 100..300,
+                },
+            ),
+        );
+        populators.add("aquifer", AquiferPopulator::new());
+        populators.add("spawn", SpawnPopulator);
+
+        populators.add(
+            "grass",
+            PlaceAbovePopulator {
+                add_surface_height: 1,
+                replace_surface_depth: 2,
+                searching_for: |m| m.material_id == *material::SMOOTH_DIRT,
+                replace: |_mat, x, y, seed, registries| {
+                    Some(
+                        registries
+                            .material_placers
+                            .get(&*placer::TEST_GRASS)
+                            .unwrap()
+                            .pixel(x, y, seed),
+                    )
+                },
+            },
+        );
+
+        populators.add(
+            "stalactite",
+            StalactitePopulator {
+                searching_for: |m| m.material_id == *material::SMOOTH_STONE,
+                replace: |mat, x, y, seed, registries| {
+                    if mat.material_id == *material::AIR {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::SMOOTH_STONE)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else {
+                        None
+                    }
+                },
+            },
+        );
+
+        populators.add(
+            "cobble_edges",
+            NearbyReplacePopulator {
+                radius: 6,
+                searching_for: |m| m.material_id == *material::AIR,
+                replace: |mat, x, y, seed, registries| {
+                    if mat.material_id == *material::SMOOTH_STONE
+                        || mat.material_id == *material::FADED_COBBLE_STONE
+                    {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::COBBLE_STONE)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else if mat.material_id == *material::SMOOTH_DIRT {
+                        Some(
+                            registries
+                                .material_placers
+                                .get(&*placer::COBBLE_DIRT)
+                                .unwrap()
+                                .pixel(x, y, seed),
+                        )
+                    } else {
+                        None
+                    }
+                },
+            },
+        );
+
+        let features = vec![
+            PlacedFeature::new(
+                "dirt_blob",
+                Blob::new(
+                    placer::SMOOTH_DIRT.clone(),
+                    Arc::new(|rng| rng.gen_range(16..64)),
+                    Arc::new(|m| m.physics == PhysicsType::Solid),
+                    false,
+                ),
+            )
+            .placement(Chance(0.25))
+            .placement(Count::range(0..=2))
+            .placement(RandomOffset::chunk())
+            .placement(MaterialMatch::material(material::SMOOTH_STONE.clone())),
+            PlacedFeature::new(
+                "iron_vein",
+                Ore::vein(
+                    OreConfig::new(
+                        placer::IRON_ORE.clone(),
+                        Arc::new(|rng| rng.gen_range(40..120)),
+                    )
+                    .depth(100..i64::MAX)
+                    .host_tag(material::TAG_STONE),
+                    4.0,
+                ),
+            )
+            .placement(Count::range(0..=2))
+            .placement(RandomOffset::chunk()),
+            PlacedFeature::new(
+                "iron_cluster",
+                Ore::cluster(
+                    OreConfig::new(
+                        placer::IRON_ORE.clone(),
+                        Arc::new(|rng| rng.gen_range(3..8)),
+                    )
+                    .host_tag(material::TAG_STONE)
+                    .host_tag(material::TAG_DIRT)
+                    .frequency(0.5),
+                    12,
+                ),
+            )
+            .placement(Count::range(0..=3))
+            .placement(RandomOffset::chunk()),
+            PlacedFeature::new(
+                "gold_deposit",
+                Ore::deposit(
+                    OreConfig::new(
+                        placer::GOLD_ORE.clone(),
+                        Arc::new(|rng| rng.gen_range(30..80)),
+                    )
+                    .depth(1200..i64::MAX)
+                    .host_tag(material::TAG_STONE)
+                    .frequency(0.1)
+                    .biome_frequency("main", 0.2),
+                    0.4,
+                ),
+            )
+            .placement(RandomOffset::chunk()),
+            PlacedFeature::new("torch", ConfiguredStructureFeature::new("torch".into()))
+                .placement(Chance(0.5))
+                .placement(Spread {
+                    count: 3,
+                    min_dist: 10.0,
+                    x: 2..i32::from(CHUNK_SIZE) - 2,
+                    y: 0..1,
+                })
+                .placement(RandomOffset::chunk_y())
+                .placement(OnGround { max_distance: Some(u32::from(CHUNK_SIZE / 2)) })
+                .placement(MaterialMatchRange {
+                    matcher: MaterialMatch::physics(PhysicsType::Air),
+                    x: 0..1,
+                    y: -10..0,
+                }),
+            PlacedFeature::new("structure_sets", TestStructure),
+        ];
+
+        Self {
+            populators,
+            features,
+            surface_level: 36,
+            surface_amplitude: 160.0,
+            spawn_flat_radius: 300.0,
+            dirt_depth: 40,
+            deep_rock_depth: 1500,
+            deep_rock_transition: 200,
+            biome_transition: BiomeTransition {
+                width: 48.0,
+                mode: BiomeBlendMode::Noise { frequency: 0.05 },
+            },
+        }
+    }
+}
+
+impl<C: Chunk> SurfaceGenerator<C> {
+    /// World y of the top of the ground in column `x`
+    pub fn surface_at(&self, x: i64, height_noise: &FastNoise) -> i64 {
+        let flatten = (x as f32 / self.spawn_flat_radius).abs().min(1.0);
+        // smoothstep
+        let flatten = flatten * flatten * (3.0 - 2.0 * flatten);
+
+        self.surface_level
+            + (height_noise.get_noise(x as f32, 0.0) * self.surface_amplitude * flatten) as i64
+    }
+}
+
+impl<C: Chunk + 'static> Default for SurfaceGenerator<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Chunk + Send + Sync> WorldGenerator<C> for SurfaceGenerator<C> {
+    #[allow(clippy::cast_lossless)]
+    #[profiling::function]
+    fn generate(&self, chunk_pos: ChunkKey, mut buf: GenBuffers, ctx: GenContext) {
+        let chunk_pixel_x = chunk_pos.0 as i64 * CHUNK_SIZE as i64;
+        let chunk_pixel_y = chunk_pos.1 as i64 * CHUNK_SIZE as i64;
+
+        let mut height_noise = FastNoise::seeded(ctx.seed as u64);
+        height_noise.set_noise_type(NoiseType::SimplexFractal);
+        height_noise.set_fractal_octaves(4);
+        height_noise.set_frequency(0.0015);
+
+        let mut dirt_noise = FastNoise::seeded(ctx.seed as u64 + 1);
+        dirt_noise.set_noise_type(NoiseType::Simplex);
+        dirt_noise.set_frequency(0.01);
+
+        let columns: Vec<(i64, i64)> = (0..CHUNK_SIZE)
+            .map(|x| {
+                let wx = chunk_pixel_x + x as i64;
+                let surface = self.surface_at(wx, &height_noise);
+                let dirt = (self.dirt_depth as f32
+                    * (1.0 + 0.5 * dirt_noise.get_noise(wx as f32, 0.0)))
+                    as i64;
+                (surface, dirt)
+            })
+            .collect();
+
+        // chunk is entirely sky, the buffers are already air
+        if columns
+            .iter()
+            .all(|(surface, _)| chunk_pixel_y + (CHUNK_SIZE as i64) <= *surface)
+        {
+            return;
+        }
+
+        // `biome_block` always returns Vec with size W*H, but this cannot be expressed until `generic_const_exprs` is stable
+        let Ok(biomes): Result<[_; CHUNK_AREA], _> = ctx
+            .registries
+            .biomes
+            .biome_weights_block::<CHUNK_SIZE, CHUNK_SIZE>(
+                chunk_pixel_x,
+                chunk_pixel_y,
+                ctx.seed,
+                self.biome_transition.width,
+            )
+            .try_into()
+        else {
+            unreachable!()
+        };
+        let blender = BiomeBlender::new(self.biome_transition, ctx.seed);
+
+        let dirt_placer = ctx
+            .registries
+            .material_placers
+            .get(&*placer::SMOOTH_DIRT)
+            .unwrap();
+        let deep_rock_placer = ctx
+            .registries
+            .material_placers
+            .get(&*placer::DEEP_ROCK)
+            .unwrap();
+
+        {
+            profiling::scope!("loop");
+            for p in ChunkLocalPosition::iter() {
+                let i: ChunkLocalIndex = p.into();
+                let wx = chunk_pixel_x + p.x() as i64;
+                let wy = chunk_pixel_y + p.y() as i64;
+                let (surface, dirt) = columns[p.x() as usize];
+                let depth = wy - surface;
+
+                let mat = if depth < 0 {
+                    MaterialInstance::air()
+                } else if depth < dirt {
+                    dirt_placer.pixel(wx, wy, ctx.seed)
+                } else if (depth - self.deep_rock_depth) as f32 / self.deep_rock_transition as f32
+                    > pixel_random(wx, wy, ctx.seed as u64)
+                {
+                    deep_rock_placer.pixel(wx, wy, ctx.seed)
+                } else {
+                    blender
+                        .pick(&biomes[i], wx, wy)
+                        .1
+                        .base_placer
+                        .as_placer(ctx.registries)
+                        .pixel(wx, wy, ctx.seed)
+                };
+
+                buf.set_pixel(i, mat.clone());
+                buf.set_bg(i, mat);
+            }
+        }
+    }
+
+    fn max_gen_stage(&self) -> u8 {
+        2
+    }
+
+    fn populators(&self) -> &PopulatorList<C> {
+        &self.populators
+    }
+
+    fn features(&self) -> &[PlacedFeature<C>] {
+        &self.features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ops::RangeInclusive;
+
+    use crate::game::common::{
+        world::{material::color::Color, test_chunk},
+        Registries,
+    };
+
+    /// Runs only [`SurfaceGenerator::generate`] (no populators or features) for `chunk`
+    #[allow(clippy::large_stack_arrays)]
+    fn generate(chunk: ChunkKey, seed: i32, registries: &Registries) -> Vec<MaterialInstance> {
+        let mut pixels = Box::new(std::array::from_fn(|_| MaterialInstance::air()));
+        let mut colors = Box::new([Color::TRANSPARENT; CHUNK_AREA]);
+        let mut background = Box::new(std::array::from_fn(|_| MaterialInstance::air()));
+        let mut background_colors = Box::new([Color::TRANSPARENT; CHUNK_AREA]);
+        SurfaceGenerator::<test_chunk::TestChunk>::new().generate(
+            chunk,
+            GenBuffers::new(
+                &mut pixels,
+                &mut colors,
+                &mut background,
+                &mut background_colors,
+            ),
+            GenContext { seed, registries },
+        );
+        pixels.to_vec()
+    }
+
+    /// Each pixel column of the chunks at `chunk_x`, `chunks_y`, from the top
+    fn columns(
+        chunk_x: i32,
+        chunks_y: RangeInclusive<i32>,
+        seed: i32,
+        registries: &Registries,
+    ) -> Vec<Vec<MaterialInstance>> {
+        let chunks: Vec<_> = chunks_y
+            .map(|chunk_y| generate((chunk_x, chunk_y), seed, registries))
+            .collect();
+        (0..usize::from(CHUNK_SIZE))
+            .map(|x| {
+                chunks
+                    .iter()
+                    .flat_map(|pixels| {
+                        (0..usize::from(CHUNK_SIZE))
+                            .map(move |y| pixels[x + y * usize::from(CHUNK_SIZE)].clone())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn first_solid(column: &[MaterialInstance]) -> usize {
+        column
+            .iter()
+            .position(|m| m.physics != PhysicsType::Air)
+            .unwrap()
+    }
+
+    #[test]
+    fn layers_near_spawn() {
+        let registries = test_chunk::asset_registries();
+        let generator = SurfaceGenerator::<test_chunk::TestChunk>::new();
+        let top = -i64::from(CHUNK_SIZE);
+        let columns = columns(0, -1..=1, 1, &registries);
+
+        // flat at spawn
+        assert_eq!(
+            i64::try_from(first_solid(&columns[0])).unwrap() + top,
+            generator.surface_level
+        );
+
+        // up to 1.5x `dirt_depth` of dirt under the surface, then the biome's base
+        let max_dirt = generator.dirt_depth as usize * 3 / 2;
+        for column in &columns {
+            let surface = first_solid(column);
+            assert_eq!(column[surface].material_id, *material::SMOOTH_DIRT);
+            let base = &column[surface + max_dirt + 1];
+            assert_eq!(base.physics, PhysicsType::Solid);
+            assert_ne!(base.material_id, *material::SMOOTH_DIRT);
+        }
+    }
+
+    #[test]
+    fn sky_and_deep_rock() {
+        let registries = test_chunk::asset_registries();
+
+        let sky = generate((3, -5), 1, &registries);
+        assert!(sky.iter().all(|m| m.physics == PhysicsType::Air));
+
+        // below `deep_rock_depth + deep_rock_transition` under the lowest possible surface
+        let deep = generate((3, 20), 1, &registries);
+        assert!(deep.iter().all(|m| m.material_id == *material::DEEP_ROCK));
+    }
+
+    #[test]
+    fn heightmap_away_from_spawn() {
+        let registries = test_chunk::asset_registries();
+        let generator = SurfaceGenerator::<test_chunk::TestChunk>::new();
+        let chunks_y = -3..=2;
+        assert!(
+            i64::from(*chunks_y.start()) * i64::from(CHUNK_SIZE)
+                < generator.surface_level - generator.surface_amplitude as i64
+        );
+        assert!(
+            i64::from(*chunks_y.end() + 1) * i64::from(CHUNK_SIZE)
+                > generator.surface_level + generator.surface_amplitude as i64
+        );
+
+        let surfaces: Vec<usize> = columns(50, chunks_y, 2, &registries)
+            .iter()
+            .map(|column| first_solid(column))
+            .collect();
+
+        // not flat like spawn, but continuous
+        assert!(surfaces.iter().any(|&y| y != surfaces[0]));
+        assert!(surfaces.windows(2).all(|w| w[0].abs_diff(w[1]) <= 3));
+    }
+
+    #[test]
+    fn deterministic_per_seed() {
+        let registries = test_chunk::asset_registries();
+
+        assert_eq!(
+            generate((7, 0), 1, &registries),
+            generate((7, 0), 1, &registries)
+        );
+        assert_ne!(
+            generate((7, 0), 1, &registries),
+            generate((7, 0), 2, &registries)
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::VecDeque;
+
+use ahash::AHashSet;
+use chunksystem::ChunkQuery;
+
+use crate::game::common::{world::CHUNK_SIZE, Rect, Registries};
+
+use super::{
+    chunk_access::FSChunkAccess,
+    chunk_handler::ChunkHandler,
+    material::{MaterialInstance, PhysicsType},
+    physics::{Physics, PHYSICS_SCALE},
+    pixel_to_chunk, pixel_to_chunk_pos,
+    rigidbody::FSRigidBody,
+    Chunk, ChunkState,
+};
+
+/// Finds solid terrain that is no longer connected to anything holding it up and turns it into rigidbodies.
+///
+/// Only runs around the [`Chunk::integrity_rect`]s, so it only costs anything after solid pixels are removed.
+/// A region counts as anchored if it is larger than `max_region_size`, touches a chunk that isn't active,
+///   or its materials' [`anchoring`](super::material::Material::anchoring) can hold it up.
+#[derive(Debug, Clone)]
+pub struct StructuralIntegrity {
+    /// Regions with more pixels than this are assumed to be anchored, limits how far each search goes
+    pub max_region_size: usize,
+}
+
+/// Pixels in world coordinates
+type Region = Vec<(i64, i64)>;
+
+enum Lookup<'a> {
+    /// Not loaded or not active, assumed to be solid ground
+    Unknown,
+    Solid(&'a MaterialInstance),
+    NotSolid,
+}
+
+impl StructuralIntegrity {
+    pub fn new() -> Self {
+        Self { max_region_size: 4096 }
+    }
+
+    #[profiling::function]
+    pub fn update<C: Chunk>(
+        &self,
+        chunk_handler: &mut ChunkHandler<C>,
+        physics: &mut Physics,
+        rigidbodies: &mut Vec<FSRigidBody>,
+        registries: &Registries,
+    ) {
+        let mut seeds = vec![];
+        for (key, chunk) in chunk_handler.manager.kv_iter_mut() {
+            let Some(rect) = chunk.integrity_rect() else {
+                continue;
+            };
+            chunk.set_integrity_rect(None);
+            if chunk.state() != ChunkState::Active {
+                continue;
+            }
+
+            let chunk_px_x = i64::from(key.0) * i64::from(CHUNK_SIZE);
+            let chunk_px_y = i64::from(key.1) * i64::from(CHUNK_SIZE);
+            let rect = rect.inflated(1);
+            for y in rect.range_tb() {
+                for x in rect.range_lr() {
+                    seeds.push((chunk_px_x + i64::from(x), chunk_px_y + i64::from(y)));
+                }
+            }
+        }
+
+        if seeds.is_empty() {
+            return;
+        }
+
+        let mut anchored = AHashSet::new();
+        let mut changed_chunks = AHashSet::new();
+        for seed in seeds {
+            if anchored.contains(&seed) || !matches!(lookup(chunk_handler, seed), Lookup::Solid(_))
+            {
+                continue;
+            }
+
+            match self.find_region(chunk_handler, seed, &anchored, registries) {
+                Ok(region) => {
+                    if Self::detach(chunk_handler, physics, rigidbodies, &region, registries) {
+                        changed_chunks
+                            .extend(region.iter().map(|&(x, y)| pixel_to_chunk_pos(x, y)));
+                    } else {
+                        anchored.extend(region);
+                    }
+                },
+                Err(region) => anchored.extend(region),
+            }
+        }
+
+        // the collision mesh is updated where the pixels were removed, but removing the region doesn't need another check
+        for key in changed_chunks {
+            if let Some(chunk) = chunk_handler.manager.chunk_at_mut(key) {
+                chunk.set_integrity_rect(None);
+            }
+        }
+    }
+
+    /// Flood fills the solid region containing `start`.
+    /// Returns `Err` with the pixels visited so far if the region is anchored.
+    fn find_region<C: Chunk>(
+        &self,
+        chunk_handler: &ChunkHandler<C>,
+        start: (i64, i64),
+        anchored: &AHashSet<(i64, i64)>,
+        registries: &Registries,
+    ) -> Result<Region, Region> {
+        let anchoring = |m: &MaterialInstance| {
+            registries
+                .materials
+                .get(&m.material_id)
+                .map_or(0.0, |m| m.anchoring)
+        };
+
+        let mut visited = AHashSet::new();
+        let mut queue = VecDeque::new();
+        let mut total_anchoring = 0.0;
+
+        visited.insert(start);
+        queue.push_back(start);
+        if let Lookup::Solid(m) = lookup(chunk_handler, start) {
+            total_anchoring += anchoring(m);
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            for n in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                if visited.contains(&n) {
+                    continue;
+                }
+
+                match lookup(chunk_handler, n) {
+                    Lookup::Unknown => return Err(visited.into_iter().collect()),
+                    Lookup::NotSolid => {},
+                    Lookup::Solid(m) => {
+                        if anchored.contains(&n) {
+                            return Err(visited.into_iter().collect());
+                        }
+
+                        total_anchoring += anchoring(m);
+                        visited.insert(n);
+                        queue.push_back(n);
+                    },
+                }
+            }
+
+            if visited.len() > self.max_region_size {
+                return Err(visited.into_iter().collect());
+            }
+        }
+
+        if total_anchoring >= visited.len() as f32 {
+            Err(visited.into_iter().collect())
+        } else {
+            Ok(visited.into_iter().collect())
+        }
+    }
+
+    /// Cuts `region` out of the world and turns it into rigidbodies.
+    /// Returns false if no bodies could be made, in which case the world is unchanged.
+    fn detach<C: Chunk>(
+        chunk_handler: &mut ChunkHandler<C>,
+        physics: &mut Physics,
+        rigidbodies: &mut Vec<FSRigidBody>,
+        region: &[(i64, i64)],
+        registries: &Registries,
+    ) -> bool {
+        let bounds = region
+            .iter()
+            .map(|&(x, y)| Rect::<i64>::new_wh(x, y, 1, 1))
+            .reduce(Rect::union)
+            .unwrap();
+        let (Ok(width), Ok(height)) = (
+            u16::try_from(bounds.width()),
+            u16::try_from(bounds.height()),
+        ) else {
+            return false;
+        };
+
+        let mut pixels = vec![MaterialInstance::air(); usize::from(width) * usize::from(height)];
+        for &(x, y) in region {
+            let i = (x - bounds.left()) as usize + (y - bounds.top()) as usize * usize::from(width);
+            pixels[i] = chunk_handler.pixel(x, y).unwrap().clone();
+        }
+
+        let bodies = match FSRigidBody::make_bodies(
+            &pixels,
+            width,
+            height,
+            physics,
+            (
+                bounds.left() as f32 / PHYSICS_SCALE,
+                bounds.top() as f32 / PHYSICS_SCALE,
+            ),
+            registries,
+        ) {
+            Ok(bodies) if !bodies.is_empty() => bodies,
+            _ => return false,
+        };
+        rigidbodies.extend(bodies);
+
+        for &(x, y) in region {
+            chunk_handler
+                .set_pixel(x, y, MaterialInstance::air())
+                .unwrap();
+        }
+
+        true
+    }
+}
+
+impl Default for StructuralIntegrity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lookup<C: Chunk>(chunk_handler: &ChunkHandler<C>, (x, y): (i64, i64)) -> Lookup<'_> {
+    let (key, local) = pixel_to_chunk(x, y);
+    let Some(chunk) = chunk_handler.manager.chunk_at(key) else {
+        return Lookup::Unknown;
+    };
+    if chunk.state() != ChunkState::Active {
+        return Lookup::Unknown;
+    }
+
+    match chunk.pixel(local) {
+        Ok(m) if m.physics == PhysicsType::Solid => Lookup::Solid(m),
+        Ok(_) => Lookup::NotSolid,
+        Err(_) => Lookup::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        material::{self, color::Color},
+        test_chunk::{self, TestChunk},
+    };
+
+    fn dirt() -> MaterialInstance {
+        material::SMOOTH_DIRT.instance(PhysicsType::Solid, Color::rgb(0x80, 0x50, 0x20))
+    }
+
+    fn deep_rock() -> MaterialInstance {
+        material::DEEP_ROCK.instance(PhysicsType::Solid, Color::rgb(0x30, 0x30, 0x30))
+    }
+
+    /// A beam at `y` 40..45 across chunk (0, 0), with chunk (-1, 0) loaded but inactive to its left
+    fn beam(mat: &MaterialInstance) -> ChunkHandler<TestChunk> {
+        let mut ch = test_chunk::chunk_handler([(-1, 0), (0, 0)]);
+        ch.manager
+            .chunk_at_mut((-1, 0))
+            .unwrap()
+            .set_state(ChunkState::Cached);
+        for y in 40..45 {
+            for x in 0..50 {
+                ch.set_pixel(x, y, mat.clone()).unwrap();
+            }
+        }
+        ch.manager
+            .chunk_at_mut((0, 0))
+            .unwrap()
+            .set_integrity_rect(None);
+        ch
+    }
+
+    /// Cuts the beam at `x` 20 and runs an update
+    fn cut(
+        integrity: &StructuralIntegrity,
+        ch: &mut ChunkHandler<TestChunk>,
+        registries: &Registries,
+    ) -> Vec<FSRigidBody> {
+        for y in 40..45 {
+            ch.set_pixel(20, y, MaterialInstance::air()).unwrap();
+        }
+
+        let mut physics = Physics::new();
+        let mut rigidbodies = vec![];
+        integrity.update(ch, &mut physics, &mut rigidbodies, registries);
+        rigidbodies
+    }
+
+    #[test]
+    fn floating_region_detaches() {
+        let registries = test_chunk::registries();
+        let mut ch = beam(&dirt());
+
+        let bodies = cut(&StructuralIntegrity::new(), &mut ch, &registries);
+
+        assert!(!bodies.is_empty());
+        for y in 40..45 {
+            for x in 21..50 {
+                assert_eq!(ch.pixel(x, y).unwrap().physics, PhysicsType::Air);
+            }
+        }
+        assert!(ch
+            .manager
+            .chunk_at((0, 0))
+            .unwrap()
+            .integrity_rect()
+            .is_none());
+    }
+
+    #[test]
+    fn region_touching_inactive_chunk_stays() {
+        let registries = test_chunk::registries();
+        let mut ch = beam(&dirt());
+
+        cut(&StructuralIntegrity::new(), &mut ch, &registries);
+
+        for y in 40..45 {
+            for x in 0..20 {
+                assert_eq!(ch.pixel(x, y).unwrap().physics, PhysicsType::Solid);
+            }
+        }
+    }
+
+    #[test]
+    fn large_region_stays() {
+        let registries = test_chunk::registries();
+        let mut ch = beam(&dirt());
+
+        let integrity = StructuralIntegrity { max_region_size: 100 };
+        let bodies = cut(&integrity, &mut ch, &registries);
+
+        assert!(bodies.is_empty());
+        assert_eq!(ch.pixel(30, 42).unwrap().physics, PhysicsType::Solid);
+    }
+
+    #[test]
+    fn anchoring_holds_region() {
+        let registries = test_chunk::registries();
+
+        // 145 pixels: 25 deep rock hold up 100 and the 120 stone 60
+        let mut ch = beam(&test_chunk::stone());
+        for y in 40..45 {
+            for x in 40..45 {
+                ch.set_pixel(x, y, deep_rock()).unwrap();
+            }
+        }
+        let bodies = cut(&StructuralIntegrity::new(), &mut ch, &registries);
+        assert!(bodies.is_empty());
+        assert_eq!(ch.pixel(30, 42).unwrap().physics, PhysicsType::Solid);
+
+        // but dirt can't hang off it
+        let mut ch = beam(&dirt());
+        for y in 40..45 {
+            for x in 40..45 {
+                ch.set_pixel(x, y, deep_rock()).unwrap();
+            }
+        }
+        let bodies = cut(&StructuralIntegrity::new(), &mut ch, &registries);
+        assert!(!bodies.is_empty());
+        assert_eq!(ch.pixel(30, 42).unwrap().physics, PhysicsType::Air);
+    }
+}
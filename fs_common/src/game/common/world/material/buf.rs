@@ -5,7 +5,10 @@ use asefile::AsepriteFile;
 use crate::game::common::{
     registry::RegistryID,
     world::{
-        chunk_access::FSChunkAccess, chunk_handler::ChunkHandler, gen::structure::AngleDiff, Chunk,
+        chunk_access::FSChunkAccess,
+        chunk_handler::ChunkHandler,
+        gen::structure::{AngleDiff, Flip},
+        Chunk,
     },
     Rect,
 };
@@ -235,6 +238,27 @@ impl MaterialBuf {
 
         new
     }
+
+    #[must_use]
+    pub fn flipped(&self, flip: Flip) -> Self {
+        let mut new = self.clone();
+        if flip == Flip::None {
+            return new;
+        }
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let (old_x, old_y) = match flip {
+                    Flip::None => (x, y),
+                    Flip::Horizontal => (self.width - x - 1, y),
+                    Flip::Vertical => (x, self.height - y - 1),
+                };
+                new.set(x, y, self.get(old_x, old_y).unwrap());
+            }
+        }
+
+        new
+    }
 }
 
 impl Debug for MaterialBuf {
@@ -21,9 +21,44 @@ pub enum PhysicsType {
 
 #[derive(Debug)]
 pub struct Material {
+    /// Translation key, see [`Lang`](crate::game::common::lang::Lang)
     pub display_name: String,
+    /// Groups of materials, eg. what ores can generate inside of
+    pub tags: Vec<String>,
+    /// How many pixels of a detached solid region each pixel of this material can hold up, including itself.
+    /// The region stays in place if the total is at least its pixel count, otherwise it falls.
+    /// `0.0` holds up nothing, `1.0` only holds up itself, and more than that lets weaker pixels hang off it.
+    pub anchoring: f32,
+    /// Impact a rigidbody containing this material can take before it breaks,
+    ///   as contact force per unit of the body's mass. The weakest material in a body is used.
+    pub fracture_strength: f32,
+    /// Mass per area of rigidbodies made of this material, or of this material as a liquid that rigidbodies float in.
+    /// Stone is `1.0`, a body floats if its average density is lower than the liquid's.
+    pub density: f32,
+    /// Friction of rigidbodies made of this material, see [`ColliderBuilder::friction`](rapier2d::prelude::ColliderBuilder::friction)
+    pub friction: f32,
+    /// Bounciness of rigidbodies made of this material, see [`ColliderBuilder::restitution`](rapier2d::prelude::ColliderBuilder::restitution)
+    pub restitution: f32,
+    /// How much this material slows down rigidbodies moving through it as sand or liquid.
+    /// Liquids also push bodies up by their `density`, see [`Submersion`](super::buoyancy::Submersion)
+    pub drag: f32,
+    /// Explosions need more than this much strength to break pixels of this material, see [`World::explode`](super::World::explode)
+    pub blast_resistance: f32,
+    /// If rigidbodies containing this material can turn back into terrain, see [`RigidBodySettling`](super::settle::RigidBodySettling)
+    pub settles: bool,
 }
 
+impl Material {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+pub const TAG_STONE: &str = "stone";
+pub const TAG_DIRT: &str = "dirt";
+pub const TAG_ORE: &str = "ore";
+pub const TAG_LIQUID: &str = "liquid";
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct MaterialInstance {
     pub material_id: RegistryID<Material>,
@@ -78,44 +113,251 @@ pub static FADED_COBBLE_STONE: Lazy<RegistryID<Material>> =
 pub static FADED_COBBLE_DIRT: Lazy<RegistryID<Material>> = Lazy::new(|| "faded_cobble_dirt".into());
 pub static SMOOTH_STONE: Lazy<RegistryID<Material>> = Lazy::new(|| "smooth_stone".into());
 pub static SMOOTH_DIRT: Lazy<RegistryID<Material>> = Lazy::new(|| "smooth_dirt".into());
+pub static DEEP_ROCK: Lazy<RegistryID<Material>> = Lazy::new(|| "deep_rock".into());
+pub static IRON_ORE: Lazy<RegistryID<Material>> = Lazy::new(|| "iron_ore".into());
+pub static GOLD_ORE: Lazy<RegistryID<Material>> = Lazy::new(|| "gold_ore".into());
+pub static WATER: Lazy<RegistryID<Material>> = Lazy::new(|| "water".into());
+pub static LAVA: Lazy<RegistryID<Material>> = Lazy::new(|| "lava".into());
 
 pub static STRUCTURE_VOID: Lazy<RegistryID<Material>> = Lazy::new(|| "structure_void".into());
 
 pub type MaterialRegistry = Registry<Material>;
 
+#[allow(clippy::too_many_lines)]
 pub fn init_material_types() -> MaterialRegistry {
     let mut registry = Registry::new();
 
-    registry.register(AIR.clone(), Material { display_name: "Air".to_string() });
-    registry.register(TEST.clone(), Material { display_name: "Test".to_string() });
+    registry.register(
+        AIR.clone(),
+        Material {
+            display_name: "material.air".to_string(),
+            tags: vec![],
+            anchoring: 0.0,
+            fracture_strength: f32::INFINITY,
+            density: 1.0,
+            friction: 0.5,
+            restitution: 0.0,
+            drag: 0.0,
+            blast_resistance: 0.0,
+            settles: true,
+        },
+    );
+    registry.register(
+        TEST.clone(),
+        Material {
+            display_name: "material.test".to_string(),
+            tags: vec![],
+            anchoring: 0.0,
+            fracture_strength: f32::INFINITY,
+            density: 1.0,
+            friction: 0.5,
+            restitution: 0.0,
+            drag: 0.1,
+            blast_resistance: 1.0,
+            // the sample objects are made of this, and should stay objects
+            settles: false,
+        },
+    );
     registry.register(
         COBBLE_STONE.clone(),
-        Material { display_name: "Cobblestone".to_string() },
+        Material {
+            display_name: "material.cobble_stone".to_string(),
+            tags: vec![TAG_STONE.to_owned()],
+            anchoring: 0.3,
+            fracture_strength: 300.0,
+            density: 1.0,
+            friction: 0.6,
+            restitution: 0.05,
+            drag: 0.1,
+            blast_resistance: 3.0,
+            settles: true,
+        },
     );
     registry.register(
         COBBLE_DIRT.clone(),
-        Material { display_name: "Cobbledirt".to_string() },
+        Material {
+            display_name: "material.cobble_dirt".to_string(),
+            tags: vec![TAG_DIRT.to_owned()],
+            anchoring: 0.0,
+            fracture_strength: 150.0,
+            density: 0.7,
+            friction: 0.8,
+            restitution: 0.0,
+            drag: 0.1,
+            blast_resistance: 1.5,
+            settles: true,
+        },
     );
     registry.register(
         FADED_COBBLE_STONE.clone(),
-        Material { display_name: "Faded Cobblestone".to_string() },
+        Material {
+            display_name: "material.faded_cobble_stone".to_string(),
+            tags: vec![TAG_STONE.to_owned()],
+            anchoring: 0.2,
+            fracture_strength: 300.0,
+            density: 1.0,
+            friction: 0.6,
+            restitution: 0.05,
+            drag: 0.1,
+            blast_resistance: 3.0,
+            settles: true,
+        },
     );
     registry.register(
         FADED_COBBLE_DIRT.clone(),
-        Material { display_name: "Faded Cobbledirt".to_string() },
+        Material {
+            display_name: "material.faded_cobble_dirt".to_string(),
+            tags: vec![TAG_DIRT.to_owned()],
+            anchoring: 0.0,
+            fracture_strength: 150.0,
+            density: 0.7,
+            friction: 0.8,
+            restitution: 0.0,
+            drag: 0.1,
+            blast_resistance: 1.5,
+            settles: true,
+        },
     );
     registry.register(
         SMOOTH_STONE.clone(),
-        Material { display_name: "Smoth Stone".to_string() },
+        Material {
+            display_name: "material.smooth_stone".to_string(),
+            tags: vec![TAG_STONE.to_owned()],
+            anchoring: 0.5,
+            fracture_strength: 400.0,
+            density: 1.0,
+            friction: 0.5,
+            restitution: 0.05,
+            drag: 0.1,
+            blast_resistance: 4.0,
+            settles: true,
+        },
     );
     registry.register(
         SMOOTH_DIRT.clone(),
-        Material { display_name: "Dirt".to_string() },
+        Material {
+            display_name: "material.smooth_dirt".to_string(),
+            tags: vec![TAG_DIRT.to_owned()],
+            anchoring: 0.0,
+            fracture_strength: 200.0,
+            density: 0.7,
+            friction: 0.8,
+            restitution: 0.0,
+            drag: 0.1,
+            blast_resistance: 2.0,
+            settles: true,
+        },
+    );
+    registry.register(
+        DEEP_ROCK.clone(),
+        Material {
+            display_name: "material.deep_rock".to_string(),
+            tags: vec![TAG_STONE.to_owned()],
+            anchoring: 4.0,
+            fracture_strength: 750.0,
+            density: 1.4,
+            friction: 0.6,
+            restitution: 0.05,
+            drag: 0.1,
+            blast_resistance: 12.0,
+            settles: true,
+        },
+    );
+    registry.register(
+        IRON_ORE.clone(),
+        Material {
+            display_name: "material.iron_ore".to_string(),
+            tags: vec![TAG_ORE.to_owned()],
+            anchoring: 0.5,
+            fracture_strength: 500.0,
+            density: 2.5,
+            friction: 0.4,
+            restitution: 0.1,
+            drag: 0.1,
+            blast_resistance: 6.0,
+            settles: true,
+        },
+    );
+    registry.register(
+        GOLD_ORE.clone(),
+        Material {
+            display_name: "material.gold_ore".to_string(),
+            tags: vec![TAG_ORE.to_owned()],
+            anchoring: 0.5,
+            fracture_strength: 350.0,
+            density: 3.0,
+            friction: 0.4,
+            restitution: 0.05,
+            drag: 0.1,
+            blast_resistance: 5.0,
+            settles: true,
+        },
+    );
+    registry.register(
+        WATER.clone(),
+        Material {
+            display_name: "material.water".to_string(),
+            tags: vec![TAG_LIQUID.to_owned()],
+            anchoring: 0.0,
+            fracture_strength: f32::INFINITY,
+            density: 0.5,
+            friction: 0.05,
+            restitution: 0.0,
+            drag: 1.0,
+            blast_resistance: 0.5,
+            settles: true,
+        },
+    );
+    registry.register(
+        LAVA.clone(),
+        Material {
+            display_name: "material.lava".to_string(),
+            tags: vec![TAG_LIQUID.to_owned()],
+            anchoring: 0.0,
+            fracture_strength: f32::INFINITY,
+            density: 1.2,
+            friction: 0.05,
+            restitution: 0.0,
+            drag: 4.0,
+            blast_resistance: 0.5,
+            settles: true,
+        },
     );
     registry.register(
         STRUCTURE_VOID.clone(),
-        Material { display_name: "Structure Void".to_string() },
+        Material {
+            display_name: "material.structure_void".to_string(),
+            tags: vec![],
+            anchoring: 0.0,
+            fracture_strength: f32::INFINITY,
+            density: 1.0,
+            friction: 0.5,
+            restitution: 0.0,
+            drag: 0.0,
+            blast_resistance: f32::INFINITY,
+            settles: true,
+        },
     );
 
     registry
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terrain_sinks_in_water() {
+        let materials = init_material_types();
+        let water = materials.get(&*WATER).unwrap();
+
+        for (id, material) in &materials {
+            if [TAG_STONE, TAG_DIRT, TAG_ORE]
+                .iter()
+                .any(|tag| material.has_tag(tag))
+            {
+                assert!(material.density > water.density, "{id} floats in water");
+            }
+        }
+    }
+}
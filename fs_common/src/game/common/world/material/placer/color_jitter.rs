@@ -0,0 +1,46 @@
+use crate::game::common::world::material::{color::Color, MaterialInstance};
+
+use super::{mix_seed, pixel_random, MaterialPlacerSampler};
+
+/// Randomly scales the brightness of each pixel by up to `amount` (eg. `0.1` = ±10%).
+pub struct ColorJitterPlacer<T> {
+    base: T,
+    amount: f32,
+    seed: u64,
+}
+
+impl<T> ColorJitterPlacer<T> {
+    pub fn new(base: T, amount: f32, seed: u64) -> Self {
+        Self { base, amount, seed }
+    }
+}
+
+impl<T: MaterialPlacerSampler> MaterialPlacerSampler for ColorJitterPlacer<T> {
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        let mut p = self.base.pixel(x, y, world_seed);
+        let f =
+            1.0 + (pixel_random(x, y, mix_seed(self.seed, world_seed)) * 2.0 - 1.0) * self.amount;
+        p.color = Color::rgba(
+            p.color.r_f32() * f,
+            p.color.g_f32() * f,
+            p.color.b_f32() * f,
+            p.color.a,
+        );
+        p
+    }
+}
+
+pub trait ColorJitterExt {
+    fn color_jitter(self, amount: f32, seed: u64) -> ColorJitterPlacer<Self>
+    where
+        Self: Sized;
+}
+
+impl<T: MaterialPlacerSampler> ColorJitterExt for T {
+    fn color_jitter(self, amount: f32, seed: u64) -> ColorJitterPlacer<Self>
+    where
+        Self: Sized,
+    {
+        ColorJitterPlacer::new(self, amount, seed)
+    }
+}
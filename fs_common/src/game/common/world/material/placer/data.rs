@@ -0,0 +1,344 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde::Deserialize;
+
+use crate::game::common::{registry::RegistryID, FileHelper};
+
+use super::{
+    color_jitter::ColorJitterPlacer, gradient::GradientPlacer, noise_blend::NoiseBlendPlacer,
+    weighted::WeightedPlacer, MaterialPlacer, MaterialPlacerMeta, MaterialPlacerRegistry,
+    MaterialPlacerSampler,
+};
+
+pub const MATERIAL_PLACER_DATA_DIR: &str = "data/material_placer";
+
+/// A material placer defined in `data/material_placer/<id>.ron`
+#[derive(Debug, Deserialize)]
+pub struct DataMaterialPlacer {
+    /// Translation key, see [`Lang`](crate::game::common::lang::Lang)
+    pub display_name: String,
+    pub sampler: SamplerConfig,
+}
+
+/// A sampler built from other placers, which can be nested.
+/// `seed`s are combined with the world seed, so they only need to differ between parts that shouldn't line up.
+#[derive(Debug, Deserialize)]
+pub enum SamplerConfig {
+    /// Another registered placer
+    Placer(RegistryID<MaterialPlacer>),
+    /// See [`WeightedPlacer`]
+    Weighted {
+        options: Vec<(f32, SamplerConfig)>,
+        #[serde(default)]
+        seed: u64,
+    },
+    /// See [`NoiseBlendPlacer`]
+    NoiseBlend {
+        a: Box<SamplerConfig>,
+        b: Box<SamplerConfig>,
+        frequency: f32,
+        #[serde(default)]
+        threshold: f32,
+        #[serde(default)]
+        seed: u64,
+    },
+    /// See [`GradientPlacer`]
+    Gradient {
+        stops: Vec<(i64, SamplerConfig)>,
+        #[serde(default)]
+        dither: bool,
+        #[serde(default)]
+        seed: u64,
+    },
+    /// See [`ColorJitterPlacer`]
+    ColorJitter {
+        base: Box<SamplerConfig>,
+        amount: f32,
+        #[serde(default)]
+        seed: u64,
+    },
+}
+
+impl SamplerConfig {
+    fn for_each_reference(&self, f: &mut impl FnMut(&RegistryID<MaterialPlacer>)) {
+        match self {
+            Self::Placer(id) => f(id),
+            Self::Weighted { options, .. } => {
+                for (_, config) in options {
+                    config.for_each_reference(f);
+                }
+            },
+            Self::NoiseBlend { a, b, .. } => {
+                a.for_each_reference(f);
+                b.for_each_reference(f);
+            },
+            Self::Gradient { stops, .. } => {
+                for (_, config) in stops {
+                    config.for_each_reference(f);
+                }
+            },
+            Self::ColorJitter { base, .. } => base.for_each_reference(f),
+        }
+    }
+
+    /// Every placer referenced must already be in `registry`.
+    fn build(
+        &self,
+        registry: &MaterialPlacerRegistry,
+    ) -> Result<Arc<dyn MaterialPlacerSampler + Send>, String> {
+        Ok(match self {
+            Self::Placer(id) => registry
+                .get(id)
+                .ok_or_else(|| format!("unknown material placer \"{id}\""))?
+                .sampler
+                .clone(),
+            Self::Weighted { options, seed } => Arc::new(WeightedPlacer::new(
+                options
+                    .iter()
+                    .map(|(weight, config)| Ok((*weight, config.build(registry)?)))
+                    .collect::<Result<_, String>>()?,
+                *seed,
+            )),
+            Self::NoiseBlend { a, b, frequency, threshold, seed } => {
+                Arc::new(NoiseBlendPlacer::new(
+                    a.build(registry)?,
+                    b.build(registry)?,
+                    *frequency,
+                    *threshold,
+                    *seed,
+                ))
+            },
+            Self::Gradient { stops, dither, seed } => Arc::new(GradientPlacer::new(
+                stops
+                    .iter()
+                    .map(|(y, config)| Ok((*y, config.build(registry)?)))
+                    .collect::<Result<_, String>>()?,
+                *dither,
+                *seed,
+            )),
+            Self::ColorJitter { base, amount, seed } => Arc::new(ColorJitterPlacer::new(
+                base.build(registry)?,
+                *amount,
+                *seed,
+            )),
+        })
+    }
+}
+
+/// Loads and registers every placer in [`MATERIAL_PLACER_DATA_DIR`].
+/// Data placers can reference each other, so they're registered once everything they reference is.
+pub fn register_data_placers(
+    registry: &mut MaterialPlacerRegistry,
+    file_helper: &FileHelper,
+) -> Result<(), String> {
+    let mut pending = HashMap::new();
+    for path in file_helper.files_in_dir_with_ext(MATERIAL_PLACER_DATA_DIR, "ron") {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let bytes = file_helper.read_asset(&path)?;
+        let placer: DataMaterialPlacer = ron::de::from_bytes(&bytes)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+
+        pending.insert(name, placer);
+    }
+
+    while !pending.is_empty() {
+        let ready: Vec<String> = pending
+            .iter()
+            .filter(|(_, placer)| {
+                let mut ready = true;
+                placer
+                    .sampler
+                    .for_each_reference(&mut |id| ready &= registry.get(id).is_some());
+                ready
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        if ready.is_empty() {
+            let mut errors = Vec::new();
+            for (name, placer) in &pending {
+                placer.sampler.for_each_reference(&mut |id| {
+                    if registry.get(id).is_none() {
+                        let reason = if pending.contains_key(&id.to_string()) {
+                            // either a cycle or depends on an unknown placer itself
+                            "unresolvable"
+                        } else {
+                            "unknown"
+                        };
+                        errors.push(format!(
+                            "material placer \"{name}\": {reason} material placer \"{id}\""
+                        ));
+                    }
+                });
+            }
+            errors.sort();
+            return Err(errors.join(", "));
+        }
+
+        for name in ready {
+            let placer = pending.remove(&name).unwrap();
+            let sampler = placer
+                .sampler
+                .build(registry)
+                .map_err(|e| format!("material placer \"{name}\": {e}"))?;
+            registry.register(
+                name,
+                MaterialPlacer {
+                    meta: MaterialPlacerMeta { display_name: placer.display_name },
+                    sampler,
+                },
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use crate::game::common::{
+        test_dir,
+        world::{material::MaterialInstance, test_chunk},
+    };
+
+    fn placer(sampler: MaterialInstance) -> MaterialPlacer {
+        MaterialPlacer {
+            meta: MaterialPlacerMeta { display_name: String::new() },
+            sampler: Arc::new(sampler),
+        }
+    }
+
+    /// Registers `files` as data placers on top of "stone" and "water"
+    fn register(name: &str, files: &[(&str, &str)]) -> Result<MaterialPlacerRegistry, String> {
+        let pack = test_dir(name);
+        let dir = pack.join(MATERIAL_PLACER_DATA_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        for (id, contents) in files {
+            fs::write(dir.join(format!("{id}.ron")), contents).unwrap();
+        }
+        let file_helper = FileHelper::new(pack.clone(), vec![pack.clone()]).unwrap();
+
+        let mut registry = MaterialPlacerRegistry::new();
+        registry.register("stone", placer(test_chunk::stone()));
+        registry.register("water", placer(test_chunk::water()));
+        let res = register_data_placers(&mut registry, &file_helper).map(|()| registry);
+
+        fs::remove_dir_all(pack).unwrap();
+        res
+    }
+
+    #[test]
+    fn parse_config() {
+        let placer: DataMaterialPlacer = ron::from_str(
+            r#"DataMaterialPlacer(
+                display_name: "test",
+                sampler: Gradient(
+                    stops: [(0, Placer("stone")), (10, ColorJitter(base: Placer("water"), amount: 0.1))],
+                ),
+            )"#,
+        )
+        .unwrap();
+
+        let SamplerConfig::Gradient { stops, dither, seed } = placer.sampler else {
+            panic!("expected a gradient, got {:?}", placer.sampler);
+        };
+        assert!(!dither);
+        assert_eq!(seed, 0);
+        assert!(matches!(&stops[0], (0, SamplerConfig::Placer(id)) if id.to_string() == "stone"));
+        assert!(matches!(
+            &stops[1],
+            (10, SamplerConfig::ColorJitter { base, .. }) if matches!(**base, SamplerConfig::Placer(_))
+        ));
+    }
+
+    #[test]
+    fn nested_references_resolve() {
+        let registry = register(
+            "data_placer_nested",
+            &[
+                // registered before `inner` alphabetically, so has to wait for it
+                ("a_outer", r#"DataMaterialPlacer(display_name: "", sampler: Placer("inner"))"#),
+                (
+                    "inner",
+                    r#"DataMaterialPlacer(display_name: "", sampler: Weighted(options: [(1.0, Placer("water"))]))"#,
+                ),
+            ],
+        )
+        .unwrap();
+
+        let water = test_chunk::water();
+        for id in ["a_outer", "inner"] {
+            let m = registry.get(id).unwrap().pixel(3, 4, 0);
+            assert_eq!(m.material_id, water.material_id);
+            assert_eq!(m.color, water.color);
+        }
+    }
+
+    #[test]
+    fn missing_reference_fails() {
+        let err = register(
+            "data_placer_missing",
+            &[(
+                "a",
+                r#"DataMaterialPlacer(display_name: "", sampler: Placer("nope"))"#,
+            )],
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err,
+            r#"material placer "a": unknown material placer "nope""#
+        );
+
+        let err = SamplerConfig::Placer("nope".into())
+            .build(&MaterialPlacerRegistry::new())
+            .err()
+            .unwrap();
+        assert_eq!(err, r#"unknown material placer "nope""#);
+    }
+
+    #[test]
+    fn reference_cycle_fails() {
+        let err = register(
+            "data_placer_cycle",
+            &[
+                ("a", r#"DataMaterialPlacer(display_name: "", sampler: Placer("b"))"#),
+                (
+                    "b",
+                    r#"DataMaterialPlacer(display_name: "", sampler: ColorJitter(base: Placer("a"), amount: 0.1))"#,
+                ),
+            ],
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err,
+            r#"material placer "a": unresolvable material placer "b", material placer "b": unresolvable material placer "a""#
+        );
+    }
+
+    #[test]
+    fn world_seed_changes_pattern() {
+        let registry = register(
+            "data_placer_seed",
+            &[(
+                "mix",
+                r#"DataMaterialPlacer(display_name: "", sampler: Weighted(options: [(1.0, Placer("stone")), (1.0, Placer("water"))], seed: 5))"#,
+            )],
+        )
+        .unwrap();
+        let mix = registry.get("mix").unwrap();
+        let pattern = |world_seed| {
+            (0..64)
+                .map(|x| mix.pixel(x, 0, world_seed).material_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(pattern(1), pattern(1));
+        assert_ne!(pattern(1), pattern(2));
+    }
+}
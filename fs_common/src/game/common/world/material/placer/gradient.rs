@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::game::common::world::material::MaterialInstance;
+
+use super::{mix_seed, pixel_random, MaterialPlacerSampler};
+
+/// Changes placer with depth (world y).
+/// Above the first stop uses the first placer, below the last stop uses the last placer.
+pub struct GradientPlacer {
+    /// Sorted by y
+    stops: Vec<(i64, Arc<dyn MaterialPlacerSampler + Send>)>,
+    /// If `true`, pixels between two stops randomly pick one of them weighted by distance,
+    /// otherwise the placer changes at the halfway point
+    dither: bool,
+    seed: u64,
+}
+
+impl GradientPlacer {
+    pub fn new(
+        mut stops: Vec<(i64, Arc<dyn MaterialPlacerSampler + Send>)>,
+        dither: bool,
+        seed: u64,
+    ) -> Self {
+        stops.sort_by_key(|(y, _)| *y);
+        Self { stops, dither, seed }
+    }
+}
+
+impl MaterialPlacerSampler for GradientPlacer {
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        let next = self.stops.partition_point(|(stop_y, _)| *stop_y <= y);
+
+        let placer = if next == 0 {
+            match self.stops.first() {
+                Some((_, placer)) => placer,
+                None => return MaterialInstance::air(),
+            }
+        } else if next == self.stops.len() {
+            &self.stops[next - 1].1
+        } else {
+            let (y1, above) = &self.stops[next - 1];
+            let (y2, below) = &self.stops[next];
+            let t = (y - y1) as f32 / (y2 - y1) as f32;
+            let cutoff = if self.dither {
+                pixel_random(x, y, mix_seed(self.seed, world_seed))
+            } else {
+                0.5
+            };
+
+            if t < cutoff {
+                above
+            } else {
+                below
+            }
+        };
+
+        placer.pixel(x, y, world_seed)
+    }
+}
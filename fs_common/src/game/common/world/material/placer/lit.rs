@@ -14,8 +14,8 @@ impl<T> LitPlacer<T> {
 }
 
 impl<T: MaterialPlacerSampler> MaterialPlacerSampler for LitPlacer<T> {
-    fn pixel(&self, x: i64, y: i64) -> MaterialInstance {
-        let mut p = self.base.pixel(x, y);
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        let mut p = self.base.pixel(x, y, world_seed);
         p.light = self.light;
         p
     }
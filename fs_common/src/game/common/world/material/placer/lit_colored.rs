@@ -14,8 +14,8 @@ impl<T> LitColoredPlacer<T> {
 }
 
 impl<T: MaterialPlacerSampler> MaterialPlacerSampler for LitColoredPlacer<T> {
-    fn pixel(&self, x: i64, y: i64) -> MaterialInstance {
-        let mut p = self.base.pixel(x, y);
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        let mut p = self.base.pixel(x, y, world_seed);
         p.light = [
             p.color.r_f32() * self.strength,
             p.color.g_f32() * self.strength,
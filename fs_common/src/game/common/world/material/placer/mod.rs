@@ -1,8 +1,13 @@
+pub mod color_jitter;
+pub mod data;
+pub mod gradient;
 pub mod lit;
 pub mod lit_colored;
+pub mod noise_blend;
 pub mod textured;
+pub mod weighted;
 
-use std::fs;
+use std::sync::Arc;
 
 use once_cell::sync::Lazy;
 
@@ -16,34 +21,63 @@ use self::{lit_colored::LitColoredExt, textured::TexturedPlacer};
 use super::{color::Color, Material, MaterialInstance, PhysicsType};
 
 pub trait MaterialPlacerSampler: Sync {
-    fn pixel(&self, x: i64, y: i64) -> MaterialInstance;
+    /// `world_seed` is for placers with random variation, so each world gets a different pattern
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance;
 }
 
 impl MaterialPlacerSampler for MaterialInstance {
-    fn pixel(&self, _x: i64, _y: i64) -> MaterialInstance {
+    fn pixel(&self, _x: i64, _y: i64, _world_seed: i32) -> MaterialInstance {
         self.clone()
     }
 }
 
 impl<F: Fn() -> MaterialInstance + Sync> MaterialPlacerSampler for F {
-    fn pixel(&self, _x: i64, _y: i64) -> MaterialInstance {
+    fn pixel(&self, _x: i64, _y: i64, _world_seed: i32) -> MaterialInstance {
         self()
     }
 }
 
+impl<T: MaterialPlacerSampler + Send + ?Sized> MaterialPlacerSampler for Arc<T> {
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        T::pixel(self, x, y, world_seed)
+    }
+}
+
+/// Deterministic random value in `0.0..1.0` for a pixel, for placers that vary per pixel.
+pub(crate) fn pixel_random(x: i64, y: i64, seed: u64) -> f32 {
+    // splitmix64 finalizer
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+
+    (h >> 40) as f32 / (1_u64 << 24) as f32
+}
+
+/// Combines a placer's own seed with the world seed.
+pub(crate) fn mix_seed(seed: u64, world_seed: i32) -> u64 {
+    seed ^ (i64::from(world_seed) as u64).wrapping_mul(0xD6E8_FEB8_6659_FD93)
+}
+
 #[derive(Debug)]
 pub struct MaterialPlacerMeta {
+    /// Translation key, see [`Lang`](crate::game::common::lang::Lang)
     pub display_name: String,
 }
 
 pub struct MaterialPlacer {
     pub meta: MaterialPlacerMeta,
-    pub sampler: Box<dyn MaterialPlacerSampler + Send>,
+    /// `Arc` so composite placers can share it
+    pub sampler: Arc<dyn MaterialPlacerSampler + Send>,
 }
 
 impl MaterialPlacerSampler for MaterialPlacer {
-    fn pixel(&self, x: i64, y: i64) -> MaterialInstance {
-        self.sampler.pixel(x, y)
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        self.sampler.pixel(x, y, world_seed)
     }
 }
 
@@ -60,6 +94,11 @@ pub static FADED_COBBLE_DIRT: Lazy<RegistryID<MaterialPlacer>> =
     Lazy::new(|| "faded_cobble_dirt".into());
 pub static SMOOTH_STONE: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "smooth_stone".into());
 pub static SMOOTH_DIRT: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "smooth_dirt".into());
+pub static DEEP_ROCK: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "deep_rock".into());
+pub static IRON_ORE: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "iron_ore".into());
+pub static GOLD_ORE: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "gold_ore".into());
+pub static WATER: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "water".into());
+pub static LAVA: Lazy<RegistryID<MaterialPlacer>> = Lazy::new(|| "lava".into());
 
 pub type MaterialPlacerRegistry = Registry<MaterialPlacer>;
 
@@ -72,55 +111,51 @@ impl MaterialPlacerRegistry {
         physics: PhysicsType,
         tex_name: impl AsRef<str>,
         file_helper: &FileHelper,
-    ) {
-        self.register(
-            id,
-            MaterialPlacer {
-                meta,
-                sampler: Box::new(TexturedPlacer::new(
-                    material_id,
-                    physics,
-                    &fs::read(
-                        file_helper
-                            .asset_path(format!("texture/material/{}.png", tex_name.as_ref())),
-                    )
-                    .unwrap(),
-                )),
-            },
-        );
+    ) -> Result<(), String> {
+        let path = format!("texture/material/{}.png", tex_name.as_ref());
+        let placer = TexturedPlacer::new(material_id, physics, &file_helper.read_asset(&path)?)
+            .map_err(|e| format!("Failed to load {path}: {e}"))?;
+        self.register(id, MaterialPlacer { meta, sampler: Arc::new(placer) });
+
+        Ok(())
     }
 }
 
 #[allow(clippy::too_many_lines)]
-pub fn init_material_placers(file_helper: &FileHelper) -> MaterialPlacerRegistry {
+pub fn init_material_placers(file_helper: &FileHelper) -> Result<MaterialPlacerRegistry, String> {
     let mut registry = Registry::new();
 
     registry.register(
         AIR_PLACER.clone(),
         MaterialPlacer {
-            meta: MaterialPlacerMeta { display_name: "Air".to_string() },
-            sampler: Box::new(MaterialInstance::air) as Box<dyn MaterialPlacerSampler + Send>,
+            meta: MaterialPlacerMeta { display_name: "material_placer.air".to_string() },
+            sampler: Arc::new(MaterialInstance::air) as Arc<dyn MaterialPlacerSampler + Send>,
         },
     );
 
     registry.register(
         TEST_PLACER_1.clone(),
         MaterialPlacer {
-            meta: MaterialPlacerMeta { display_name: "Test 1".to_string() },
-            sampler: Box::new(super::TEST.instance(PhysicsType::Solid, Color::GRAY)),
+            meta: MaterialPlacerMeta {
+                display_name: "material_placer.test_placer_1".to_string(),
+            },
+            sampler: Arc::new(super::TEST.instance(PhysicsType::Solid, Color::GRAY)),
         },
     );
 
     registry.register(
         TEST_PLACER_2.clone(),
         MaterialPlacer {
-            meta: MaterialPlacerMeta { display_name: "Test 2".to_string() },
-            sampler: Box::new(
+            meta: MaterialPlacerMeta {
+                display_name: "material_placer.test_placer_2".to_string(),
+            },
+            sampler: Arc::new(
                 TexturedPlacer::new(
                     super::TEST.clone(),
                     PhysicsType::Sand,
-                    &fs::read(file_helper.asset_path("texture/material/test.png")).unwrap(),
+                    &file_helper.read_asset("texture/material/test.png")?,
                 )
+                .map_err(|e| format!("Failed to load texture/material/test.png: {e}"))?
                 .lit_colored(0.5),
             ),
         },
@@ -129,63 +164,132 @@ pub fn init_material_placers(file_helper: &FileHelper) -> MaterialPlacerRegistry
     registry.register(
         TEST_GRASS.clone(),
         MaterialPlacer {
-            meta: MaterialPlacerMeta { display_name: "Test Grass".to_string() },
-            sampler: Box::new(super::TEST.instance(PhysicsType::Solid, Color::rgb(0, 127, 0))),
+            meta: MaterialPlacerMeta {
+                display_name: "material_placer.test_grass".to_string(),
+            },
+            sampler: Arc::new(super::TEST.instance(PhysicsType::Solid, Color::rgb(0, 127, 0))),
         },
     );
 
     registry.register_basic_textured(
         COBBLE_STONE.clone(),
         super::COBBLE_STONE.clone(),
-        MaterialPlacerMeta { display_name: "Cobblestone".to_string() },
+        MaterialPlacerMeta {
+            display_name: "material_placer.cobble_stone".to_string(),
+        },
         PhysicsType::Solid,
         "cobble_stone_128x",
         file_helper,
-    );
+    )?;
 
     registry.register_basic_textured(
         COBBLE_DIRT.clone(),
         super::COBBLE_DIRT.clone(),
-        MaterialPlacerMeta { display_name: "Cobbledirt".to_string() },
+        MaterialPlacerMeta {
+            display_name: "material_placer.cobble_dirt".to_string(),
+        },
         PhysicsType::Solid,
         "cobble_dirt_128x",
         file_helper,
-    );
+    )?;
 
     registry.register_basic_textured(
         FADED_COBBLE_STONE.clone(),
         super::FADED_COBBLE_STONE.clone(),
-        MaterialPlacerMeta { display_name: "Faded Cobblestone".to_string() },
+        MaterialPlacerMeta {
+            display_name: "material_placer.faded_cobble_stone".to_string(),
+        },
         PhysicsType::Solid,
         "flat_cobble_stone_128x",
         file_helper,
-    );
+    )?;
 
     registry.register_basic_textured(
         FADED_COBBLE_DIRT.clone(),
         super::FADED_COBBLE_DIRT.clone(),
-        MaterialPlacerMeta { display_name: "Faded Cobbledirt".to_string() },
+        MaterialPlacerMeta {
+            display_name: "material_placer.faded_cobble_dirt".to_string(),
+        },
         PhysicsType::Solid,
         "flat_cobble_dirt_128x",
         file_helper,
-    );
+    )?;
 
     registry.register_basic_textured(
         SMOOTH_STONE.clone(),
         super::SMOOTH_STONE.clone(),
-        MaterialPlacerMeta { display_name: "Smooth Stone".to_string() },
+        MaterialPlacerMeta {
+            display_name: "material_placer.smooth_stone".to_string(),
+        },
         PhysicsType::Solid,
         "smooth_stone_128x",
         file_helper,
-    );
+    )?;
 
     registry.register_basic_textured(
         SMOOTH_DIRT.clone(),
         super::SMOOTH_DIRT.clone(),
-        MaterialPlacerMeta { display_name: "Dirt".to_string() },
+        MaterialPlacerMeta {
+            display_name: "material_placer.smooth_dirt".to_string(),
+        },
         PhysicsType::Solid,
         "smooth_dirt_128x",
         file_helper,
+    )?;
+
+    registry.register_basic_textured(
+        DEEP_ROCK.clone(),
+        super::DEEP_ROCK.clone(),
+        MaterialPlacerMeta {
+            display_name: "material_placer.deep_rock".to_string(),
+        },
+        PhysicsType::Solid,
+        "deep_rock_128x",
+        file_helper,
+    )?;
+
+    registry.register_basic_textured(
+        IRON_ORE.clone(),
+        super::IRON_ORE.clone(),
+        MaterialPlacerMeta {
+            display_name: "material_placer.iron_ore".to_string(),
+        },
+        PhysicsType::Solid,
+        "iron",
+        file_helper,
+    )?;
+
+    registry.register_basic_textured(
+        GOLD_ORE.clone(),
+        super::GOLD_ORE.clone(),
+        MaterialPlacerMeta {
+            display_name: "material_placer.gold_ore".to_string(),
+        },
+        PhysicsType::Solid,
+        "gold",
+        file_helper,
+    )?;
+
+    registry.register(
+        WATER.clone(),
+        MaterialPlacer {
+            meta: MaterialPlacerMeta { display_name: "material_placer.water".to_string() },
+            sampler: Arc::new(
+                super::WATER.instance(PhysicsType::Liquid, Color::rgba(0x20, 0x60, 0xd0, 0xc0)),
+            ),
+        },
+    );
+
+    registry.register(
+        LAVA.clone(),
+        MaterialPlacer {
+            meta: MaterialPlacerMeta { display_name: "material_placer.lava".to_string() },
+            sampler: Arc::new(
+                super::LAVA
+                    .instance(PhysicsType::Liquid, Color::rgb(0xff, 0x60, 0x10))
+                    .with_light([1.0, 0.5, 0.1]),
+            ),
+        },
     );
 
     // test placers
@@ -194,19 +298,23 @@ pub fn init_material_placers(file_helper: &FileHelper) -> MaterialPlacerRegistry
         registry.register_basic_textured(
             format!("test_{color}"),
             super::COBBLE_STONE.clone(),
-            MaterialPlacerMeta { display_name: format!("Test {color}") },
+            MaterialPlacerMeta {
+                display_name: format!("material_placer.test_{color}"),
+            },
             PhysicsType::Solid,
             format!("test_{color}"),
             file_helper,
-        );
+        )
     };
-    register_test("red", &mut registry);
-    register_test("green", &mut registry);
-    register_test("blue", &mut registry);
-    register_test("magenta", &mut registry);
-    register_test("cyan", &mut registry);
-    register_test("yellow", &mut registry);
-    register_test("white", &mut registry);
-
-    registry
+    register_test("red", &mut registry)?;
+    register_test("green", &mut registry)?;
+    register_test("blue", &mut registry)?;
+    register_test("magenta", &mut registry)?;
+    register_test("cyan", &mut registry)?;
+    register_test("yellow", &mut registry)?;
+    register_test("white", &mut registry)?;
+
+    data::register_data_placers(&mut registry, file_helper)?;
+
+    Ok(registry)
 }
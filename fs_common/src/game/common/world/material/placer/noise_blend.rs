@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use bracket_noise::prelude::{FastNoise, NoiseType};
+
+use crate::game::common::world::material::MaterialInstance;
+
+use super::{pixel_random, MaterialPlacerSampler};
+
+const NOISE_OFFSET_RANGE: f32 = 100_000.0;
+
+/// Uses `a` where the noise is below `threshold` and `b` everywhere else.
+pub struct NoiseBlendPlacer {
+    a: Arc<dyn MaterialPlacerSampler + Send>,
+    b: Arc<dyn MaterialPlacerSampler + Send>,
+    noise: FastNoise,
+    threshold: f32,
+}
+
+impl NoiseBlendPlacer {
+    pub fn new(
+        a: Arc<dyn MaterialPlacerSampler + Send>,
+        b: Arc<dyn MaterialPlacerSampler + Send>,
+        frequency: f32,
+        threshold: f32,
+        seed: u64,
+    ) -> Self {
+        let mut noise = FastNoise::seeded(seed);
+        noise.set_noise_type(NoiseType::Simplex);
+        noise.set_frequency(frequency);
+        Self { a, b, noise, threshold }
+    }
+}
+
+impl MaterialPlacerSampler for NoiseBlendPlacer {
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        // the noise is built once for every world, so each world samples a different area of it
+        let offset =
+            |axis| (pixel_random(i64::from(world_seed), axis, 0) * NOISE_OFFSET_RANGE) as i64;
+        let (nx, ny) = (x.wrapping_add(offset(0)), y.wrapping_add(offset(1)));
+        if self.noise.get_noise(nx as f32, ny as f32) < self.threshold {
+            self.a.pixel(x, y, world_seed)
+        } else {
+            self.b.pixel(x, y, world_seed)
+        }
+    }
+}
@@ -14,14 +14,18 @@ pub struct TexturedPlacer {
 }
 
 impl TexturedPlacer {
-    pub fn new(material_id: RegistryID<Material>, physics: PhysicsType, image_buf: &[u8]) -> Self {
-        let image = image::load_from_memory(image_buf).unwrap();
-        Self { material_id, physics, image }
+    pub fn new(
+        material_id: RegistryID<Material>,
+        physics: PhysicsType,
+        image_buf: &[u8],
+    ) -> Result<Self, String> {
+        let image = image::load_from_memory(image_buf).map_err(|e| e.to_string())?;
+        Ok(Self { material_id, physics, image })
     }
 }
 
 impl MaterialPlacerSampler for TexturedPlacer {
-    fn pixel(&self, x: i64, y: i64) -> MaterialInstance {
+    fn pixel(&self, x: i64, y: i64, _world_seed: i32) -> MaterialInstance {
         let px = (x.rem_euclid(i64::from(self.image.width()))) as u32;
         let py = (y.rem_euclid(i64::from(self.image.height()))) as u32;
 
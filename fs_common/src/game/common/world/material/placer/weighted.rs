@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use crate::game::common::world::material::MaterialInstance;
+
+use super::{mix_seed, pixel_random, MaterialPlacerSampler};
+
+/// Picks one of several placers per pixel, with probability proportional to its weight.
+pub struct WeightedPlacer {
+    options: Vec<(f32, Arc<dyn MaterialPlacerSampler + Send>)>,
+    total_weight: f32,
+    seed: u64,
+}
+
+impl WeightedPlacer {
+    pub fn new(options: Vec<(f32, Arc<dyn MaterialPlacerSampler + Send>)>, seed: u64) -> Self {
+        let total_weight = options.iter().map(|(w, _)| w.max(0.0)).sum();
+        Self { options, total_weight, seed }
+    }
+}
+
+impl MaterialPlacerSampler for WeightedPlacer {
+    fn pixel(&self, x: i64, y: i64, world_seed: i32) -> MaterialInstance {
+        let mut pick = pixel_random(x, y, mix_seed(self.seed, world_seed)) * self.total_weight;
+        for (weight, placer) in &self.options {
+            let weight = weight.max(0.0);
+            if pick < weight {
+                return placer.pixel(x, y, world_seed);
+            }
+            pick -= weight;
+        }
+
+        // float error or no options
+        self.options
+            .last()
+            .map_or_else(MaterialInstance::air, |(_, placer)| {
+                placer.pixel(x, y, world_seed)
+            })
+    }
+}
@@ -1,6 +1,10 @@
 use mint::Point2;
 
-use super::material::{MaterialInstance, PhysicsType};
+use super::{
+    collision_tile_pos,
+    material::{MaterialInstance, PhysicsType},
+    CHUNK_SIZE, COLLISION_TILE_SIZE,
+};
 
 pub type Tri = ((f64, f64), (f64, f64), (f64, f64));
 
@@ -30,6 +34,36 @@ pub fn generate_mesh_only_simplified(
     generate_mesh_with_simplified(values, width, height).map(|t| t.1)
 }
 
+/// Generates the unsimplified and simplified mesh of one collision tile of a chunk's pixels, in chunk local pixels
+pub fn generate_tile_mesh(
+    pixels: &[MaterialInstance],
+    tile: usize,
+) -> Result<(Mesh, Mesh), String> {
+    let (x0, y0) = collision_tile_pos(tile);
+    let size = usize::from(COLLISION_TILE_SIZE);
+    let values: Vec<f64> = (0..size)
+        .flat_map(|y| {
+            let start = usize::from(x0) + (usize::from(y0) + y) * usize::from(CHUNK_SIZE);
+            pixels_to_valuemap(&pixels[start..start + size])
+        })
+        .collect();
+
+    let offset = |mut mesh: Mesh| {
+        for pt in mesh.iter_mut().flatten().flatten() {
+            pt[0] += f64::from(x0);
+            pt[1] += f64::from(y0);
+        }
+        mesh
+    };
+
+    generate_mesh_with_simplified(
+        &values,
+        u32::from(COLLISION_TILE_SIZE),
+        u32::from(COLLISION_TILE_SIZE),
+    )
+    .map(|(mesh, simplified)| (offset(mesh), offset(simplified)))
+}
+
 #[allow(dead_code)]
 pub fn generate_mesh_only_unsimplified(
     values: &[f64],
@@ -160,3 +194,69 @@ pub fn triangulate(mesh: &Mesh) -> Vec<Vec<Tri>> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        material::{self, color::Color},
+        CHUNK_AREA,
+    };
+
+    fn chunk_pixels(solid: impl Fn(usize, usize) -> bool) -> Vec<MaterialInstance> {
+        (0..CHUNK_AREA)
+            .map(|i| {
+                let (x, y) = (i % usize::from(CHUNK_SIZE), i / usize::from(CHUNK_SIZE));
+                if solid(x, y) {
+                    material::SMOOTH_STONE.instance(PhysicsType::Solid, Color::GRAY)
+                } else {
+                    MaterialInstance::air()
+                }
+            })
+            .collect()
+    }
+
+    /// Smallest and largest x and y of every point in `mesh`
+    fn bounds(mesh: &Mesh) -> Option<(f64, f64, f64, f64)> {
+        mesh.iter().flatten().flatten().fold(None, |b, pt| {
+            let (x1, y1, x2, y2) = b.unwrap_or((pt[0], pt[1], pt[0], pt[1]));
+            Some((x1.min(pt[0]), y1.min(pt[1]), x2.max(pt[0]), y2.max(pt[1])))
+        })
+    }
+
+    #[test]
+    fn tile_mesh_is_in_chunk_pixels() {
+        let pixels = chunk_pixels(|x, y| (25..30).contains(&x) && (25..30).contains(&y));
+
+        let (mesh, simplified) = generate_tile_mesh(&pixels, 6).unwrap();
+        // the outline goes through the centers of the edge pixels
+        for mesh in [&mesh, &simplified] {
+            let (x1, y1, x2, y2) = bounds(mesh).unwrap();
+            assert!((25.0..26.0).contains(&x1) && (25.0..26.0).contains(&y1));
+            assert!((29.0..30.0).contains(&x2) && (29.0..30.0).contains(&y2));
+        }
+
+        // other tiles don't see it
+        assert!(bounds(&generate_tile_mesh(&pixels, 0).unwrap().1).is_none());
+        assert!(bounds(&generate_tile_mesh(&pixels, 7).unwrap().1).is_none());
+    }
+
+    #[test]
+    fn tile_mesh_stops_at_tile_edges() {
+        // crosses the edge between tiles 0 and 1
+        let pixels = chunk_pixels(|x, y| (15..25).contains(&x) && y < 5);
+
+        // and both tiles' outlines meet at the same line, leaving no gap
+        let (left, _, edge, _) = bounds(&generate_tile_mesh(&pixels, 0).unwrap().1).unwrap();
+        assert!((15.0..16.0).contains(&left) && (19.0..=20.0).contains(&edge));
+        let (x1, _, x2, _) = bounds(&generate_tile_mesh(&pixels, 1).unwrap().1).unwrap();
+        assert!((x1 - edge).abs() < 1e-9 && (24.0..25.0).contains(&x2));
+
+        // last tile, at the chunk's corner
+        let pixels = chunk_pixels(|x, y| x >= 95 && y >= 95);
+        let (x1, y1, x2, y2) = bounds(&generate_tile_mesh(&pixels, 24).unwrap().1).unwrap();
+        assert!((95.0..96.0).contains(&x1) && (95.0..96.0).contains(&y1));
+        assert!((99.0..100.0).contains(&x2) && (99.0..100.0).contains(&y2));
+    }
+}
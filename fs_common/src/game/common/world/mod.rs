@@ -9,12 +9,21 @@ mod simulator;
 mod world;
 mod world_loading;
 
+pub mod buoyancy;
 pub mod chunk_access;
 pub mod chunk_data;
 pub mod chunk_handler;
 pub mod chunk_index;
+pub mod explosion;
+pub mod fracture;
 pub mod gen;
+pub mod integrity;
 pub mod physics;
+pub mod query;
+pub mod rope;
+pub mod settle;
+#[cfg(test)]
+pub(crate) mod test_chunk;
 pub mod tile_entity;
 
 pub use chunk::*;
@@ -1,9 +1,10 @@
 use rapier2d::{
+    crossbeam::channel::{self, Receiver},
     na::Vector2,
     prelude::{
-        BroadPhase, CCDSolver, ColliderSet, EventHandler, ImpulseJointSet, IntegrationParameters,
-        IslandManager, MultibodyJointSet, NarrowPhase, PhysicsHooks, PhysicsPipeline, RigidBody,
-        RigidBodyHandle, RigidBodySet,
+        BroadPhase, CCDSolver, ChannelEventCollector, ColliderSet, ContactForceEvent, EventHandler,
+        ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet, NarrowPhase,
+        PhysicsHooks, PhysicsPipeline, QueryPipeline, RigidBody, RigidBodyHandle, RigidBodySet,
     },
 };
 // use salva2d::{
@@ -29,8 +30,12 @@ pub struct Physics {
     pub ccd_solver: CCDSolver,
     pub impulse_joints: ImpulseJointSet,
     pub multibody_joints: MultibodyJointSet,
+    /// Scene queries against colliders, updated after each step
+    pub query_pipeline: QueryPipeline,
     pub hooks: Box<dyn PhysicsHooks>,
     pub event_handler: Box<dyn EventHandler>,
+    /// Contact forces reported by colliders with [`ActiveEvents::CONTACT_FORCE_EVENTS`](rapier2d::prelude::ActiveEvents::CONTACT_FORCE_EVENTS)
+    pub contact_force_events: Receiver<ContactForceEvent>,
 }
 
 impl Physics {
@@ -87,6 +92,9 @@ impl Physics {
         //     ColliderSampling::DynamicContactSampling,
         // );
 
+        let (collision_send, _) = channel::unbounded();
+        let (contact_force_send, contact_force_events) = channel::unbounded();
+
         #[allow(clippy::default_trait_access)]
         Self {
             // fluid_pipeline,
@@ -94,7 +102,11 @@ impl Physics {
             colliders,
             gravity: Vector2::y() * 3.0,
             hooks: Box::new(()),
-            event_handler: Box::new(()),
+            event_handler: Box::new(ChannelEventCollector::new(
+                collision_send,
+                contact_force_send,
+            )),
+            contact_force_events,
             integration_parameters: Default::default(),
             physics_pipeline: Default::default(),
             islands: Default::default(),
@@ -103,6 +115,7 @@ impl Physics {
             ccd_solver: Default::default(),
             impulse_joints: Default::default(),
             multibody_joints: Default::default(),
+            query_pipeline: Default::default(),
         }
     }
 
@@ -121,7 +134,7 @@ impl Physics {
             &mut self.impulse_joints,
             &mut self.multibody_joints,
             &mut self.ccd_solver,
-            None,
+            Some(&mut self.query_pipeline),
             &*self.hooks,
             &*self.event_handler,
         );
@@ -0,0 +1,725 @@
+use rapier2d::{
+    na::{Isometry2, Point2, Vector2},
+    prelude::{
+        Ball, Collider, ColliderHandle, Cuboid, InteractionGroups, QueryFilter as ColliderFilter,
+        Ray, RigidBodyHandle, Shape,
+    },
+};
+use specs::{Entities, Entity, Join, ReadStorage};
+
+use crate::game::common::{Rect, Registries};
+
+use super::{
+    chunk_access::FSChunkAccess,
+    entity::Hitbox,
+    material::{MaterialInstance, PhysicsType},
+    physics::PHYSICS_SCALE,
+    Chunk, CollisionFlags, Position, World,
+};
+
+/// Rays longer than this many pixels are cut short
+pub const MAX_RAY_LENGTH: f64 = 10_000.0;
+
+/// Areas wider or taller than this many pixels are shrunk around their center
+pub const MAX_AREA_SIZE: f64 = 1_000.0;
+
+type PixelFilter<'a> = Box<dyn Fn((i64, i64), &MaterialInstance) -> bool + 'a>;
+type BodyFilter<'a> = Box<dyn Fn(RigidBodyHandle) -> bool + 'a>;
+type EntityFilter<'a> = Box<dyn Fn(Entity) -> bool + 'a>;
+
+/// What [`World::cast_ray`] and [`World::query_area`] can hit.
+/// Each kind of target has its own filter, `None` skips that kind entirely.
+pub struct QueryFilter<'a> {
+    pub pixels: Option<PixelFilter<'a>>,
+    /// Rapier bodies with colliders, like [`FSRigidBody`](super::rigidbody::FSRigidBody)s and rope segments.
+    /// Terrain is only hit as pixels and entities only by their [`Hitbox`].
+    pub bodies: Option<BodyFilter<'a>>,
+    /// Entities with a [`Position`] and [`Hitbox`]
+    pub entities: Option<EntityFilter<'a>>,
+}
+
+impl<'a> QueryFilter<'a> {
+    /// Hits every pixel that isn't air or part of an object, every body, and every entity
+    pub fn new() -> Self {
+        Self {
+            pixels: Some(Box::new(|_, m| {
+                !matches!(m.physics, PhysicsType::Air | PhysicsType::Object)
+            })),
+            bodies: Some(Box::new(|_| true)),
+            entities: Some(Box::new(|_| true)),
+        }
+    }
+
+    /// Hits nothing, use the builder methods to add targets
+    pub fn empty() -> Self {
+        Self { pixels: None, bodies: None, entities: None }
+    }
+
+    #[must_use]
+    pub fn pixels(self, filter: impl Fn((i64, i64), &MaterialInstance) -> bool + 'a) -> Self {
+        Self { pixels: Some(Box::new(filter)), ..self }
+    }
+
+    /// Only hits pixels with one of these physics types
+    #[must_use]
+    pub fn physics(self, physics: &'a [PhysicsType]) -> Self {
+        self.pixels(move |_, m| physics.contains(&m.physics))
+    }
+
+    /// Only hits (non-air) pixels whose material has `tag`, see [`Material::has_tag`](super::material::Material::has_tag)
+    #[must_use]
+    pub fn tag(self, tag: &'a str, registries: &'a Registries) -> Self {
+        self.pixels(move |_, m| {
+            m.physics != PhysicsType::Air
+                && registries
+                    .materials
+                    .get(&m.material_id)
+                    .is_some_and(|mat| mat.has_tag(tag))
+        })
+    }
+
+    #[must_use]
+    pub fn bodies(self, filter: impl Fn(RigidBodyHandle) -> bool + 'a) -> Self {
+        Self { bodies: Some(Box::new(filter)), ..self }
+    }
+
+    #[must_use]
+    pub fn entities(self, filter: impl Fn(Entity) -> bool + 'a) -> Self {
+        Self { entities: Some(Box::new(filter)), ..self }
+    }
+
+    #[must_use]
+    pub fn without_pixels(self) -> Self {
+        Self { pixels: None, ..self }
+    }
+
+    #[must_use]
+    pub fn without_bodies(self) -> Self {
+        Self { bodies: None, ..self }
+    }
+
+    #[must_use]
+    pub fn without_entities(self) -> Self {
+        Self { entities: None, ..self }
+    }
+}
+
+impl Default for QueryFilter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryTarget<'w> {
+    Pixel {
+        x: i64,
+        y: i64,
+        material: &'w MaterialInstance,
+    },
+    Body {
+        body: RigidBodyHandle,
+        collider: ColliderHandle,
+    },
+    Entity(Entity),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RayHit<'w> {
+    pub target: QueryTarget<'w>,
+    /// Where the ray hit, in world pixels
+    pub point: (f64, f64),
+    /// Unit normal of the surface that was hit, or (0, 0) if the ray started inside of it
+    pub normal: (f64, f64),
+    /// Distance from the start of the ray, in pixels
+    pub distance: f64,
+}
+
+/// An area in world pixels for [`World::query_area`]
+#[derive(Debug, Clone, Copy)]
+pub enum QueryArea {
+    Circle { x: f64, y: f64, radius: f64 },
+    Rect(Rect<f64>),
+}
+
+impl QueryArea {
+    /// This area shrunk to at most [`MAX_AREA_SIZE`] across, or `None` if any of it isn't finite
+    fn clamped(self) -> Option<Self> {
+        match self {
+            Self::Circle { x, y, radius } => [x, y, radius]
+                .iter()
+                .all(|v| v.is_finite())
+                .then_some(Self::Circle { x, y, radius: radius.min(MAX_AREA_SIZE / 2.0) }),
+            Self::Rect(rect) => {
+                let sides = [rect.left(), rect.top(), rect.right(), rect.bottom()];
+                if !sides.iter().all(|v| v.is_finite()) {
+                    return None;
+                }
+
+                let clamp = |min: f64, max: f64| {
+                    let center = min / 2.0 + max / 2.0;
+                    let half = (max - min).min(MAX_AREA_SIZE) / 2.0;
+                    (center - half, center + half)
+                };
+                let (left, right) = clamp(rect.left(), rect.right());
+                let (top, bottom) = clamp(rect.top(), rect.bottom());
+                Some(Self::Rect(Rect::new(left, top, right, bottom)))
+            },
+        }
+    }
+
+    fn bounds(&self) -> Rect<f64> {
+        match *self {
+            Self::Circle { x, y, radius } => {
+                Rect::new(x - radius, y - radius, x + radius, y + radius)
+            },
+            Self::Rect(rect) => rect,
+        }
+    }
+
+    fn contains(&self, px: f64, py: f64) -> bool {
+        match *self {
+            Self::Circle { x, y, radius } => (px - x).hypot(py - y) <= radius,
+            Self::Rect(rect) => rect.contains_point((px, py)),
+        }
+    }
+
+    fn overlaps(&self, other: &Rect<f64>) -> bool {
+        match *self {
+            Self::Circle { x, y, radius } => {
+                let nearest_x = x.clamp(other.left(), other.right());
+                let nearest_y = y.clamp(other.top(), other.bottom());
+                (nearest_x - x).hypot(nearest_y - y) <= radius
+            },
+            Self::Rect(rect) => rect.intersects(other),
+        }
+    }
+
+    /// The area as a shape in physics units
+    fn shape(&self) -> (Isometry2<f32>, Box<dyn Shape>) {
+        match *self {
+            Self::Circle { x, y, radius } => (
+                Isometry2::translation(x as f32 / PHYSICS_SCALE, y as f32 / PHYSICS_SCALE),
+                Box::new(Ball::new(radius as f32 / PHYSICS_SCALE)),
+            ),
+            Self::Rect(rect) => (
+                Isometry2::translation(
+                    (rect.left() + rect.width() / 2.0) as f32 / PHYSICS_SCALE,
+                    (rect.top() + rect.height() / 2.0) as f32 / PHYSICS_SCALE,
+                ),
+                Box::new(Cuboid::new(Vector2::new(
+                    rect.width() as f32 / 2.0 / PHYSICS_SCALE,
+                    rect.height() as f32 / 2.0 / PHYSICS_SCALE,
+                ))),
+            ),
+        }
+    }
+}
+
+/// Everything found by [`World::query_area`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AreaHits<'w> {
+    pub pixels: Vec<((i64, i64), &'w MaterialInstance)>,
+    pub bodies: Vec<RigidBodyHandle>,
+    pub entities: Vec<Entity>,
+}
+
+impl<C: Chunk + Send + Sync + 'static> World<C> {
+    /// Finds the closest thing on the line from `from` to `to` (in world pixels) that `filter` accepts.
+    /// Only the first [`MAX_RAY_LENGTH`] pixels are checked, and nothing is hit if either end isn't finite.
+    ///
+    /// Bodies are checked as of the last physics step.
+    pub fn cast_ray(
+        &self,
+        from: (f64, f64),
+        to: (f64, f64),
+        filter: &QueryFilter,
+    ) -> Option<RayHit<'_>> {
+        if ![from.0, from.1, to.0, to.1].iter().all(|v| v.is_finite()) {
+            return None;
+        }
+
+        let from = Vector2::new(from.0, from.1);
+        let delta = Vector2::new(to.0, to.1) - from;
+        let mut max = delta.norm();
+        let dir = if max > f64::EPSILON {
+            delta / max
+        } else {
+            Vector2::x()
+        };
+        max = max.min(MAX_RAY_LENGTH);
+
+        let mut best = None;
+        if let Some(pixels) = &filter.pixels {
+            best = self.cast_ray_pixels(from, dir, max, pixels);
+        }
+        if let Some(hit) = &best {
+            max = hit.distance;
+        }
+
+        if let Some(bodies) = &filter.bodies {
+            if let Some(hit) = self.cast_ray_bodies(from, dir, max, bodies) {
+                max = hit.distance;
+                best = Some(hit);
+            }
+        }
+
+        if let Some(entities) = &filter.entities {
+            let (entity_storage, positions, hitboxes) =
+                self.ecs
+                    .system_data::<(Entities, ReadStorage<Position>, ReadStorage<Hitbox>)>();
+            for (entity, pos, hitbox) in (&entity_storage, &positions, &hitboxes).join() {
+                if !entities(entity) {
+                    continue;
+                }
+
+                if let Some((distance, normal)) =
+                    ray_rect(from, dir, max, &hitbox_rect(pos, hitbox))
+                {
+                    max = distance;
+                    let point = from + dir * distance;
+                    best = Some(RayHit {
+                        target: QueryTarget::Entity(entity),
+                        point: (point.x, point.y),
+                        normal,
+                        distance,
+                    });
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Lists every pixel, body and entity in `area` that `filter` accepts.
+    /// Pixels count if their center is inside, bodies and entities if they overlap it at all.
+    /// The area is limited to [`MAX_AREA_SIZE`] across, and nothing is found if it isn't finite.
+    pub fn query_area(&self, area: QueryArea, filter: &QueryFilter) -> AreaHits<'_> {
+        let mut hits = AreaHits::default();
+        let Some(area) = area.clamped() else {
+            return hits;
+        };
+
+        if let Some(pixels) = &filter.pixels {
+            let bounds = area.bounds();
+            for y in bounds.top().floor() as i64..=bounds.bottom().ceil() as i64 {
+                for x in bounds.left().floor() as i64..=bounds.right().ceil() as i64 {
+                    if !area.contains(x as f64 + 0.5, y as f64 + 0.5) {
+                        continue;
+                    }
+
+                    if let Ok(m) = self.chunk_handler.pixel(x, y) {
+                        if pixels((x, y), m) {
+                            hits.pixels.push(((x, y), m));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(bodies) = &filter.bodies {
+            let predicate = |_, c: &Collider| c.parent().is_some_and(bodies);
+            let (iso, shape) = area.shape();
+            self.physics.query_pipeline.intersections_with_shape(
+                &self.physics.bodies,
+                &self.physics.colliders,
+                &iso,
+                &*shape,
+                body_filter(&predicate),
+                |collider| {
+                    if let Some(body) = self.physics.colliders[collider].parent() {
+                        if !hits.bodies.contains(&body) {
+                            hits.bodies.push(body);
+                        }
+                    }
+                    true
+                },
+            );
+        }
+
+        if let Some(entities) = &filter.entities {
+            let (entity_storage, positions, hitboxes) =
+                self.ecs
+                    .system_data::<(Entities, ReadStorage<Position>, ReadStorage<Hitbox>)>();
+            for (entity, pos, hitbox) in (&entity_storage, &positions, &hitboxes).join() {
+                if entities(entity) && area.overlaps(&hitbox_rect(pos, hitbox)) {
+                    hits.entities.push(entity);
+                }
+            }
+        }
+
+        hits
+    }
+
+    /// Steps through pixels along the ray until `filter` accepts one
+    fn cast_ray_pixels(
+        &self,
+        from: Vector2<f64>,
+        dir: Vector2<f64>,
+        max: f64,
+        filter: &PixelFilter,
+    ) -> Option<RayHit<'_>> {
+        let check = |x: i64, y: i64| {
+            self.chunk_handler
+                .pixel(x, y)
+                .ok()
+                .filter(|m| filter((x, y), m))
+        };
+
+        let (mut x, mut y) = (from.x.floor() as i64, from.y.floor() as i64);
+        if let Some(material) = check(x, y) {
+            return Some(RayHit {
+                target: QueryTarget::Pixel { x, y, material },
+                point: (from.x, from.y),
+                normal: (0.0, 0.0),
+                distance: 0.0,
+            });
+        }
+
+        // distance along the ray to the next pixel edge on each axis, and between edges
+        let axis = |pos: f64, d: f64| {
+            if d > 0.0 {
+                (1, (pos.floor() + 1.0 - pos) / d, 1.0 / d)
+            } else if d < 0.0 {
+                (-1, (pos - pos.floor()) / -d, -1.0 / d)
+            } else {
+                (0, f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let (step_x, mut next_x, delta_x) = axis(from.x, dir.x);
+        let (step_y, mut next_y, delta_y) = axis(from.y, dir.y);
+
+        // every step crosses one pixel edge, so this many steps always reach `max`
+        let steps = (max * (dir.x.abs() + dir.y.abs())).ceil() as usize + 2;
+        for _ in 0..steps {
+            let (distance, normal) = if next_x < next_y {
+                x += step_x;
+                next_x += delta_x;
+                (next_x - delta_x, (-step_x as f64, 0.0))
+            } else {
+                y += step_y;
+                next_y += delta_y;
+                (next_y - delta_y, (0.0, -step_y as f64))
+            };
+
+            if distance > max {
+                return None;
+            }
+
+            if let Some(material) = check(x, y) {
+                let point = from + dir * distance;
+                return Some(RayHit {
+                    target: QueryTarget::Pixel { x, y, material },
+                    point: (point.x, point.y),
+                    normal,
+                    distance,
+                });
+            }
+        }
+
+        None
+    }
+
+    fn cast_ray_bodies(
+        &self,
+        from: Vector2<f64>,
+        dir: Vector2<f64>,
+        max: f64,
+        filter: &BodyFilter,
+    ) -> Option<RayHit<'_>> {
+        let predicate = |_, c: &Collider| c.parent().is_some_and(filter);
+        let ray = Ray::new(
+            Point2::new(from.x as f32, from.y as f32) / PHYSICS_SCALE,
+            Vector2::new(dir.x as f32, dir.y as f32),
+        );
+
+        let (collider, intersection) = self.physics.query_pipeline.cast_ray_and_get_normal(
+            &self.physics.bodies,
+            &self.physics.colliders,
+            &ray,
+            max as f32 / PHYSICS_SCALE,
+            true,
+            body_filter(&predicate),
+        )?;
+
+        let distance = f64::from(intersection.toi * PHYSICS_SCALE);
+        let point = from + dir * distance;
+        Some(RayHit {
+            target: QueryTarget::Body {
+                body: self.physics.colliders[collider].parent()?,
+                collider,
+            },
+            point: (point.x, point.y),
+            normal: (
+                f64::from(intersection.normal.x),
+                f64::from(intersection.normal.y),
+            ),
+            distance,
+        })
+    }
+}
+
+/// Only colliders of bodies that aren't terrain or entities
+fn body_filter(predicate: &dyn Fn(ColliderHandle, &Collider) -> bool) -> ColliderFilter<'_> {
+    ColliderFilter {
+        groups: Some(InteractionGroups::new(
+            CollisionFlags::all().bits().into(),
+            (CollisionFlags::RIGIDBODY | CollisionFlags::ROPE)
+                .bits()
+                .into(),
+        )),
+        predicate: Some(predicate),
+        ..ColliderFilter::default()
+    }
+}
+
+fn hitbox_rect(pos: &Position, hitbox: &Hitbox) -> Rect<f64> {
+    Rect::new(
+        pos.x + f64::from(hitbox.x1),
+        pos.y + f64::from(hitbox.y1),
+        pos.x + f64::from(hitbox.x2),
+        pos.y + f64::from(hitbox.y2),
+    )
+}
+
+/// Distance along the ray to where it enters `rect` and the normal of that side
+fn ray_rect(
+    from: Vector2<f64>,
+    dir: Vector2<f64>,
+    max: f64,
+    rect: &Rect<f64>,
+) -> Option<(f64, (f64, f64))> {
+    if rect.contains_point((from.x, from.y)) {
+        return Some((0.0, (0.0, 0.0)));
+    }
+
+    let mut enter = 0.0;
+    let mut exit = max;
+    let mut normal = (0.0, 0.0);
+    for (pos, d, min, max, n) in [
+        (from.x, dir.x, rect.left(), rect.right(), (1.0, 0.0)),
+        (from.y, dir.y, rect.top(), rect.bottom(), (0.0, 1.0)),
+    ] {
+        if d == 0.0 {
+            if pos < min || pos > max {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far, sign) = if d > 0.0 {
+            ((min - pos) / d, (max - pos) / d, -1.0)
+        } else {
+            ((max - pos) / d, (min - pos) / d, 1.0)
+        };
+        if near > enter {
+            enter = near;
+            normal = (n.0 * sign, n.1 * sign);
+        }
+        exit = f64::min(exit, far);
+        if enter > exit {
+            return None;
+        }
+    }
+
+    Some((enter, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        test_chunk::{self, TestChunk},
+        World,
+    };
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    #[test]
+    fn ray_rect_hits_nearest_side() {
+        let rect = Rect::new(10.0, 10.0, 20.0, 20.0);
+
+        let (distance, normal) =
+            ray_rect(Vector2::new(0.0, 15.0), Vector2::x(), 100.0, &rect).unwrap();
+        assert!((distance - 10.0).abs() < 1e-9);
+        assert!(close(normal, (-1.0, 0.0)));
+
+        let (distance, normal) =
+            ray_rect(Vector2::new(15.0, 30.0), -Vector2::y(), 100.0, &rect).unwrap();
+        assert!((distance - 10.0).abs() < 1e-9);
+        assert!(close(normal, (0.0, 1.0)));
+
+        let dir = Vector2::new(1.0, 1.0).normalize();
+        let (distance, _) = ray_rect(Vector2::new(0.0, 5.0), dir, 100.0, &rect).unwrap();
+        assert!((distance - 10.0 * 2f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ray_rect_misses() {
+        let rect = Rect::new(10.0, 10.0, 20.0, 20.0);
+
+        // too short
+        assert_eq!(
+            ray_rect(Vector2::new(0.0, 15.0), Vector2::x(), 9.0, &rect),
+            None
+        );
+        // pointing away
+        assert_eq!(
+            ray_rect(Vector2::new(0.0, 15.0), -Vector2::x(), 100.0, &rect),
+            None
+        );
+        // parallel to a side, outside of it
+        assert_eq!(
+            ray_rect(Vector2::new(0.0, 25.0), Vector2::x(), 100.0, &rect),
+            None
+        );
+        // passes by a corner
+        let dir = Vector2::new(1.0, 1.0).normalize();
+        assert_eq!(ray_rect(Vector2::new(0.0, 15.0), dir, 100.0, &rect), None);
+    }
+
+    #[test]
+    fn ray_rect_starts_inside() {
+        let rect = Rect::new(10.0, 10.0, 20.0, 20.0);
+        assert_eq!(
+            ray_rect(Vector2::new(15.0, 15.0), Vector2::x(), 100.0, &rect),
+            Some((0.0, (0.0, 0.0)))
+        );
+    }
+
+    fn world_with_stone(pixels: &[(i64, i64)]) -> World<TestChunk> {
+        let mut world = test_chunk::world([(0, 0), (-1, 0)]);
+        for &(x, y) in pixels {
+            world
+                .chunk_handler
+                .set_pixel(x, y, test_chunk::stone())
+                .unwrap();
+        }
+        world
+    }
+
+    fn pixel_hit(hit: RayHit) -> ((i64, i64), (f64, f64), f64) {
+        match hit.target {
+            QueryTarget::Pixel { x, y, .. } => ((x, y), hit.normal, hit.distance),
+            target => panic!("expected a pixel, got {target:?}"),
+        }
+    }
+
+    #[test]
+    fn cast_ray_steps_through_pixels() {
+        let world = world_with_stone(&[(10, 5), (3, 13)]);
+        let filter = QueryFilter::new();
+
+        let (pos, normal, distance) =
+            pixel_hit(world.cast_ray((0.5, 5.5), (20.5, 5.5), &filter).unwrap());
+        assert_eq!(pos, (10, 5));
+        assert!(close(normal, (-1.0, 0.0)));
+        assert!((distance - 9.5).abs() < 1e-9);
+
+        // from the other side, crossing into the chunk to the left
+        let (pos, normal, _) =
+            pixel_hit(world.cast_ray((-5.5, 5.5), (20.0, 5.5), &filter).unwrap());
+        assert_eq!(pos, (10, 5));
+        assert!(close(normal, (-1.0, 0.0)));
+        let (pos, normal, _) =
+            pixel_hit(world.cast_ray((30.5, 5.5), (-5.0, 5.5), &filter).unwrap());
+        assert_eq!(pos, (10, 5));
+        assert!(close(normal, (1.0, 0.0)));
+
+        // diagonal, entering through the top
+        let (pos, normal, _) =
+            pixel_hit(world.cast_ray((0.2, 10.0), (5.0, 15.0), &filter).unwrap());
+        assert_eq!(pos, (3, 13));
+        assert!(close(normal, (0.0, -1.0)));
+
+        // stops short
+        assert_eq!(world.cast_ray((0.5, 5.5), (9.9, 5.5), &filter), None);
+        // starts inside
+        let (pos, normal, distance) =
+            pixel_hit(world.cast_ray((10.5, 5.5), (20.0, 5.5), &filter).unwrap());
+        assert_eq!(pos, (10, 5));
+        assert!(close(normal, (0.0, 0.0)));
+        assert!(distance.abs() < 1e-9);
+    }
+
+    #[test]
+    fn cast_ray_rejects_non_finite() {
+        let world = world_with_stone(&[(10, 5)]);
+        let filter = QueryFilter::new();
+
+        assert_eq!(world.cast_ray((f64::NAN, 5.5), (20.0, 5.5), &filter), None);
+        assert_eq!(
+            world.cast_ray((0.5, 5.5), (f64::INFINITY, 5.5), &filter),
+            None
+        );
+        assert_eq!(
+            world.cast_ray((0.5, 5.5), (f64::NEG_INFINITY, f64::NAN), &filter),
+            None
+        );
+        // very long rays are cut short instead of running forever
+        assert!(world
+            .cast_ray((0.5, 50.5), (1e300, 50.5), &filter)
+            .is_none());
+    }
+
+    #[test]
+    fn query_area_rejects_non_finite() {
+        let world = world_with_stone(&[(10, 5)]);
+        let filter = QueryFilter::new();
+
+        for area in [
+            QueryArea::Circle { x: 10.0, y: 5.0, radius: f64::NAN },
+            QueryArea::Circle { x: 10.0, y: 5.0, radius: f64::INFINITY },
+            QueryArea::Circle { x: f64::NEG_INFINITY, y: 5.0, radius: 10.0 },
+            QueryArea::Rect(Rect::new(0.0, 0.0, f64::INFINITY, 10.0)),
+            QueryArea::Rect(Rect::new(f64::NAN, 0.0, 20.0, 10.0)),
+        ] {
+            assert_eq!(world.query_area(area, &filter), AreaHits::default());
+        }
+
+        assert_eq!(
+            world
+                .query_area(QueryArea::Circle { x: 10.5, y: 5.5, radius: 2.0 }, &filter)
+                .pixels
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn query_area_is_clamped() {
+        let world = world_with_stone(&[(10, 5), (-99, 99)]);
+        let filter = QueryFilter::new().without_bodies().without_entities();
+
+        // the stone would be inside, but is far outside the clamped area
+        let hits = world.query_area(
+            QueryArea::Circle { x: 1e6, y: 5.0, radius: 1e6 + 20.0 },
+            &filter,
+        );
+        assert!(hits.pixels.is_empty());
+
+        let hits = world.query_area(
+            QueryArea::Circle { x: 10.0, y: 5.0, radius: 1e300 },
+            &filter,
+        );
+        assert_eq!(
+            hits.pixels.iter().map(|(p, _)| *p).collect::<Vec<_>>(),
+            vec![(10, 5), (-99, 99)]
+        );
+
+        let hits = world.query_area(
+            QueryArea::Rect(Rect::new(-1e300, -1e300, 1e300, 1e300)),
+            &filter,
+        );
+        assert_eq!(hits.pixels.len(), 2);
+        // shrunk to x 500..1500
+        let hits = world.query_area(
+            QueryArea::Rect(Rect::new(-2_000.0, 0.0, 4_000.0, 10.0)),
+            &filter,
+        );
+        assert!(hits.pixels.is_empty());
+    }
+}
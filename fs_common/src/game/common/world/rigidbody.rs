@@ -1,15 +1,18 @@
-use glium::texture::Texture2d;
 use rapier2d::{
     na::{Isometry2, Point2, Vector2},
     prelude::{
-        ColliderBuilder, InteractionGroups, RigidBody, RigidBodyBuilder, RigidBodyHandle,
-        SharedShape,
+        ActiveEvents, Collider, ColliderBuilder, InteractionGroups, RigidBody, RigidBodyBuilder,
+        RigidBodyHandle, SharedShape,
     },
 };
+
+use crate::game::common::{registry::RegistryID, Registries};
 // use salva2d::{integrations::rapier::ColliderSampling, object::Boundary};
 
 use super::{
-    material::MaterialInstance,
+    buoyancy::LiquidForce,
+    fracture,
+    material::{Material, MaterialInstance, PhysicsType},
     mesh,
     physics::{Physics, PHYSICS_SCALE},
     CollisionFlags,
@@ -20,8 +23,12 @@ pub struct FSRigidBody {
     pub height: u16,
     pub pixels: Vec<MaterialInstance>,
     pub body: Option<RigidBodyHandle>,
-    pub image: Option<Texture2d>,
+    /// If the pixels changed since the client last drew them
     pub image_dirty: bool,
+    /// If the body can turn back into terrain after resting on it, see [`RigidBodySettling`](super::settle::RigidBodySettling)
+    pub settle: bool,
+    /// Buoyancy and drag currently applied to the body, see [`Submersion`](super::buoyancy::Submersion)
+    pub liquid_force: LiquidForce,
 }
 
 impl FSRigidBody {
@@ -39,8 +46,9 @@ impl FSRigidBody {
             height,
             pixels,
             body: None,
-            image: None,
             image_dirty: true,
+            settle: true,
+            liquid_force: LiquidForce::default(),
         })
     }
 
@@ -51,6 +59,7 @@ impl FSRigidBody {
         height: u16,
         physics: &mut Physics,
         position: (f32, f32),
+        registries: &Registries,
     ) -> Result<Self, String> {
         if pixels.len() != width as usize * height as usize {
             return Err(format!("RigidBody::from_pixels incorrect Vec size: pixels.len() = {}, width = {width}, height = {height}", pixels.len()));
@@ -67,53 +76,11 @@ impl FSRigidBody {
             .build();
         let rb_handle = physics.bodies.insert(rigid_body);
 
-        let mut shapes = Vec::new();
-        for tri in tris {
-            let verts = vec![
-                (
-                    tri.0 .0 as f32 / PHYSICS_SCALE,
-                    tri.0 .1 as f32 / PHYSICS_SCALE,
-                ),
-                (
-                    tri.1 .0 as f32 / PHYSICS_SCALE,
-                    tri.1 .1 as f32 / PHYSICS_SCALE,
-                ),
-                (
-                    tri.2 .0 as f32 / PHYSICS_SCALE,
-                    tri.2 .1 as f32 / PHYSICS_SCALE,
-                ),
-            ];
-
-            // let mut sh = PolygonShape::new();
-            // sh.set(verts);
-
-            // let mut fixture_def = FixtureDef::new(&sh);
-            // fixture_def.density = 1.0;
-            // fixture_def.filter.category_bits = CollisionFlags::RIGIDBODY.bits();
-            // fixture_def.filter.mask_bits = CollisionFlags::all().bits();
-            // bod.create_fixture(&fixture_def);
-
-            shapes.push((
-                Isometry2::new(Vector2::new(0.0, 0.0), 0.0),
-                SharedShape::triangle(
-                    Point2::new(verts[0].0, verts[0].1),
-                    Point2::new(verts[1].0, verts[1].1),
-                    Point2::new(verts[2].0, verts[2].1),
-                ),
-            ));
-        }
-
-        let collider = ColliderBuilder::compound(shapes)
-            .collision_groups(InteractionGroups::new(
-                CollisionFlags::RIGIDBODY.bits().into(),
-                CollisionFlags::all().bits().into(),
-            ))
-            .density(1.0)
-            .build();
-        let _co_handle =
-            physics
-                .colliders
-                .insert_with_parent(collider, rb_handle, &mut physics.bodies);
+        add_colliders(
+            colliders(tris, &pixels, width, registries),
+            rb_handle,
+            physics,
+        );
         // let bo_handle = physics
         //     .fluid_pipeline
         //     .liquid_world
@@ -129,8 +96,9 @@ impl FSRigidBody {
             height,
             pixels,
             body: Some(rb_handle),
-            image: None,
             image_dirty: true,
+            settle: true,
+            liquid_force: LiquidForce::default(),
         })
     }
 
@@ -148,6 +116,7 @@ impl FSRigidBody {
         height: u16,
         physics: &mut Physics,
         position: (f32, f32),
+        registries: &Registries,
     ) -> Result<Vec<FSRigidBody>, String> {
         let values = mesh::pixels_to_valuemap(pixels);
         let mesh =
@@ -203,8 +172,9 @@ impl FSRigidBody {
                 .collect();
 
             if n_pix > 0 && !a_loop.is_empty() {
-                let rb =
-                    FSRigidBody::from_tris(a_loop, my_pixels, width, height, physics, position)?;
+                let rb = FSRigidBody::from_tris(
+                    a_loop, my_pixels, width, height, physics, position, registries,
+                )?;
                 // debug!("mass = {}", rb.body.as_ref().unwrap().get_mass());
                 if physics.bodies.get(rb.body.unwrap()).unwrap().mass() > 0.0 {
                     rbs.push(rb);
@@ -215,7 +185,58 @@ impl FSRigidBody {
         Ok(rbs)
     }
 
-    pub fn make_body(&mut self, physics: &mut Physics, position: (f32, f32)) -> Result<(), String> {
+    /// Replaces the body with new ones made from the current `pixels`, in the same place and moving the same way.
+    /// Needed after solid pixels are added or removed, which can also split it into pieces.
+    pub fn remesh(self, physics: &mut Physics, registries: &Registries) -> Vec<FSRigidBody> {
+        let Some(old) = self.body.and_then(|b| physics.remove_rigidbody(b)) else {
+            return vec![self];
+        };
+
+        let mut bodies = Self::make_bodies(
+            &self.pixels,
+            self.width,
+            self.height,
+            physics,
+            (old.translation().x, old.translation().y),
+            registries,
+        )
+        .unwrap_or_default();
+
+        let iso = *old.position();
+        let w = usize::from(self.width);
+        for rb in &mut bodies {
+            rb.settle = self.settle;
+
+            // a spinning body's pieces move at the velocity of the point they were at
+            let (sum, count) = rb
+                .pixels
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.physics != PhysicsType::Air)
+                .fold((Vector2::zeros(), 0), |(sum, count), (i, _)| {
+                    (
+                        sum + Vector2::new((i % w) as f32, (i / w) as f32),
+                        count + 1,
+                    )
+                });
+            let center = sum / count.max(1) as f32 / PHYSICS_SCALE;
+            let vel = old.velocity_at_point(&(iso * Point2::from(center)));
+
+            let body = rb.get_body_mut(physics).unwrap();
+            body.set_position(iso, true);
+            body.set_linvel(vel, true);
+            body.set_angvel(old.angvel(), true);
+        }
+
+        bodies
+    }
+
+    pub fn make_body(
+        &mut self,
+        physics: &mut Physics,
+        position: (f32, f32),
+        registries: &Registries,
+    ) -> Result<(), String> {
         if self.body.is_some() {
             let b = self.body.take().unwrap();
             physics.bodies.remove(
@@ -242,49 +263,16 @@ impl FSRigidBody {
             .build();
         let rb_handle = physics.bodies.insert(rigid_body);
 
-        let mut shapes = Vec::new();
-        for a_loop in loops {
-            for tri in a_loop {
-                let verts = vec![
-                    (
-                        tri.0 .0 as f32 / PHYSICS_SCALE,
-                        tri.0 .1 as f32 / PHYSICS_SCALE,
-                    ),
-                    (
-                        tri.1 .0 as f32 / PHYSICS_SCALE,
-                        tri.1 .1 as f32 / PHYSICS_SCALE,
-                    ),
-                    (
-                        tri.2 .0 as f32 / PHYSICS_SCALE,
-                        tri.2 .1 as f32 / PHYSICS_SCALE,
-                    ),
-                ];
-
-                // let mut sh = PolygonShape::new();
-                // sh.set(verts);
-
-                // let mut fixture_def = FixtureDef::new(&sh);
-                // fixture_def.density = 1.0;
-                // fixture_def.filter.category_bits = CollisionFlags::RIGIDBODY.bits();
-                // fixture_def.filter.mask_bits = CollisionFlags::all().bits();
-                // bod.create_fixture(&fixture_def);
-
-                shapes.push((
-                    Isometry2::new(Vector2::new(0.0, 0.0), 0.0),
-                    SharedShape::triangle(
-                        Point2::new(verts[0].0, verts[0].1),
-                        Point2::new(verts[1].0, verts[1].1),
-                        Point2::new(verts[2].0, verts[2].1),
-                    ),
-                ));
-            }
-        }
-
-        let collider = ColliderBuilder::compound(shapes).build();
-        let _co_handle =
-            physics
-                .colliders
-                .insert_with_parent(collider, rb_handle, &mut physics.bodies);
+        add_colliders(
+            colliders(
+                loops.into_iter().flatten(),
+                &self.pixels,
+                self.width,
+                registries,
+            ),
+            rb_handle,
+            physics,
+        );
         // let bo_handle = physics
         //     .fluid_pipeline
         //     .liquid_world
@@ -296,7 +284,157 @@ impl FSRigidBody {
         // );
 
         self.body = Some(rb_handle);
+        self.liquid_force = LiquidForce::default();
 
         Ok(())
     }
 }
+
+/// Builds one collider per material in `pixels`,
+///   so each part of the body gets that material's density, friction, and restitution
+fn colliders(
+    tris: impl IntoIterator<Item = mesh::Tri>,
+    pixels: &[MaterialInstance],
+    width: u16,
+    registries: &Registries,
+) -> Vec<Collider> {
+    let mut groups: Vec<(Option<RegistryID<Material>>, Vec<_>)> = vec![];
+    for tri in tris {
+        let material = tri_material(&tri, pixels, width);
+        let shape = (
+            Isometry2::new(Vector2::new(0.0, 0.0), 0.0),
+            SharedShape::triangle(
+                Point2::new(tri.0 .0 as f32, tri.0 .1 as f32) / PHYSICS_SCALE,
+                Point2::new(tri.1 .0 as f32, tri.1 .1 as f32) / PHYSICS_SCALE,
+                Point2::new(tri.2 .0 as f32, tri.2 .1 as f32) / PHYSICS_SCALE,
+            ),
+        );
+
+        match groups.iter_mut().find(|(id, _)| *id == material) {
+            Some((_, shapes)) => shapes.push(shape),
+            None => groups.push((material, vec![shape])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(id, shapes)| {
+            let material = id.and_then(|id| registries.materials.get(&id));
+            ColliderBuilder::compound(shapes)
+                .collision_groups(InteractionGroups::new(
+                    CollisionFlags::RIGIDBODY.bits().into(),
+                    CollisionFlags::all().bits().into(),
+                ))
+                .density(material.map_or(1.0, |m| m.density))
+                .friction(material.map_or(0.5, |m| m.friction))
+                .restitution(material.map_or(0.0, |m| m.restitution))
+                .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                .build()
+        })
+        .collect()
+}
+
+/// The material of the pixel under the middle of `tri`, or under one of its corners if that is air
+fn tri_material(
+    tri: &mesh::Tri,
+    pixels: &[MaterialInstance],
+    width: u16,
+) -> Option<RegistryID<Material>> {
+    let ((x1, y1), (x2, y2), (x3, y3)) = *tri;
+    let center = ((x1 + x2 + x3) / 3.0, (y1 + y2 + y3) / 3.0);
+    let width = f64::from(width);
+    let height = (pixels.len() as f64 / width).floor();
+
+    [center, (x1, y1), (x2, y2), (x3, y3)]
+        .into_iter()
+        .flat_map(|(x, y)| {
+            // corners lie on pixel edges, so check the pixels on both sides
+            [(x, y), (x - 1.0, y), (x, y - 1.0), (x - 1.0, y - 1.0)]
+        })
+        .filter(|&(x, y)| x >= 0.0 && y >= 0.0 && x < width && y < height)
+        .map(|(x, y)| &pixels[x as usize + y as usize * width as usize])
+        .find(|m| m.physics != PhysicsType::Air)
+        .map(|m| m.material_id.clone())
+}
+
+/// Attaches `colliders` to `body`.
+/// Impacts are judged against the whole body's mass, so the threshold can only be set once every collider is attached.
+fn add_colliders(colliders: Vec<Collider>, body: RigidBodyHandle, physics: &mut Physics) {
+    let handles: Vec<_> = colliders
+        .into_iter()
+        .map(|c| {
+            physics
+                .colliders
+                .insert_with_parent(c, body, &mut physics.bodies)
+        })
+        .collect();
+
+    let threshold = physics.bodies[body].mass() * fracture::MIN_IMPACT;
+    for handle in handles {
+        physics.colliders[handle].set_contact_force_event_threshold(threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{material, material::color::Color, test_chunk};
+
+    #[test]
+    fn impact_threshold_uses_body_mass() {
+        let registries = test_chunk::registries();
+        let mut physics = Physics::new();
+
+        // left half stone, right half dirt, so the body gets two colliders
+        let dirt = material::SMOOTH_DIRT.instance(PhysicsType::Solid, Color::rgb(0x80, 0x50, 0x20));
+        let pixels = (0..20 * 10)
+            .map(|i| {
+                if i % 20 < 10 {
+                    test_chunk::stone()
+                } else {
+                    dirt.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        let rb = FSRigidBody::make_bodies(&pixels, 20, 10, &mut physics, (0.0, 0.0), &registries)
+            .unwrap()
+            .remove(0);
+
+        let body = rb.get_body(&physics).unwrap();
+        assert_eq!(body.colliders().len(), 2);
+        for handle in body.colliders() {
+            let collider = &physics.colliders[*handle];
+            assert!(collider.mass() < body.mass());
+            let expected = body.mass() * fracture::MIN_IMPACT;
+            assert!((collider.contact_force_event_threshold() - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn remesh_keeps_point_velocity() {
+        let registries = test_chunk::registries();
+        let mut physics = Physics::new();
+
+        let mut rb = test_chunk::stone_body(30, 10, (0.0, 0.0), &mut physics);
+        rb.get_body_mut(&mut physics).unwrap().set_angvel(2.0, true);
+
+        // cut it in half, the halves should move apart like the ends of the spinning body
+        for y in 0..10 {
+            rb.pixels[15 + y * 30] = MaterialInstance::air();
+        }
+        let halves = rb.remesh(&mut physics, &registries);
+        assert_eq!(halves.len(), 2);
+
+        let vels: Vec<_> = halves
+            .iter()
+            .map(|h| *h.get_body(&physics).unwrap().linvel())
+            .collect();
+        assert!(vels[0].y.abs() > 0.1);
+        assert!(vels[0].y * vels[1].y < 0.0);
+        for h in &halves {
+            let angvel = h.get_body(&physics).unwrap().angvel();
+            assert!((angvel - 2.0).abs() < 1e-4);
+        }
+    }
+}
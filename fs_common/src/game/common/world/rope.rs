@@ -0,0 +1,808 @@
+use rapier2d::{
+    na::{Isometry2, Point2, Vector2},
+    prelude::{
+        Capsule, ColliderBuilder, ImpulseJointHandle, InteractionGroups, RevoluteJointBuilder,
+        RigidBody, RigidBodyBuilder, RigidBodyHandle,
+    },
+};
+use specs::{Entity, ReadStorage, WorldExt};
+
+use super::{
+    chunk_access::FSChunkAccess,
+    chunk_handler::ChunkHandler,
+    entity::{Hitbox, PhysicsEntity},
+    material::{color::Color, PhysicsType},
+    physics::{Physics, PHYSICS_SCALE},
+    rigidbody::FSRigidBody,
+    Chunk, CollisionFlags, Position, RigidBodyComponent,
+};
+
+/// Where one end of a [`Rope`] is attached
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RopeAnchor {
+    /// A point in the world that never moves, in pixels. Used to hang ropes from terrain,
+    ///   comes loose once there is no solid terrain within a pixel of it.
+    Fixed { x: f32, y: f32 },
+    /// A point on a rapier body, in pixels relative to the body's position and rotation.
+    /// For [`FSRigidBody`]s that is the top left corner, for entities it is their [`Position`].
+    Body {
+        body: RigidBodyHandle,
+        x: f32,
+        y: f32,
+    },
+    /// Not attached to anything, eg. after the body it was on broke up and left nothing at the anchor
+    Loose,
+}
+
+impl RopeAnchor {
+    pub fn fixed(x: f32, y: f32) -> Self {
+        Self::Fixed { x, y }
+    }
+
+    /// A point on `rb`, in pixels relative to its top left corner
+    pub fn rigidbody(rb: &FSRigidBody, x: f32, y: f32) -> Option<Self> {
+        rb.body.map(|body| Self::Body { body, x, y })
+    }
+
+    /// Whatever is solid at the world pixel position `point`: an [`FSRigidBody`] if one has a pixel there,
+    ///   otherwise the terrain. Returns `None` if there is nothing to hold on to.
+    pub fn at<C: Chunk>(
+        point: Point2<f32>,
+        rigidbodies: &[FSRigidBody],
+        chunk_handler: &ChunkHandler<C>,
+        physics: &Physics,
+    ) -> Option<Self> {
+        for rb in rigidbodies {
+            let Some(body) = rb.get_body(physics) else {
+                continue;
+            };
+
+            let local = body
+                .position()
+                .inverse_transform_point(&(point / PHYSICS_SCALE))
+                * PHYSICS_SCALE;
+            let (x, y) = (local.x.floor() as i64, local.y.floor() as i64);
+            // the point can be on the edge between pixels
+            let solid = [(x, y), (x - 1, y), (x, y - 1), (x - 1, y - 1)]
+                .into_iter()
+                .any(|(x, y)| {
+                    (0..i64::from(rb.width)).contains(&x)
+                        && (0..i64::from(rb.height)).contains(&y)
+                        && rb.pixels[x as usize + y as usize * usize::from(rb.width)].physics
+                            != PhysicsType::Air
+                });
+            if solid {
+                return Some(Self::Body { body: rb.body.unwrap(), x: local.x, y: local.y });
+            }
+        }
+
+        held_by_terrain(point, chunk_handler).then_some(Self::Fixed { x: point.x, y: point.y })
+    }
+
+    /// A point on `entity`, in pixels relative to its [`Position`].
+    /// Uses the entity's [`RigidBodyComponent`]. Entities without one (eg. a grapple hook) get a body that
+    ///   follows them the same way the player's does, which needs a [`PhysicsEntity`] and a [`Hitbox`].
+    pub fn entity(
+        ecs: &specs::World,
+        physics: &mut Physics,
+        entity: Entity,
+        x: f32,
+        y: f32,
+    ) -> Option<Self> {
+        let mut bodies = ecs.write_storage::<RigidBodyComponent>();
+        if let Some(b) = bodies.get(entity) {
+            return Some(Self::Body { body: b.body, x, y });
+        }
+
+        let (positions, hitboxes, phys_ents) = ecs.system_data::<(
+            ReadStorage<Position>,
+            ReadStorage<Hitbox>,
+            ReadStorage<PhysicsEntity>,
+        )>();
+        let (pos, hitbox) = (positions.get(entity)?, hitboxes.get(entity)?);
+        phys_ents.get(entity)?;
+
+        // entity physics handles gravity and collision, the body only has to carry the rope's pull
+        let body = physics.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector2::new(
+                    pos.x as f32 / PHYSICS_SCALE,
+                    pos.y as f32 / PHYSICS_SCALE,
+                ))
+                .lock_rotations()
+                .gravity_scale(0.0)
+                .build(),
+        );
+        physics.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(
+                (hitbox.x2 - hitbox.x1) / PHYSICS_SCALE / 2.0,
+                (hitbox.y2 - hitbox.y1) / PHYSICS_SCALE / 2.0,
+            )
+            .translation(Vector2::new(
+                (hitbox.x1 + hitbox.x2) / PHYSICS_SCALE / 2.0,
+                (hitbox.y1 + hitbox.y2) / PHYSICS_SCALE / 2.0,
+            ))
+            .collision_groups(InteractionGroups::none())
+            .density(1.5)
+            .build(),
+            body,
+            &mut physics.bodies,
+        );
+        bodies.insert(entity, RigidBodyComponent::of(body)).ok()?;
+
+        Some(Self::Body { body, x, y })
+    }
+
+    /// The anchor's current position in the world, in pixels
+    pub fn position(&self, physics: &Physics) -> Option<Point2<f32>> {
+        match *self {
+            Self::Fixed { x, y } => Some(Point2::new(x, y)),
+            Self::Body { body, x, y } => physics.bodies.get(body).map(|b| {
+                b.position() * Point2::new(x / PHYSICS_SCALE, y / PHYSICS_SCALE) * PHYSICS_SCALE
+            }),
+            Self::Loose => None,
+        }
+    }
+
+    /// The body and local point (in physics units) that joints should attach to
+    fn attach_point(
+        &self,
+        physics: &mut Physics,
+        fixed: &mut Vec<(Point2<f32>, RigidBodyHandle)>,
+    ) -> Option<(RigidBodyHandle, Point2<f32>)> {
+        let point = match *self {
+            Self::Fixed { x, y } => {
+                let body = physics.bodies.insert(
+                    RigidBodyBuilder::fixed()
+                        .translation(Vector2::new(x / PHYSICS_SCALE, y / PHYSICS_SCALE))
+                        .build(),
+                );
+                fixed.push((Point2::new(x, y), body));
+                (body, Point2::origin())
+            },
+            Self::Body { body, x, y } => (body, Point2::new(x / PHYSICS_SCALE, y / PHYSICS_SCALE)),
+            Self::Loose => return None,
+        };
+        Some(point)
+    }
+}
+
+/// A rope or chain made of small rigidbody segments connected by joints.
+///
+/// Create one with [`Rope::new`] or [`Rope::chain`] and add it with [`World::add_rope`](super::World::add_rope).
+/// If the body at either end is replaced (eg. remeshed, fractured, or settled into terrain),
+///   that end moves over to whatever is solid where it was attached. If there is nothing, it comes loose.
+#[derive(Debug, Clone)]
+pub struct Rope {
+    pub start: RopeAnchor,
+    /// A rope without an end hangs loose from `start`
+    pub end: Option<RopeAnchor>,
+    /// Total length in pixels, defaults to the distance between the anchors
+    pub length: Option<f32>,
+    /// Target length of each segment in pixels, shorter segments bend more smoothly
+    pub segment_length: f32,
+    /// Thickness in pixels, for collision and rendering
+    pub width: f32,
+    pub density: f32,
+    pub color: Color,
+    /// Draw alternating links instead of a smooth line
+    pub chain: bool,
+    segments: Vec<RigidBodyHandle>,
+    joints: Vec<ImpulseJointHandle>,
+    /// Bodies created for [`RopeAnchor::Fixed`] ends, and where they are in pixels
+    fixed: Vec<(Point2<f32>, RigidBodyHandle)>,
+}
+
+impl Rope {
+    pub fn new(start: RopeAnchor, end: Option<RopeAnchor>) -> Self {
+        Self {
+            start,
+            end,
+            length: None,
+            segment_length: 4.0,
+            width: 1.0,
+            density: 0.5,
+            color: Color::rgb(150, 110, 60),
+            chain: false,
+            segments: vec![],
+            joints: vec![],
+            fixed: vec![],
+        }
+    }
+
+    /// A heavier rope with longer segments, drawn as links
+    pub fn chain(start: RopeAnchor, end: Option<RopeAnchor>) -> Self {
+        Self {
+            segment_length: 6.0,
+            width: 2.0,
+            density: 2.5,
+            color: Color::rgb(110, 110, 120),
+            chain: true,
+            ..Self::new(start, end)
+        }
+    }
+
+    #[must_use]
+    pub fn with_length(self, length: f32) -> Self {
+        Self { length: Some(length), ..self }
+    }
+
+    #[must_use]
+    pub fn with_segment_length(self, segment_length: f32) -> Self {
+        Self { segment_length, ..self }
+    }
+
+    #[must_use]
+    pub fn with_width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    #[must_use]
+    pub fn with_density(self, density: f32) -> Self {
+        Self { density, ..self }
+    }
+
+    #[must_use]
+    pub fn with_color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Creates the rope's segments and joints in `physics`.
+    /// Slack ropes start out sagging in the middle, ropes shorter than the distance between their ends pull them together.
+    pub fn attach(mut self, physics: &mut Physics) -> Result<Self, String> {
+        if !self.segments.is_empty() {
+            return Err("Rope is already attached".into());
+        }
+
+        let start = self
+            .start
+            .position(physics)
+            .ok_or("Rope start is attached to a body that doesn't exist")?;
+        let end = match self.end {
+            Some(end) => Some(
+                end.position(physics)
+                    .ok_or("Rope end is attached to a body that doesn't exist")?,
+            ),
+            None => None,
+        };
+
+        let length = match (self.length, end) {
+            (Some(length), _) => length,
+            (None, Some(end)) => (end - start).norm(),
+            (None, None) => return Err("Rope without an end needs a length".into()),
+        };
+        if length <= 0.0 || self.segment_length <= 0.0 {
+            return Err(format!(
+                "Invalid rope length {length} or segment length {}",
+                self.segment_length
+            ));
+        }
+
+        let count = (length / self.segment_length).ceil() as usize;
+        let half = length / count as f32 / 2.0 / PHYSICS_SCALE;
+        let path = layout(start, end, length);
+        let radius = self.width / 2.0 / PHYSICS_SCALE;
+
+        for i in 0..count {
+            let a = point_along(&path, i as f32 / count as f32);
+            let b = point_along(&path, (i + 1) as f32 / count as f32);
+            let center = Point2::from((a.coords + b.coords) / 2.0 / PHYSICS_SCALE);
+            let angle = (b.y - a.y).atan2(b.x - a.x);
+
+            let body = physics.bodies.insert(
+                RigidBodyBuilder::dynamic()
+                    .position(Isometry2::new(center.coords, angle))
+                    .build(),
+            );
+            physics.colliders.insert_with_parent(
+                ColliderBuilder::capsule_x(half, radius)
+                    .density(self.density)
+                    .collision_groups(InteractionGroups::new(
+                        CollisionFlags::ROPE.bits().into(),
+                        (CollisionFlags::WORLD | CollisionFlags::RIGIDBODY)
+                            .bits()
+                            .into(),
+                    ))
+                    .build(),
+                body,
+                &mut physics.bodies,
+            );
+            self.segments.push(body);
+        }
+
+        let mut joints = vec![];
+        let (start_body, start_anchor) = self
+            .start
+            .attach_point(physics, &mut self.fixed)
+            .ok_or("Rope start isn't attached to anything")?;
+        joints.push(link(
+            physics,
+            start_body,
+            start_anchor,
+            self.segments[0],
+            Point2::new(-half, 0.0),
+        ));
+        for pair in self.segments.windows(2) {
+            joints.push(link(
+                physics,
+                pair[0],
+                Point2::new(half, 0.0),
+                pair[1],
+                Point2::new(-half, 0.0),
+            ));
+        }
+        if let Some((end_body, end_anchor)) = self
+            .end
+            .and_then(|end| end.attach_point(physics, &mut self.fixed))
+        {
+            joints.push(link(
+                physics,
+                *self.segments.last().unwrap(),
+                Point2::new(half, 0.0),
+                end_body,
+                end_anchor,
+            ));
+        }
+        self.joints = joints;
+
+        Ok(self)
+    }
+
+    /// Removes the rope's segments and joints from `physics`
+    pub fn detach(&mut self, physics: &mut Physics) {
+        for body in self
+            .segments
+            .drain(..)
+            .chain(self.fixed.drain(..).map(|(_, body)| body))
+        {
+            physics.remove_rigidbody(body);
+        }
+        self.joints.clear();
+    }
+
+    /// Lets go of [`RopeAnchor::Fixed`] ends that the terrain no longer holds up,
+    ///   and moves [`RopeAnchor::Body`] ends whose body was replaced over to what is there now.
+    pub fn update<C: Chunk>(
+        &mut self,
+        chunk_handler: &ChunkHandler<C>,
+        physics: &mut Physics,
+        rigidbodies: &[FSRigidBody],
+    ) {
+        let mut released = vec![];
+        self.fixed.retain(|&(point, body)| {
+            let held = held_by_terrain(point, chunk_handler);
+            if !held {
+                released.push(body);
+            }
+            held
+        });
+        for body in released {
+            // removing the body removes its joint from `physics`, so drop the handle too
+            let joint_body = |joint: Option<&ImpulseJointHandle>, start: bool| {
+                joint.and_then(|j| physics.impulse_joints.get(*j)).map(|j| {
+                    if start {
+                        j.body1
+                    } else {
+                        j.body2
+                    }
+                })
+            };
+            if matches!(self.start, RopeAnchor::Fixed { .. })
+                && joint_body(self.joints.first(), true) == Some(body)
+            {
+                self.start = RopeAnchor::Loose;
+                self.joints.remove(0);
+            } else if matches!(self.end, Some(RopeAnchor::Fixed { .. }))
+                && joint_body(self.joints.last(), false) == Some(body)
+            {
+                self.end = Some(RopeAnchor::Loose);
+                self.joints.pop();
+            }
+            physics.remove_rigidbody(body);
+        }
+
+        if matches!(self.start, RopeAnchor::Body { body, .. } if !physics.bodies.contains(body)) {
+            self.start = self.reattach(true, chunk_handler, physics, rigidbodies);
+        }
+        if matches!(self.end, Some(RopeAnchor::Body { body, .. }) if !physics.bodies.contains(body))
+        {
+            self.end = Some(self.reattach(false, chunk_handler, physics, rigidbodies));
+        }
+    }
+
+    /// Attaches the start or end segment to whatever is at its free end, replacing the joint that was removed with the old body
+    fn reattach<C: Chunk>(
+        &mut self,
+        start: bool,
+        chunk_handler: &ChunkHandler<C>,
+        physics: &mut Physics,
+        rigidbodies: &[FSRigidBody],
+    ) -> RopeAnchor {
+        let segment = if start {
+            self.segments.first()
+        } else {
+            self.segments.last()
+        };
+        let Some((segment, seg_body)) =
+            segment.and_then(|s| physics.bodies.get(*s).map(|b| (*s, b)))
+        else {
+            return RopeAnchor::Loose;
+        };
+
+        let half = half_length(seg_body, physics);
+        let seg_anchor = Point2::new(if start { -half } else { half }, 0.0);
+        let point = seg_body.position() * seg_anchor * PHYSICS_SCALE;
+
+        // the old joint was removed along with the old body
+        let joint_index = if start { 0 } else { self.joints.len() - 1 };
+        let Some(anchor) = RopeAnchor::at(point, rigidbodies, chunk_handler, physics) else {
+            self.joints.remove(joint_index);
+            return RopeAnchor::Loose;
+        };
+
+        let (body, local) = anchor.attach_point(physics, &mut self.fixed).unwrap();
+        self.joints[joint_index] = if start {
+            link(physics, body, local, segment, seg_anchor)
+        } else {
+            link(physics, segment, seg_anchor, body, local)
+        };
+        anchor
+    }
+
+    /// If any of the rope's segments are still in `physics`
+    pub fn is_attached(&self, physics: &Physics) -> bool {
+        self.segments.iter().any(|s| physics.bodies.contains(*s))
+    }
+
+    pub fn segments(&self) -> &[RigidBodyHandle] {
+        &self.segments
+    }
+
+    pub fn joints(&self) -> &[ImpulseJointHandle] {
+        &self.joints
+    }
+
+    /// The ends of each segment in world pixels, from `start` to `end`
+    pub fn points(&self, physics: &Physics) -> Vec<Point2<f32>> {
+        let mut points = Vec::with_capacity(self.segments.len() + 1);
+        for (i, body) in self
+            .segments
+            .iter()
+            .filter_map(|s| physics.bodies.get(*s))
+            .enumerate()
+        {
+            let half = half_length(body, physics);
+            if i == 0 {
+                points.push(body.position() * Point2::new(-half, 0.0) * PHYSICS_SCALE);
+            }
+            points.push(body.position() * Point2::new(half, 0.0) * PHYSICS_SCALE);
+        }
+        points
+    }
+}
+
+fn link(
+    physics: &mut Physics,
+    body1: RigidBodyHandle,
+    anchor1: Point2<f32>,
+    body2: RigidBodyHandle,
+    anchor2: Point2<f32>,
+) -> ImpulseJointHandle {
+    let joint = RevoluteJointBuilder::new()
+        .local_anchor1(anchor1)
+        .local_anchor2(anchor2)
+        .contacts_enabled(false);
+    physics.impulse_joints.insert(body1, body2, joint, true)
+}
+
+/// Half the length of a rope segment in physics units
+fn half_length(segment: &RigidBody, physics: &Physics) -> f32 {
+    segment
+        .colliders()
+        .first()
+        .and_then(|c| physics.colliders.get(*c))
+        .and_then(|c| c.shape().as_capsule())
+        .map_or(0.0, Capsule::half_height)
+}
+
+/// If there is solid terrain within a pixel of `point`, unloaded terrain counts as solid
+fn held_by_terrain<C: Chunk>(point: Point2<f32>, chunk_handler: &ChunkHandler<C>) -> bool {
+    let (x, y) = (point.x.floor() as i64, point.y.floor() as i64);
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+        .any(|(x, y)| {
+            chunk_handler
+                .pixel(x, y)
+                .map_or(true, |m| m.physics == PhysicsType::Solid)
+        })
+}
+
+/// The path a new rope is laid along: hanging down, straight, or sagging in a V
+fn layout(start: Point2<f32>, end: Option<Point2<f32>>, length: f32) -> Vec<Point2<f32>> {
+    let Some(end) = end else {
+        return vec![start, start + Vector2::y() * length];
+    };
+
+    let dist = (end - start).norm();
+    if length <= dist {
+        return vec![start, end];
+    }
+
+    let sag = ((length / 2.0).powi(2) - (dist / 2.0).powi(2)).sqrt();
+    let middle = Point2::from((start.coords + end.coords) / 2.0) + Vector2::y() * sag;
+    vec![start, middle, end]
+}
+
+/// The point `fraction` of the way along `path`
+fn point_along(path: &[Point2<f32>], fraction: f32) -> Point2<f32> {
+    let total: f32 = path.windows(2).map(|w| (w[1] - w[0]).norm()).sum();
+    let mut remaining = total * fraction;
+    for w in path.windows(2) {
+        let len = (w[1] - w[0]).norm();
+        if remaining <= len && len > 0.0 {
+            return w[0] + (w[1] - w[0]) * (remaining / len);
+        }
+        remaining -= len;
+    }
+    *path.last().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use specs::Builder;
+
+    use crate::game::common::{
+        world::{
+            chunk_access::FSChunkAccess,
+            entity::{GameEntity, Player},
+            material::MaterialInstance,
+            test_chunk::{self, TestChunk},
+            Velocity, World,
+        },
+        Settings,
+    };
+
+    /// Adds a 20x10 stone body covering world pixels (20, 20) to (40, 30)
+    fn add_body(world: &mut World<TestChunk>) -> RigidBodyHandle {
+        let rb = test_chunk::stone_body(20, 10, (2.0, 2.0), &mut world.physics);
+        let handle = rb.body.unwrap();
+        world.rigidbodies.push(rb);
+        handle
+    }
+
+    fn update(world: &mut World<TestChunk>) {
+        for rope in &mut world.ropes {
+            rope.update(&world.chunk_handler, &mut world.physics, &world.rigidbodies);
+        }
+    }
+
+    #[test]
+    fn anchor_at_finds_body_then_terrain() {
+        let mut world = test_chunk::world([(0, 0)]);
+        let body = add_body(&mut world);
+        world
+            .chunk_handler
+            .set_pixel(50, 50, test_chunk::stone())
+            .unwrap();
+
+        let Some(RopeAnchor::Body { body: found, x, y }) = world.rope_anchor_at(25.0, 25.0) else {
+            panic!("expected a body anchor");
+        };
+        assert_eq!(found, body);
+        assert!((x - 5.0).abs() < 1e-3 && (y - 5.0).abs() < 1e-3);
+
+        // on the body's edge
+        assert!(matches!(
+            world.rope_anchor_at(40.0, 30.0),
+            Some(RopeAnchor::Body { .. })
+        ));
+        assert_eq!(
+            world.rope_anchor_at(50.5, 50.5),
+            Some(RopeAnchor::Fixed { x: 50.5, y: 50.5 })
+        );
+        assert_eq!(world.rope_anchor_at(80.0, 80.0), None);
+        // unloaded terrain holds on
+        assert!(matches!(
+            world.rope_anchor_at(-50.0, 50.0),
+            Some(RopeAnchor::Fixed { .. })
+        ));
+    }
+
+    #[test]
+    fn attach_links_every_segment() {
+        let mut world = test_chunk::world([]);
+        world
+            .add_rope(Rope::new(
+                RopeAnchor::Fixed { x: 10.0, y: 10.0 },
+                Some(RopeAnchor::Fixed { x: 30.0, y: 10.0 }),
+            ))
+            .unwrap();
+
+        let rope = &world.ropes[0];
+        assert_eq!(rope.segments().len(), 5);
+        assert_eq!(rope.joints().len(), 6);
+        let points = rope.points(&world.physics);
+        assert!((points[0] - Point2::new(10.0, 10.0)).norm() < 1e-3);
+        assert!((points.last().unwrap() - Point2::new(30.0, 10.0)).norm() < 1e-3);
+
+        world.remove_rope(0);
+        assert_eq!(world.physics.bodies.len(), 0);
+        assert_eq!(world.physics.impulse_joints.len(), 0);
+    }
+
+    #[test]
+    fn body_end_follows_remesh() {
+        let mut world = test_chunk::world([]);
+        let body = add_body(&mut world);
+        world
+            .add_rope(Rope::new(
+                RopeAnchor::Body { body, x: 5.0, y: 0.0 },
+                Some(RopeAnchor::Fixed { x: 25.0, y: 0.0 }),
+            ))
+            .unwrap();
+        let joints = world.ropes[0].joints().len();
+
+        // cut off the far end, which replaces the body
+        let mut rb = world.rigidbodies.remove(0);
+        for y in 0..10 {
+            for x in 15..20 {
+                rb.pixels[x + y * 20] = MaterialInstance::air();
+            }
+        }
+        let registries = test_chunk::registries();
+        world.rigidbodies = rb.remesh(&mut world.physics, &registries);
+        let new_body = world.rigidbodies[0].body.unwrap();
+        assert_ne!(new_body, body);
+
+        update(&mut world);
+        let rope = &world.ropes[0];
+        let RopeAnchor::Body { body: anchored, x, y } = rope.start else {
+            panic!("expected a body anchor, got {:?}", rope.start);
+        };
+        assert_eq!(anchored, new_body);
+        assert!((x - 5.0).abs() < 0.1 && y.abs() < 0.1);
+        assert_eq!(rope.joints().len(), joints);
+        let joint = world.physics.impulse_joints.get(rope.joints()[0]).unwrap();
+        assert_eq!(joint.body1, new_body);
+        assert_eq!(joint.body2, rope.segments()[0]);
+    }
+
+    #[test]
+    fn removed_body_end_falls_back() {
+        // the terrain at the start is unloaded, so it holds on
+        let mut world = test_chunk::world([]);
+        let body = add_body(&mut world);
+        world
+            .add_rope(Rope::new(RopeAnchor::Body { body, x: 5.0, y: 0.0 }, None).with_length(20.0))
+            .unwrap();
+        world.physics.remove_rigidbody(body);
+        world.rigidbodies.clear();
+        update(&mut world);
+        assert!(matches!(world.ropes[0].start, RopeAnchor::Fixed { .. }));
+        let joint = world.ropes[0].joints()[0];
+        assert!(world.physics.impulse_joints.get(joint).is_some());
+
+        // loaded air doesn't
+        let mut world = test_chunk::world([(0, 0)]);
+        let body = add_body(&mut world);
+        world
+            .add_rope(Rope::new(RopeAnchor::Body { body, x: 5.0, y: 0.0 }, None).with_length(20.0))
+            .unwrap();
+        let joints = world.ropes[0].joints().len();
+        world.physics.remove_rigidbody(body);
+        world.rigidbodies.clear();
+        update(&mut world);
+        assert_eq!(world.ropes[0].start, RopeAnchor::Loose);
+        assert_eq!(world.ropes[0].joints().len(), joints - 1);
+    }
+
+    #[test]
+    fn rope_holds_entity() {
+        let mut world = test_chunk::world((-1..=1).flat_map(|y| (-1..=1).map(move |x| (x, y))));
+        world
+            .chunk_handler
+            .set_pixel(50, 30, test_chunk::stone())
+            .unwrap();
+
+        // the player already has a body
+        let player = Player::create_and_add(&mut world);
+        let player_body = world
+            .ecs
+            .read_storage::<RigidBodyComponent>()
+            .get(player)
+            .unwrap()
+            .body;
+        assert!(matches!(
+            RopeAnchor::entity(&world.ecs, &mut world.physics, player, 0.0, 0.0),
+            Some(RopeAnchor::Body { body, .. }) if body == player_body
+        ));
+        world.ecs.delete_entity(player).unwrap();
+        world.physics.remove_rigidbody(player_body);
+
+        // a falling entity like a grapple hook doesn't
+        let hook = world
+            .ecs
+            .create_entity()
+            .with(GameEntity)
+            .with(Position { x: 50.0, y: 50.0 })
+            .with(Velocity { x: 0.0, y: 0.0 })
+            .with(Hitbox { x1: -4.0, y1: -4.0, x2: 4.0, y2: 4.0 })
+            .with(PhysicsEntity {
+                gravity: 0.5,
+                on_ground: false,
+                edge_clip_distance: 0.0,
+                collision: true,
+                collide_with_sand: false,
+            })
+            .build();
+        let end = RopeAnchor::entity(&world.ecs, &mut world.physics, hook, 0.0, 0.0).unwrap();
+        let RopeAnchor::Body { body, .. } = end else {
+            panic!("expected a body anchor, got {end:?}");
+        };
+        assert_eq!(
+            world
+                .ecs
+                .read_storage::<RigidBodyComponent>()
+                .get(hook)
+                .unwrap()
+                .body,
+            body
+        );
+
+        world
+            .add_rope(Rope::new(RopeAnchor::fixed(50.0, 30.0), Some(end)))
+            .unwrap();
+        let points = world.ropes[0].points(&world.physics);
+        assert!((points.last().unwrap() - Point2::new(50.0, 50.0)).norm() < 1e-3);
+
+        test_chunk::tick(&mut world, Settings::default(), 200);
+        let y = world.ecs.read_storage::<Position>().get(hook).unwrap().y;
+        assert!(y < 60.0, "entity fell to {y}");
+    }
+
+    #[test]
+    fn fixed_end_comes_loose() {
+        let mut world = test_chunk::world([(0, 0)]);
+        for x in [10, 30] {
+            world
+                .chunk_handler
+                .set_pixel(x, 10, test_chunk::stone())
+                .unwrap();
+        }
+        world
+            .add_rope(Rope::new(
+                RopeAnchor::fixed(10.0, 10.0),
+                Some(RopeAnchor::fixed(30.0, 10.0)),
+            ))
+            .unwrap();
+        let joints = world.ropes[0].joints().len();
+        let segments = world.ropes[0].segments().len();
+
+        world
+            .chunk_handler
+            .set_pixel(30, 10, MaterialInstance::air())
+            .unwrap();
+        update(&mut world);
+        let rope = &world.ropes[0];
+        assert!(matches!(rope.start, RopeAnchor::Fixed { .. }));
+        assert_eq!(rope.end, Some(RopeAnchor::Loose));
+        assert_eq!(rope.joints().len(), joints - 1);
+        assert_eq!(world.physics.bodies.len(), segments + 1);
+
+        world
+            .chunk_handler
+            .set_pixel(10, 10, MaterialInstance::air())
+            .unwrap();
+        update(&mut world);
+        let rope = &world.ropes[0];
+        assert_eq!(rope.start, RopeAnchor::Loose);
+        assert_eq!(rope.joints().len(), joints - 2);
+        assert_eq!(world.physics.bodies.len(), segments);
+        assert!(rope
+            .joints()
+            .iter()
+            .all(|j| world.physics.impulse_joints.get(*j).is_some()));
+    }
+}
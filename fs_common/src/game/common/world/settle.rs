@@ -0,0 +1,343 @@
+use ahash::{AHashMap, AHashSet};
+use rapier2d::{
+    na::Point2,
+    prelude::{RigidBody, RigidBodyHandle},
+};
+
+use crate::game::common::Registries;
+
+use super::{
+    chunk_access::FSChunkAccess,
+    chunk_handler::ChunkHandler,
+    material::{MaterialInstance, PhysicsType},
+    particle::Particle,
+    physics::{Physics, PHYSICS_SCALE},
+    rigidbody::FSRigidBody,
+    Chunk, CollisionFlags, Position, Velocity,
+};
+
+/// Turns rigidbodies back into terrain pixels once they have been asleep on it for a while,
+///   so debris doesn't stay around as bodies forever.
+///
+/// Bodies with [`FSRigidBody::settle`] turned off, or containing a material that doesn't
+///   [`settle`](super::material::Material::settles), stay bodies.
+#[derive(Debug, Clone)]
+pub struct RigidBodySettling {
+    /// Seconds a body has to be asleep before it settles
+    pub sleep_time: f32,
+    /// Ticks each sleeping body has been asleep for
+    asleep: AHashMap<RigidBodyHandle, u32>,
+}
+
+impl RigidBodySettling {
+    pub fn new() -> Self {
+        Self { sleep_time: 5.0, asleep: AHashMap::new() }
+    }
+
+    #[profiling::function]
+    pub fn update<C: Chunk>(
+        &mut self,
+        rigidbodies: &mut Vec<FSRigidBody>,
+        chunk_handler: &mut ChunkHandler<C>,
+        physics: &mut Physics,
+        particles: &mut Vec<Particle>,
+        tick_speed: u16,
+        registries: &Registries,
+    ) {
+        let required = (self.sleep_time * f32::from(tick_speed)).ceil() as u32;
+        let mut asleep = AHashMap::new();
+
+        for rb in std::mem::take(rigidbodies) {
+            let Some(handle) = rb
+                .body
+                .filter(|b| physics.bodies.get(*b).is_some_and(RigidBody::is_sleeping))
+            else {
+                rigidbodies.push(rb);
+                continue;
+            };
+
+            let ticks = self.asleep.get(&handle).copied().unwrap_or(0) + 1;
+            if ticks >= required
+                && Self::can_settle(&rb, registries)
+                && Self::on_terrain(handle, physics)
+                && Self::stamp(&rb, handle, chunk_handler, physics, particles)
+            {
+                physics.remove_rigidbody(handle);
+            } else {
+                asleep.insert(handle, ticks);
+                rigidbodies.push(rb);
+            }
+        }
+
+        self.asleep = asleep;
+    }
+
+    fn can_settle(rb: &FSRigidBody, registries: &Registries) -> bool {
+        rb.settle
+            && rb
+                .pixels
+                .iter()
+                .filter(|m| m.physics != PhysicsType::Air)
+                .all(|m| {
+                    registries
+                        .materials
+                        .get(&m.material_id)
+                        .is_none_or(|m| m.settles)
+                })
+    }
+
+    /// If the body is touching a chunk collider
+    fn on_terrain(handle: RigidBodyHandle, physics: &Physics) -> bool {
+        physics.bodies[handle].colliders().iter().any(|&collider| {
+            physics.narrow_phase.contacts_with(collider).any(|pair| {
+                let other = if pair.collider1 == collider {
+                    pair.collider2
+                } else {
+                    pair.collider1
+                };
+                pair.has_any_active_contact
+                    && physics.colliders.get(other).is_some_and(|c| {
+                        c.collision_groups()
+                            .memberships
+                            .contains(CollisionFlags::WORLD.bits().into())
+                    })
+            })
+        })
+    }
+
+    /// Writes the body's pixels into the world where it is.
+    /// Sand and liquid in the way is pushed aside, pixels that don't fit become particles.
+    /// Fails without changing anything if any of the chunks it covers aren't loaded,
+    ///   or if any of the solid pixels it would add aren't connected to solid terrain (see [`Self::connected`]).
+    fn stamp<C: Chunk>(
+        rb: &FSRigidBody,
+        handle: RigidBodyHandle,
+        chunk_handler: &mut ChunkHandler<C>,
+        physics: &Physics,
+        particles: &mut Vec<Particle>,
+    ) -> bool {
+        let iso = physics.bodies[handle].position();
+        let (w, h) = (f32::from(rb.width), f32::from(rb.height));
+        let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)]
+            .map(|(x, y)| iso * Point2::new(x / PHYSICS_SCALE, y / PHYSICS_SCALE) * PHYSICS_SCALE);
+        let min = corners
+            .iter()
+            .fold(Point2::new(f32::MAX, f32::MAX), |a, p| a.inf(p));
+        let max = corners
+            .iter()
+            .fold(Point2::new(f32::MIN, f32::MIN), |a, p| a.sup(p));
+
+        // sample the body at the center of each world pixel it covers, so rotated bodies don't leave holes
+        let mut placed: Vec<(i64, i64, &MaterialInstance, MaterialInstance)> = vec![];
+        for wy in min.y.floor() as i64..max.y.ceil() as i64 {
+            for wx in min.x.floor() as i64..max.x.ceil() as i64 {
+                let local = iso.inverse_transform_point(&Point2::new(
+                    (wx as f32 + 0.5) / PHYSICS_SCALE,
+                    (wy as f32 + 0.5) / PHYSICS_SCALE,
+                )) * PHYSICS_SCALE;
+                if local.x < 0.0 || local.y < 0.0 || local.x >= w || local.y >= h {
+                    continue;
+                }
+
+                let mat = &rb.pixels[local.x as usize + local.y as usize * usize::from(rb.width)];
+                if mat.physics == PhysicsType::Air {
+                    continue;
+                }
+
+                let Ok(cur) = chunk_handler.pixel(wx, wy) else {
+                    return false;
+                };
+                placed.push((wx, wy, mat, cur.clone()));
+            }
+        }
+
+        let solid: AHashSet<(i64, i64)> = placed
+            .iter()
+            .filter(|(_, _, mat, cur)| {
+                mat.physics == PhysicsType::Solid
+                    && matches!(
+                        cur.physics,
+                        PhysicsType::Air | PhysicsType::Sand | PhysicsType::Liquid
+                    )
+            })
+            .map(|&(x, y, _, _)| (x, y))
+            .collect();
+        if !Self::connected(&solid, chunk_handler) {
+            return false;
+        }
+
+        for (x, y, mat, cur) in placed {
+            let pos = Position { x: x as f64 + 0.5, y: y as f64 + 0.5 };
+            match cur.physics {
+                PhysicsType::Air => {
+                    let _ignore = chunk_handler.set_pixel(x, y, mat.clone());
+                },
+                PhysicsType::Sand | PhysicsType::Liquid => {
+                    if chunk_handler.set_pixel(x, y, mat.clone()).is_ok()
+                        && !chunk_handler.displace_pixel(x, y, cur.clone())
+                    {
+                        particles.push(Particle::new(cur, pos, Velocity { x: 0.0, y: -0.5 }));
+                    }
+                },
+                _ => {
+                    particles.push(Particle::new(
+                        mat.clone(),
+                        pos,
+                        Velocity { x: 0.0, y: -0.5 },
+                    ));
+                },
+            }
+        }
+
+        true
+    }
+
+    /// If every pixel in `pixels` is connected to solid terrain through its 4 neighbors,
+    ///   so a body resting on terrain by a corner or only touching it diagonally doesn't leave floating pixels.
+    fn connected<C: Chunk>(pixels: &AHashSet<(i64, i64)>, chunk_handler: &ChunkHandler<C>) -> bool {
+        let neighbors = |(x, y): (i64, i64)| [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)];
+
+        let mut stack: Vec<(i64, i64)> = pixels
+            .iter()
+            .copied()
+            .filter(|&p| {
+                neighbors(p).into_iter().any(|n| {
+                    !pixels.contains(&n)
+                        && chunk_handler
+                            .pixel(n.0, n.1)
+                            .is_ok_and(|m| m.physics == PhysicsType::Solid)
+                })
+            })
+            .collect();
+        let mut reached: AHashSet<(i64, i64)> = stack.iter().copied().collect();
+        while let Some(p) = stack.pop() {
+            for n in neighbors(p) {
+                if pixels.contains(&n) && reached.insert(n) {
+                    stack.push(n);
+                }
+            }
+        }
+
+        reached.len() == pixels.len()
+    }
+}
+
+impl Default for RigidBodySettling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::{
+        world::{
+            material::{self, color::Color},
+            test_chunk::{self, TestChunk},
+            World,
+        },
+        Settings,
+    };
+
+    fn solid_at<C: Chunk>(chunk_handler: &ChunkHandler<C>, x: i64, y: i64) -> bool {
+        chunk_handler.pixel(x, y).unwrap().physics == PhysicsType::Solid
+    }
+
+    #[test]
+    fn stamp_needs_connected_terrain() {
+        let mut chunk_handler = test_chunk::chunk_handler([(0, 0)]);
+        let mut physics = Physics::new();
+        let mut particles = vec![];
+        chunk_handler
+            .set_pixel(50, 60, test_chunk::stone())
+            .unwrap();
+
+        // floating
+        let rb = test_chunk::stone_body(10, 10, (4.0, 3.0), &mut physics);
+        let handle = rb.body.unwrap();
+        assert!(!RigidBodySettling::stamp(
+            &rb,
+            handle,
+            &mut chunk_handler,
+            &physics,
+            &mut particles
+        ));
+        assert!(!solid_at(&chunk_handler, 45, 35));
+
+        // only touching diagonally
+        let rb = test_chunk::stone_body(10, 10, (4.0, 5.0), &mut physics);
+        let handle = rb.body.unwrap();
+        assert!(!RigidBodySettling::stamp(
+            &rb,
+            handle,
+            &mut chunk_handler,
+            &physics,
+            &mut particles
+        ));
+        assert!(!solid_at(&chunk_handler, 45, 55));
+
+        // resting on it
+        chunk_handler
+            .set_pixel(49, 60, test_chunk::stone())
+            .unwrap();
+        assert!(RigidBodySettling::stamp(
+            &rb,
+            handle,
+            &mut chunk_handler,
+            &physics,
+            &mut particles
+        ));
+        assert!((40..50).all(|x| (50..60).all(|y| solid_at(&chunk_handler, x, y))));
+        assert!(particles.is_empty());
+    }
+
+    #[test]
+    fn can_opt_out() {
+        let registries = test_chunk::registries();
+        let mut physics = Physics::new();
+
+        let mut rb = test_chunk::stone_body(10, 10, (0.0, 0.0), &mut physics);
+        assert!(RigidBodySettling::can_settle(&rb, &registries));
+        rb.settle = false;
+        assert!(!RigidBodySettling::can_settle(&rb, &registries));
+
+        let mut rb = test_chunk::stone_body(10, 10, (0.0, 0.0), &mut physics);
+        rb.pixels[0] = material::TEST.instance(PhysicsType::Solid, Color::GRAY);
+        assert!(!RigidBodySettling::can_settle(&rb, &registries));
+    }
+
+    /// A body dropped onto a stone floor at y 60 in chunk (0, 0)
+    fn dropped(mat: &MaterialInstance) -> World<TestChunk> {
+        let mut world = test_chunk::world((-1..=1).flat_map(|y| (-1..=1).map(move |x| (x, y))));
+        for x in 0..100 {
+            for y in 60..70 {
+                world
+                    .chunk_handler
+                    .set_pixel(x, y, test_chunk::stone())
+                    .unwrap();
+            }
+        }
+        let rb = test_chunk::body(mat, 10, 10, (4.0, 4.5), &mut world.physics);
+        world.rigidbodies.push(rb);
+        world.settling.sleep_time = 0.5;
+        world
+    }
+
+    #[test]
+    fn resting_body_settles() {
+        let settings = || Settings { fracture_rigidbodies: false, ..Settings::default() };
+
+        let mut world = dropped(&test_chunk::stone());
+        test_chunk::tick(&mut world, settings(), 300);
+        assert!(world.rigidbodies.is_empty());
+        assert!(world.physics.bodies.iter().all(|(_, b)| !b.is_dynamic()));
+        assert!(solid_at(&world.chunk_handler, 45, 55));
+
+        let mut world = dropped(&material::TEST.instance(PhysicsType::Solid, Color::GRAY));
+        test_chunk::tick(&mut world, settings(), 300);
+        assert_eq!(world.rigidbodies.len(), 1);
+        assert!(!solid_at(&world.chunk_handler, 45, 55));
+    }
+}
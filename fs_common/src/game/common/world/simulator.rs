@@ -3,10 +3,9 @@ use std::sync::Arc;
 
 use chunksystem::ChunkQuery;
 use fastrand::Rng;
-use rapier2d::na::Isometry2;
 
 use crate::game::common::world::material::{MaterialInstance, PhysicsType};
-use crate::game::common::world::{rigidbody, CHUNK_SIZE};
+use crate::game::common::world::CHUNK_SIZE;
 use crate::game::common::{Rect, Registries};
 
 use super::chunk_access::FSChunkAccess;
@@ -167,7 +166,7 @@ impl SimulationHelperChunk<'_, '_> {
     fn light_from_index(&self, (ch, px, ..): (usize, usize, u16, u16)) -> &[f32; 3] {
         // Safety: slicing [f32; 4] as &[f32; 3] will never fail
         unsafe {
-            (*self.chunk_data[ch].lights[px].get())[0..3]
+            (&*self.chunk_data[ch].lights[px].get())[0..3]
                 .try_into()
                 .unwrap_unchecked()
         }
@@ -183,7 +182,7 @@ impl SimulationHelperChunk<'_, '_> {
         &self,
         (ch, px, ..): (usize, usize, u16, u16),
     ) -> [f32; 3] {
-        (*self
+        (&*self
             .chunk_data
             .get_unchecked(ch)
             .lights
@@ -536,6 +535,7 @@ impl Simulator {
         rigidbodies: &mut Vec<FSRigidBody>,
         physics: &mut Physics,
         particles: &mut Vec<Particle>,
+        registries: &Registries,
     ) {
         let mut dirty = vec![false; rigidbodies.len()];
         let mut needs_remesh = vec![false; rigidbodies.len()];
@@ -559,8 +559,8 @@ impl Simulator {
                 };
 
                 let rng = fastrand::Rng::new();
-                for rb_y in 0..rb_w {
-                    for rb_x in 0..rb_h {
+                for rb_y in 0..rb_h {
+                    for rb_x in 0..rb_w {
                         let tx = f32::from(rb_x) * c - f32::from(rb_y) * s + pos_x;
                         let ty = f32::from(rb_x) * s + f32::from(rb_y) * c + pos_y;
 
@@ -598,44 +598,9 @@ impl Simulator {
         let mut new_rb: Vec<FSRigidBody> = rigidbodies
             .drain(..)
             .enumerate()
-            .flat_map(|(i, mut rb): (usize, FSRigidBody)| {
+            .flat_map(|(i, rb): (usize, FSRigidBody)| {
                 if needs_remesh[i] {
-                    let pos = (
-                        rb.get_body(physics).unwrap().translation().x,
-                        rb.get_body(physics).unwrap().translation().y,
-                    );
-
-                    let rb_pos = *rb.get_body(physics).unwrap().translation();
-                    let rb_angle = rb.get_body(physics).unwrap().rotation().angle();
-                    let rb_linear_velocity = *rb.get_body(physics).unwrap().linvel();
-                    let rb_angular_velocity = rb.get_body(physics).unwrap().angvel();
-
-                    physics.bodies.remove(
-                        rb.body.take().unwrap(),
-                        &mut physics.islands,
-                        &mut physics.colliders,
-                        &mut physics.impulse_joints,
-                        &mut physics.multibody_joints,
-                        true,
-                    );
-                    let mut r = rigidbody::FSRigidBody::make_bodies(
-                        &rb.pixels, rb.width, rb.height, physics, pos,
-                    )
-                    .unwrap_or_default();
-
-                    for rb in &mut r {
-                        rb.get_body_mut(physics)
-                            .unwrap()
-                            .set_position(Isometry2::new(rb_pos, rb_angle), true);
-                        rb.get_body_mut(physics)
-                            .unwrap()
-                            .set_linvel(rb_linear_velocity, true);
-                        rb.get_body_mut(physics)
-                            .unwrap()
-                            .set_angvel(rb_angular_velocity, true);
-                    }
-
-                    r
+                    rb.remesh(physics, registries)
                 } else {
                     vec![rb]
                 }
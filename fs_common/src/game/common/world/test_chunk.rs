@@ -0,0 +1,391 @@
+//! A minimal [`Chunk`] and helpers for tests that need loaded terrain without a client or server.
+
+use std::sync::Arc;
+
+use specs::{Builder, WorldExt};
+
+use crate::game::common::{FileHelper, Rect, Registries, Settings};
+
+use super::{
+    chunk_data::{CommonChunkData, SidedChunkData},
+    chunk_handler::ChunkHandler,
+    chunk_index::{ChunkLocalIndex, ChunkLocalPosition},
+    gen::TestGenerator,
+    material::{self, color::Color, MaterialInstance, PhysicsType},
+    mesh::Mesh,
+    physics::Physics,
+    rigidbody::FSRigidBody,
+    tile_entity::{TileEntity, TileEntityCommon, TileEntitySided},
+    Chunk, ChunkRigidBodyState, ChunkState, Loader, Position, SidedChunk, World, CHUNK_AREA,
+    CHUNK_SIZE,
+};
+
+pub struct TestChunk {
+    pub data: CommonChunkData<Self>,
+    pub color_data: Box<[Color; CHUNK_AREA]>,
+    pub light_data: Box<[[f32; 4]; CHUNK_AREA]>,
+    pub background_data: Box<[Color; CHUNK_AREA]>,
+}
+
+impl SidedChunkData for TestChunk {
+    type TileEntityData = TestTileEntity;
+}
+
+#[derive(Default)]
+pub struct TestTileEntity;
+
+impl TileEntitySided for TestTileEntity {
+    type D = TestChunk;
+}
+
+/// A chunk handler with `chunks` loaded, active, and filled with air
+pub fn chunk_handler(chunks: impl IntoIterator<Item = (i32, i32)>) -> ChunkHandler<TestChunk> {
+    let mut ch = ChunkHandler::new(TestGenerator::new(), None);
+    for (x, y) in chunks {
+        let mut chunk = TestChunk::new_empty(x, y);
+        chunk.set_pixels(Box::new(std::array::from_fn(|_| MaterialInstance::air())));
+        chunk.set_background_pixels(Box::new(std::array::from_fn(|_| MaterialInstance::air())));
+        chunk.set_state(ChunkState::Active);
+        ch.manager.insert((x, y), chunk);
+    }
+    ch
+}
+
+/// The chunks within `radius` of `center` in the order [`ChunkContext`](super::gen::populator::ChunkContext) expects,
+///   with each pixel set by `fill` from its world position
+pub fn chunk_area(
+    center: (i32, i32),
+    radius: i32,
+    fill: impl Fn(i64, i64) -> MaterialInstance,
+) -> Vec<TestChunk> {
+    (-radius..=radius)
+        .flat_map(|y| (-radius..=radius).map(move |x| (center.0 + x, center.1 + y)))
+        .map(|(chunk_x, chunk_y)| {
+            let mut chunk = TestChunk::new_empty(chunk_x, chunk_y);
+            chunk.set_pixels(Box::new(std::array::from_fn(|i| {
+                let p = ChunkLocalPosition::from(ChunkLocalIndex::new(i).unwrap());
+                fill(
+                    i64::from(chunk_x) * i64::from(CHUNK_SIZE) + i64::from(p.x()),
+                    i64::from(chunk_y) * i64::from(CHUNK_SIZE) + i64::from(p.y()),
+                )
+            })));
+            chunk
+        })
+        .collect()
+}
+
+/// A world with only `chunks` loaded (see [`chunk_handler`]) and no rigidbodies.
+/// A [`Loader`] at the origin keeps the chunks near it active.
+pub fn world(chunks: impl IntoIterator<Item = (i32, i32)>) -> World<TestChunk> {
+    let mut world = World::create(None, Some(1), &registries());
+    world.chunk_handler = chunk_handler(chunks);
+    world.rigidbodies.clear();
+    world.physics = Physics::new();
+    world
+        .ecs
+        .create_entity()
+        .with(Loader)
+        .with(Position { x: 0.0, y: 0.0 })
+        .build();
+    world
+}
+
+/// Runs `ticks` ticks of `world` the way the server does, each followed by a physics step.
+/// New chunks are never loaded, whatever `settings` says.
+pub fn tick(world: &mut World<TestChunk>, settings: Settings, ticks: u32) {
+    let settings = Settings { load_chunks: false, ..settings };
+    let registries = Arc::new(registries());
+    let file_helper = FileHelper::new(std::env::temp_dir(), vec![]).unwrap();
+
+    for tick_time in 0..ticks {
+        world.tick(tick_time, &settings, registries.clone(), &file_helper);
+        world.tick_physics(&settings);
+    }
+}
+
+/// Registries with the built in materials
+pub fn registries() -> Registries {
+    Registries {
+        materials: material::init_material_types(),
+        ..Registries::empty()
+    }
+}
+
+/// Registries loaded from the game's assets, for tests of world generation
+pub fn asset_registries() -> Registries {
+    let file_helper =
+        FileHelper::new("../gamedir/".into(), vec!["../gamedir/assets/".into()]).unwrap();
+    Registries::try_init(&file_helper).unwrap()
+}
+
+pub fn stone() -> MaterialInstance {
+    material::SMOOTH_STONE.instance(PhysicsType::Solid, Color::GRAY)
+}
+
+pub fn water() -> MaterialInstance {
+    material::WATER.instance(PhysicsType::Liquid, Color::BLUE)
+}
+
+/// A `width` x `height` body of `mat` with its top left corner at `position` (in physics units)
+pub fn body(
+    mat: &MaterialInstance,
+    width: u16,
+    height: u16,
+    position: (f32, f32),
+    physics: &mut Physics,
+) -> FSRigidBody {
+    FSRigidBody::make_bodies(
+        &vec![mat.clone(); usize::from(width) * usize::from(height)],
+        width,
+        height,
+        physics,
+        position,
+        &registries(),
+    )
+    .unwrap()
+    .remove(0)
+}
+
+/// A [`body`] of [`stone`]
+pub fn stone_body(
+    width: u16,
+    height: u16,
+    position: (f32, f32),
+    physics: &mut Physics,
+) -> FSRigidBody {
+    body(&stone(), width, height, position, physics)
+}
+
+impl Chunk for TestChunk {
+    #[allow(clippy::large_stack_arrays)]
+    fn new_empty(chunk_x: i32, chunk_y: i32) -> Self {
+        Self {
+            data: CommonChunkData::new(chunk_x, chunk_y),
+            color_data: Box::new([Color::TRANSPARENT; CHUNK_AREA]),
+            light_data: Box::new([[0.0; 4]; CHUNK_AREA]),
+            background_data: Box::new([Color::TRANSPARENT; CHUNK_AREA]),
+        }
+    }
+
+    fn chunk_x(&self) -> i32 {
+        self.data.chunk_x
+    }
+
+    fn chunk_y(&self) -> i32 {
+        self.data.chunk_y
+    }
+
+    fn state(&self) -> ChunkState {
+        self.data.state
+    }
+
+    fn set_state(&mut self, state: ChunkState) {
+        self.data.state = state;
+    }
+
+    fn dirty_rect(&self) -> Option<Rect<i32>> {
+        self.data.dirty_rect
+    }
+
+    fn set_dirty_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.dirty_rect = rect;
+    }
+
+    fn integrity_rect(&self) -> Option<Rect<i32>> {
+        self.data.integrity_rect
+    }
+
+    fn set_integrity_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.integrity_rect = rect;
+    }
+
+    fn refresh(&mut self) {}
+
+    fn set_pixel(&mut self, pos: ChunkLocalPosition, mat: MaterialInstance) -> Result<(), String> {
+        self.data.set(pos, mat, |_| Ok(()))
+    }
+
+    unsafe fn set_pixel_unchecked(&mut self, pos: ChunkLocalPosition, mat: MaterialInstance) {
+        self.data.set_unchecked(pos, mat);
+    }
+
+    fn pixel(&self, pos: ChunkLocalPosition) -> Result<&MaterialInstance, String> {
+        self.data.pixel(pos)
+    }
+
+    unsafe fn pixel_unchecked(&self, pos: ChunkLocalPosition) -> &MaterialInstance {
+        self.data.pixel_unchecked(pos)
+    }
+
+    fn replace_pixel<F>(&mut self, pos: ChunkLocalPosition, cb: F) -> Result<bool, String>
+    where
+        Self: Sized,
+        F: FnOnce(&MaterialInstance) -> Option<MaterialInstance>,
+    {
+        self.data.replace_pixel(pos, cb, |_| Ok(()))
+    }
+
+    fn set_light(&mut self, pos: ChunkLocalPosition, light: [f32; 3]) -> Result<(), String> {
+        self.data.set_light(pos, light, |_| Ok(()))
+    }
+
+    unsafe fn set_light_unchecked(&mut self, pos: ChunkLocalPosition, light: [f32; 3]) {
+        self.data.set_light_unchecked(pos, light);
+    }
+
+    fn light(&self, pos: ChunkLocalPosition) -> Result<&[f32; 3], String> {
+        self.data.light(pos)
+    }
+
+    unsafe fn light_unchecked(&self, pos: ChunkLocalPosition) -> &[f32; 3] {
+        self.data.light_unchecked(pos)
+    }
+
+    fn set_color(&mut self, pos: ChunkLocalPosition, color: Color) {
+        let i: ChunkLocalIndex = pos.into();
+        self.color_data[i] = color;
+    }
+
+    fn color(&self, pos: ChunkLocalPosition) -> Color {
+        let i: ChunkLocalIndex = pos.into();
+        self.color_data[i]
+    }
+
+    fn set_pixels(&mut self, pixels: Box<[MaterialInstance; CHUNK_AREA]>) {
+        self.data.set_pixels(pixels);
+    }
+
+    fn pixels_mut(&mut self) -> &mut Option<Box<[MaterialInstance; CHUNK_AREA]>> {
+        &mut self.data.pixels
+    }
+
+    fn pixels(&self) -> &Option<Box<[MaterialInstance; CHUNK_AREA]>> {
+        &self.data.pixels
+    }
+
+    fn set_pixel_colors(&mut self, colors: Box<[Color; CHUNK_AREA]>) {
+        self.color_data = colors;
+    }
+
+    fn colors_mut(&mut self) -> &mut [Color; CHUNK_AREA] {
+        &mut self.color_data
+    }
+
+    fn colors(&self) -> &[Color; CHUNK_AREA] {
+        &self.color_data
+    }
+
+    fn set_background_pixels(&mut self, pixels: Box<[MaterialInstance; CHUNK_AREA]>) {
+        self.data.background = Some(pixels);
+    }
+
+    fn background_pixels_mut(&mut self) -> &mut Option<Box<[MaterialInstance; CHUNK_AREA]>> {
+        &mut self.data.background
+    }
+
+    fn background_pixels(&self) -> &Option<Box<[MaterialInstance; CHUNK_AREA]>> {
+        &self.data.background
+    }
+
+    fn set_background_pixel_colors(&mut self, colors: Box<[Color; CHUNK_AREA]>) {
+        self.background_data = colors;
+    }
+
+    fn background_colors_mut(&mut self) -> &mut [Color; CHUNK_AREA] {
+        &mut self.background_data
+    }
+
+    fn background_colors(&self) -> &[Color; CHUNK_AREA] {
+        &self.background_data
+    }
+
+    fn mark_dirty(&mut self) {}
+
+    fn generate_mesh_tiles(&mut self, tiles: &[usize]) -> Result<(), String> {
+        self.data.generate_mesh_tiles(tiles).map(|_| ())
+    }
+
+    fn mesh_loops(&self) -> &Option<Vec<Mesh>> {
+        &self.data.mesh_simplified
+    }
+
+    fn mesh_rect(&self) -> Option<Rect<i32>> {
+        self.data.mesh_rect
+    }
+
+    fn set_mesh_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.mesh_rect = rect;
+    }
+
+    fn rigidbody(&self) -> &Option<ChunkRigidBodyState> {
+        &self.data.rigidbody
+    }
+
+    fn rigidbody_mut(&mut self) -> &mut Option<ChunkRigidBodyState> {
+        &mut self.data.rigidbody
+    }
+
+    fn set_rigidbody(&mut self, body: Option<ChunkRigidBodyState>) {
+        self.data.rigidbody = body;
+    }
+
+    fn lights_mut(&mut self) -> &mut [[f32; 4]; CHUNK_AREA] {
+        self.light_data.as_mut()
+    }
+
+    fn lights(&self) -> &[[f32; 4]; CHUNK_AREA] {
+        self.light_data.as_ref()
+    }
+
+    fn set_background(
+        &mut self,
+        pos: ChunkLocalPosition,
+        mat: MaterialInstance,
+    ) -> Result<(), String> {
+        self.data.set_background(pos, mat, |_| Ok(()))
+    }
+
+    unsafe fn set_background_unchecked(&mut self, pos: ChunkLocalPosition, mat: MaterialInstance) {
+        self.data.set_background_unchecked(pos, mat);
+    }
+
+    fn background(&self, pos: ChunkLocalPosition) -> Result<&MaterialInstance, String> {
+        self.data.background(pos)
+    }
+
+    unsafe fn background_unchecked(&self, pos: ChunkLocalPosition) -> &MaterialInstance {
+        self.data.background_unchecked(pos)
+    }
+
+    fn add_tile_entity(&mut self, te: TileEntityCommon) {
+        self.data.tile_entities.push(te.into());
+    }
+
+    fn common_tile_entities(&self) -> Box<dyn Iterator<Item = &TileEntityCommon> + '_> {
+        Box::new(self.data.tile_entities.iter().map(|te| &te.common))
+    }
+
+    fn common_tile_entities_mut(&mut self) -> Box<dyn Iterator<Item = &mut TileEntityCommon> + '_> {
+        Box::new(self.data.tile_entities.iter_mut().map(|te| &mut te.common))
+    }
+}
+
+impl SidedChunk for TestChunk {
+    type S = Self;
+
+    fn sided_tile_entities(&self) -> &[TileEntity<<Self::S as SidedChunkData>::TileEntityData>] {
+        &self.data.tile_entities
+    }
+
+    fn sided_tile_entities_mut(
+        &mut self,
+    ) -> &mut [TileEntity<<Self::S as SidedChunkData>::TileEntityData>] {
+        &mut self.data.tile_entities
+    }
+
+    fn sided_tile_entities_removable(
+        &mut self,
+    ) -> &mut Vec<TileEntity<<Self::S as SidedChunkData>::TileEntityData>> {
+        &mut self.data.tile_entities
+    }
+}
@@ -2,7 +2,7 @@ use chunksystem::ChunkQueryOne;
 
 use crate::game::common::{FileHelper, Registries};
 
-use super::{material::buf::MaterialRect, Chunk};
+use super::{explosion::Explosion, material::buf::MaterialRect, Chunk};
 
 pub struct TileEntity<S> {
     pub common: TileEntityCommon,
@@ -31,6 +31,8 @@ pub struct TileEntityTickContext<'a, 'b, D> {
     pub registries: &'a Registries,
     pub file_helper: &'a FileHelper,
     pub chunks: &'a mut ChunkQueryOne<'b, D>,
+    /// Applied with [`World::explode`](super::World::explode) after chunks are done ticking
+    pub explosions: &'a mut Vec<Explosion>,
 }
 
 impl<S: TileEntitySided> TileEntity<S>
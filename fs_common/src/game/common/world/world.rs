@@ -7,6 +7,7 @@ use std::{
 };
 
 use crate::game::common::{
+    registry::RegistryID,
     world::{physics::PHYSICS_SCALE, ChunkRigidBodyState},
     FileHelper, Registries, Settings,
 };
@@ -14,7 +15,7 @@ use crate::game::common::{
 use chunksystem::ChunkQuery;
 use rapier2d::{
     na::{Point2, Vector2},
-    prelude::{ColliderBuilder, InteractionGroups, RigidBodyBuilder, RigidBodyType},
+    prelude::{Collider, ColliderBuilder, InteractionGroups, RigidBodyBuilder, RigidBodyType},
 };
 // use salva2d::{integrations::rapier::ColliderSampling, object::Boundary};
 use specs::{
@@ -23,18 +24,29 @@ use specs::{
 };
 
 use super::{
+    buoyancy::{self, Submersion},
     chunk_access::FSChunkAccess,
     chunk_data::SidedChunkData,
     chunk_handler::{ChunkHandler, ChunkTickContext},
+    collision_tiles_in,
     entity::{
         CollisionDetector, GameEntity, Hitbox, Persistent, PhysicsEntity, Player,
         UpdatePhysicsEntities,
     },
-    gen::{biome_test::BiomeTestGenerator, structure::StructureNode},
+    explosion::{Explosion, PendingExplosions},
+    fracture::RigidBodyFracture,
+    gen::{
+        structure::{configured_structure::ConfiguredStructure, set::StructureSet, StructureNode},
+        surface::SurfaceGenerator,
+    },
+    integrity::StructuralIntegrity,
     material::{self, color::Color, MaterialInstance, PhysicsType},
+    mesh::Mesh,
     particle::{Particle, ParticleSystem, UpdateParticles},
     physics::Physics,
     rigidbody::FSRigidBody,
+    rope::{Rope, RopeAnchor},
+    settle::RigidBodySettling,
     simulator,
     tile_entity::TileEntitySided,
     ApplyRigidBodies, AutoTarget, Camera, Chunk, CollisionFlags, DeltaTime, FilePersistent, Loader,
@@ -54,7 +66,11 @@ pub struct World<C: Chunk> {
     pub chunk_handler: ChunkHandler<C>,
     pub net_mode: WorldNetworkMode,
     pub rigidbodies: Vec<FSRigidBody>,
+    pub ropes: Vec<Rope>,
     pub physics: Physics,
+    pub fracture: RigidBodyFracture,
+    pub structural_integrity: StructuralIntegrity,
+    pub settling: RigidBodySettling,
     pub seed: i32,
 }
 
@@ -65,6 +81,7 @@ pub fn ecs() -> specs::World {
     ecs.insert(DeltaTime(Duration::from_millis(1)));
     ecs.insert(TickTime(0));
     ecs.insert(ParticleSystem::default());
+    ecs.insert(PendingExplosions::default());
     ecs.register::<Position>();
     ecs.register::<Velocity>();
     ecs.register::<GameEntity>();
@@ -83,7 +100,7 @@ pub fn ecs() -> specs::World {
 
 impl<C: Chunk + Send + Sync + 'static> World<C> {
     #[profiling::function]
-    pub fn create(path: Option<PathBuf>, seed: Option<i32>) -> Self {
+    pub fn create(path: Option<PathBuf>, seed: Option<i32>, registries: &Registries) -> Self {
         let mut ecs = ecs();
 
         if let Some(path) = &path {
@@ -129,11 +146,15 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
 
         let mut w = World {
             ecs,
-            chunk_handler: ChunkHandler::new(BiomeTestGenerator::new(), path.clone()),
+            chunk_handler: ChunkHandler::new(SurfaceGenerator::new(), path.clone()),
             path,
             net_mode: WorldNetworkMode::Local,
             rigidbodies: Vec::new(),
+            ropes: Vec::new(),
             physics: Physics::new(),
+            fracture: RigidBodyFracture::new(),
+            structural_integrity: StructuralIntegrity::new(),
+            settling: RigidBodySettling::new(),
             seed: seed.unwrap_or_else(|| {
                 let mut h = DefaultHasher::new();
                 (std::time::SystemTime::now()
@@ -169,7 +190,7 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
                 .collect();
 
             if let Ok(mut r) =
-                FSRigidBody::make_bodies(&pixels, 40, 40, &mut w.physics, (-1.0, -7.0))
+                FSRigidBody::make_bodies(&pixels, 40, 40, &mut w.physics, (-1.0, -7.0), registries)
             {
                 w.rigidbodies.append(&mut r);
             }
@@ -195,7 +216,7 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
                 .collect();
 
             if let Ok(mut r) =
-                FSRigidBody::make_bodies(&pixels, 40, 40, &mut w.physics, (-0.0, -10.0))
+                FSRigidBody::make_bodies(&pixels, 40, 40, &mut w.physics, (-0.0, -10.0), registries)
             {
                 w.rigidbodies.append(&mut r);
             }
@@ -225,7 +246,7 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
                 .collect();
 
             if let Ok(mut r) =
-                FSRigidBody::make_bodies(&pixels, 40, 40, &mut w.physics, (2.0, -6.5))
+                FSRigidBody::make_bodies(&pixels, 40, 40, &mut w.physics, (2.0, -6.5), registries)
             {
                 w.rigidbodies.append(&mut r);
             }
@@ -259,6 +280,7 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
                     30,
                     &mut w.physics,
                     (5.0 + n as f32 * 2.0, -7.0 + n as f32 * -0.75),
+                    registries,
                 ) {
                     w.rigidbodies.append(&mut r);
                 }
@@ -338,57 +360,189 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
         update_auto_targets.run_now(&self.ecs);
     }
 
-    pub fn raycast(
-        &self,
-        mut x1: i64,
-        mut y1: i64,
-        x2: i64,
-        y2: i64,
-        collide_filder: fn((i64, i64), &MaterialInstance) -> bool,
-    ) -> Option<((i64, i64), &MaterialInstance)> {
-        let check_pixel = |x: i64, y: i64| {
-            let r = self.chunk_handler.pixel(x, y);
-            if let Ok(m) = r {
-                if m.physics != PhysicsType::Air {
-                    return Some(((x, y), m));
-                }
-            }
-            None
+    /// Blows up everything within `radius` pixels of (`x`, `y`), up to [`MAX_RADIUS`](super::explosion::MAX_RADIUS).
+    ///
+    /// The blast's strength falls off from `power` at the center to 0 at `radius`,
+    ///   and breaks any pixels (including in rigidbodies) where it is stronger than their material's [`blast_resistance`](material::Material::blast_resistance).
+    /// Broken pixels become particles flying outwards, and rigidbodies and physics entities in range get pushed away.
+    #[profiling::function]
+    pub fn explode(&mut self, x: f64, y: f64, radius: f32, power: f32, registries: &Registries) {
+        if !x.is_finite() || !y.is_finite() {
+            return;
+        }
+        let explosion = Explosion::new(x, y, radius, power);
+        let radius = explosion.radius;
+        let resistance = |m: &MaterialInstance| {
+            registries
+                .materials
+                .get(&m.material_id)
+                .map_or(0.0, |m| m.blast_resistance)
         };
+        let rng = fastrand::Rng::new();
+        let mut particles = vec![];
 
-        let x_dist = (x2 - x1).abs();
-        let y_dist = -(y2 - y1).abs();
-        let x_step = if x1 < x2 { 1 } else { -1 };
-        let y_step = if y1 < y2 { 1 } else { -1 };
-        let mut error = x_dist + y_dist;
+        for mut rb in std::mem::take(&mut self.rigidbodies) {
+            let Some(body) = rb.get_body(&self.physics) else {
+                self.rigidbodies.push(rb);
+                continue;
+            };
 
-        if let Some(r) = check_pixel(x1, y1) {
-            if collide_filder(r.0, r.1) {
-                return Some(r);
+            // no pixel can be further from the body's origin than this
+            let reach = f32::from(rb.width).hypot(f32::from(rb.height));
+            let origin = body.translation() * PHYSICS_SCALE;
+            if Vector2::new(origin.x - x as f32, origin.y - y as f32).norm() > radius + reach {
+                self.rigidbodies.push(rb);
+                continue;
             }
-        }
 
-        while x1 != x2 || y1 != y2 {
-            let tmp = 2 * error;
+            let iso = *body.position();
+            let width = usize::from(rb.width);
+            let mut changed = false;
+            for (i, px) in rb.pixels.iter_mut().enumerate() {
+                if px.physics == PhysicsType::Air {
+                    continue;
+                }
 
-            if tmp > y_dist {
-                error += y_dist;
-                x1 += x_step;
+                let p =
+                    iso * Point2::new(
+                        (i % width) as f32 / PHYSICS_SCALE,
+                        (i / width) as f32 / PHYSICS_SCALE,
+                    ) * PHYSICS_SCALE;
+                let (px_x, px_y) = (f64::from(p.x), f64::from(p.y));
+                let force = explosion.strength_at(px_x, px_y) - resistance(px);
+                if force > 0.0 {
+                    let m = std::mem::take(px);
+                    particles.push(explosion.particle(m, px_x, px_y, force, &rng));
+                    changed = true;
+                }
             }
 
-            if tmp < x_dist {
-                error += x_dist;
-                y1 += y_step;
+            if changed {
+                self.rigidbodies
+                    .append(&mut rb.remesh(&mut self.physics, registries));
+            } else {
+                self.rigidbodies.push(rb);
             }
+        }
 
-            if let Some(r) = check_pixel(x1, y1) {
-                if collide_filder(r.0, r.1) {
-                    return Some(r);
+        let r = radius.ceil() as i64;
+        let (cx, cy) = (x.floor() as i64, y.floor() as i64);
+        for py in cy - r..=cy + r {
+            for px in cx - r..=cx + r {
+                let strength = explosion.strength_at(px as f64, py as f64);
+                if strength <= 0.0 {
+                    continue;
+                }
+
+                let Ok(m) = self.chunk_handler.pixel(px, py) else {
+                    continue;
+                };
+                if matches!(m.physics, PhysicsType::Air | PhysicsType::Object) {
+                    continue;
+                }
+
+                let force = strength - resistance(m);
+                if force <= 0.0 {
+                    continue;
+                }
+
+                let m = m.clone();
+                if self
+                    .chunk_handler
+                    .set_pixel(px, py, MaterialInstance::air())
+                    .is_ok()
+                {
+                    particles.push(explosion.particle(m, px as f64, py as f64, force, &rng));
                 }
             }
         }
 
-        None
+        explosion.push_bodies(&mut self.physics);
+        explosion.push_entities(&self.ecs);
+
+        self.ecs
+            .write_resource::<ParticleSystem>()
+            .active
+            .append(&mut particles);
+    }
+
+    /// Creates `rope`'s segments and joints and adds it to the world
+    pub fn add_rope(&mut self, rope: Rope) -> Result<(), String> {
+        let rope = rope.attach(&mut self.physics)?;
+        self.ropes.push(rope);
+        Ok(())
+    }
+
+    /// What a rope end at the world pixel position `(x, y)` would hold on to, see [`RopeAnchor::at`]
+    pub fn rope_anchor_at(&self, x: f32, y: f32) -> Option<RopeAnchor> {
+        RopeAnchor::at(
+            Point2::new(x, y),
+            &self.rigidbodies,
+            &self.chunk_handler,
+            &self.physics,
+        )
+    }
+
+    /// Removes the rope at `index` along with its segments and joints
+    pub fn remove_rope(&mut self, index: usize) -> Rope {
+        let mut rope = self.ropes.remove(index);
+        rope.detach(&mut self.physics);
+        rope
+    }
+
+    /// Finds the nearest chunk to the world pixel position `pos` where the structure set `id` will generate,
+    ///   searching up to `max_distance` chunks away.
+    pub fn locate_structure_set(
+        &self,
+        id: &RegistryID<StructureSet>,
+        pos: (f64, f64),
+        max_distance: u32,
+        registries: &Registries,
+    ) -> Result<Option<(i32, i32)>, String> {
+        let set = registries
+            .structure_sets
+            .get(id)
+            .ok_or_else(|| format!("Unknown structure set: {id}"))?;
+
+        Ok(set.locate(
+            Self::pos_to_chunk(pos),
+            self.seed as u64,
+            registries,
+            max_distance,
+        ))
+    }
+
+    /// Finds the nearest chunk to the world pixel position `pos` where the configured structure `id` will generate,
+    ///   from any structure set that generates it, searching up to `max_distance` chunks away.
+    pub fn locate_configured_structure(
+        &self,
+        id: &RegistryID<ConfiguredStructure>,
+        pos: (f64, f64),
+        max_distance: u32,
+        registries: &Registries,
+    ) -> Result<Option<(i32, i32)>, String> {
+        if registries.configured_structures.get(id).is_none() {
+            return Err(format!("Unknown configured structure: {id}"));
+        }
+
+        let chunk = Self::pos_to_chunk(pos);
+        let dist_sq = |(x, y): (i32, i32)| {
+            (i64::from(x) - i64::from(chunk.0)).pow(2) + (i64::from(y) - i64::from(chunk.1)).pow(2)
+        };
+
+        Ok((&registries.structure_sets)
+            .into_iter()
+            .filter_map(|(_, set)| {
+                set.locate_structure(id, chunk, self.seed as u64, registries, max_distance)
+            })
+            .min_by_key(|c| dist_sq(*c)))
+    }
+
+    fn pos_to_chunk(pos: (f64, f64)) -> (i32, i32) {
+        (
+            (pos.0 / f64::from(CHUNK_SIZE)).floor() as i32,
+            (pos.1 / f64::from(CHUNK_SIZE)).floor() as i32,
+        )
     }
 }
 
@@ -408,6 +562,7 @@ where
 
         {
             profiling::scope!("fill rigidbodies");
+            let gravity = self.physics.gravity;
             for rb in &mut self.rigidbodies {
                 if let Some(body) = rb.get_body_mut(&mut self.physics) {
                     let rb_w = rb.width;
@@ -417,6 +572,7 @@ where
                     let pos_y = body.translation().y * PHYSICS_SCALE;
 
                     let mut impediment = 0.0_f32;
+                    let mut submersion = Submersion::new();
 
                     for rb_y in 0..rb_h {
                         for rb_x in 0..rb_w {
@@ -425,6 +581,13 @@ where
 
                             let cur = &rb.pixels[(rb_x + rb_y * rb_w) as usize];
                             if cur.physics != PhysicsType::Air {
+                                submersion.sample(
+                                    &self.chunk_handler,
+                                    tx,
+                                    ty,
+                                    buoyancy::is_edge(rb, rb_x, rb_y),
+                                );
+
                                 let world = self.chunk_handler.pixel(tx as i64, ty as i64);
                                 if let Ok(mat) = world {
                                     if mat.material_id == *material::AIR {
@@ -437,6 +600,43 @@ where
                                                 ..cur.clone()
                                             },
                                         );
+                                    } else if mat.physics == PhysicsType::Liquid {
+                                        // push the liquid aside so it doesn't flow through the body
+                                        let m = mat.clone();
+                                        let res = self.chunk_handler.set_pixel(
+                                            tx as i64,
+                                            ty as i64,
+                                            MaterialInstance {
+                                                physics: PhysicsType::Object,
+                                                ..cur.clone()
+                                            },
+                                        );
+
+                                        if res.is_ok()
+                                            && !self.chunk_handler.displace_pixel(
+                                                tx as i64,
+                                                ty as i64,
+                                                m.clone(),
+                                            )
+                                        {
+                                            let point_velocity =
+                                                body.velocity_at_point(&Point2::new(
+                                                    tx / PHYSICS_SCALE,
+                                                    ty / PHYSICS_SCALE,
+                                                ));
+                                            let part = Particle::new(
+                                                m,
+                                                Position { x: f64::from(tx), y: f64::from(ty) },
+                                                Velocity {
+                                                    x: f64::from(point_velocity.x * 0.1),
+                                                    y: f64::from(point_velocity.y * 0.1 - 0.5),
+                                                },
+                                            );
+                                            self.ecs
+                                                .write_resource::<ParticleSystem>()
+                                                .active
+                                                .push(part);
+                                        }
                                     } else if mat.physics == PhysicsType::Sand {
                                         // let local_point = Vec2::new(f32::from(rb_x) / f32::from(rb_w), f32::from(rb_y) / f32::from(rb_h));
                                         let world_point =
@@ -446,11 +646,14 @@ where
                                             tx / PHYSICS_SCALE,
                                             ty / PHYSICS_SCALE,
                                         ));
-                                        // TODO: extract constant into material property (like weight or something)
+                                        let drag = registries
+                                            .materials
+                                            .get(&mat.material_id)
+                                            .map_or(0.1, |m| m.drag);
                                         body.apply_impulse_at_point(
                                             Vector2::new(
-                                                -point_velocity.x * 0.1 / body.mass(),
-                                                -point_velocity.y * 0.1 / body.mass(),
+                                                -point_velocity.x * drag / body.mass(),
+                                                -point_velocity.y * drag / body.mass(),
                                             ),
                                             world_point,
                                             true,
@@ -574,6 +777,8 @@ where
                         }
                     }
 
+                    submersion.apply(rb, body, gravity, &registries);
+
                     // this gravity manipulation makes it so the body can come to a full stop in sand
                     // the if is to help with making sure the body is woken up by changes in impedement
 
@@ -781,6 +986,60 @@ where
                 &mut self.rigidbodies,
                 &mut self.physics,
                 &mut new_parts,
+                &registries,
+            );
+            self.ecs
+                .write_resource::<ParticleSystem>()
+                .active
+                .append(&mut new_parts);
+        }
+
+        {
+            profiling::scope!("explosions");
+            let pending = std::mem::take(&mut self.ecs.write_resource::<PendingExplosions>().0);
+            for e in pending {
+                self.explode(e.x, e.y, e.radius, e.power, &registries);
+            }
+        }
+
+        if settings.fracture_rigidbodies {
+            profiling::scope!("fracture rigidbodies");
+            let mut new_parts = Vec::new();
+            self.fracture.update(
+                &mut self.rigidbodies,
+                &mut self.physics,
+                &mut new_parts,
+                &registries,
+            );
+            self.ecs
+                .write_resource::<ParticleSystem>()
+                .active
+                .append(&mut new_parts);
+        } else {
+            // nothing else reads the impacts, so don't let them pile up
+            while self.physics.contact_force_events.try_recv().is_ok() {}
+        }
+
+        if settings.structural_integrity {
+            profiling::scope!("structural integrity");
+            self.structural_integrity.update(
+                &mut self.chunk_handler,
+                &mut self.physics,
+                &mut self.rigidbodies,
+                &registries,
+            );
+        }
+
+        if settings.settle_rigidbodies {
+            profiling::scope!("settle rigidbodies");
+            let mut new_parts = Vec::new();
+            self.settling.update(
+                &mut self.rigidbodies,
+                &mut self.chunk_handler,
+                &mut self.physics,
+                &mut new_parts,
+                settings.tick_speed,
+                &registries,
             );
             self.ecs
                 .write_resource::<ParticleSystem>()
@@ -788,9 +1047,23 @@ where
                 .append(&mut new_parts);
         }
 
+        {
+            profiling::scope!("update ropes");
+            for rope in &mut self.ropes {
+                rope.update(&self.chunk_handler, &mut self.physics, &self.rigidbodies);
+            }
+        }
+
         {
             profiling::scope!("update chunk collision");
             for c in self.chunk_handler.manager.chunks_iter_mut() {
+                if let Some(rect) = c.mesh_rect() {
+                    c.set_mesh_rect(None);
+                    let tiles = collision_tiles_in(rect).collect::<Vec<_>>();
+                    let _: Result<(), _> = c.generate_mesh_tiles(&tiles);
+                    replace_tile_colliders(&mut **c, &tiles, &mut self.physics);
+                }
+
                 if c.rigidbody().is_none() {
                     // if let Some(tr) = c.get_tris() {
                     //     let mut body_def = BodyDef::default();
@@ -815,36 +1088,18 @@ where
                     //     c.set_b2_body(Some(body));
                     // }
 
-                    if let Some(loops) = c.mesh_loops() {
+                    if let Some(tiles) = c.mesh_loops() {
                         let rigid_body = RigidBodyBuilder::fixed()
                             .translation(Vector2::new(
                                 (c.chunk_x() * i32::from(CHUNK_SIZE)) as f32 / PHYSICS_SCALE,
                                 (c.chunk_y() * i32::from(CHUNK_SIZE)) as f32 / PHYSICS_SCALE,
                             ))
                             .build();
-                        let mut colliders = Vec::new();
-
-                        for a_loop in loops.iter() {
-                            for pts in a_loop.iter() {
-                                let mut verts: Vec<Point2<f32>> = Vec::new();
-
-                                for p in pts.iter() {
-                                    verts.push(Point2::new(
-                                        p[0] as f32 / PHYSICS_SCALE,
-                                        p[1] as f32 / PHYSICS_SCALE,
-                                    ));
-                                }
-
-                                let collider = ColliderBuilder::polyline(verts, None)
-                                    .collision_groups(InteractionGroups::new(
-                                        CollisionFlags::WORLD.bits().into(),
-                                        CollisionFlags::RIGIDBODY.bits().into(),
-                                    ))
-                                    .density(0.0)
-                                    .build();
-                                colliders.push(collider);
-                            }
-                        }
+                        let colliders = tiles
+                            .iter()
+                            .enumerate()
+                            .flat_map(|(tile, mesh)| tile_colliders(tile, mesh))
+                            .collect();
 
                         c.set_rigidbody(Some(ChunkRigidBodyState::Inactive(
                             Box::new(rigid_body),
@@ -973,3 +1228,142 @@ where
 fn replace_object_with_air(mat: &MaterialInstance) -> Option<MaterialInstance> {
     (mat.physics == PhysicsType::Object).then(MaterialInstance::air)
 }
+
+/// Colliders for the mesh of one collision tile, with the tile's index as their `user_data`
+fn tile_colliders(tile: usize, mesh: &Mesh) -> impl Iterator<Item = Collider> + '_ {
+    mesh.iter().flatten().map(move |pts| {
+        let verts: Vec<Point2<f32>> = pts
+            .iter()
+            .map(|p| Point2::new(p[0] as f32 / PHYSICS_SCALE, p[1] as f32 / PHYSICS_SCALE))
+            .collect();
+
+        ColliderBuilder::polyline(verts, None)
+            .collision_groups(InteractionGroups::new(
+                CollisionFlags::WORLD.bits().into(),
+                (CollisionFlags::RIGIDBODY | CollisionFlags::ROPE)
+                    .bits()
+                    .into(),
+            ))
+            .density(0.0)
+            .user_data(tile as u128)
+            .build()
+    })
+}
+
+/// Swaps out the colliders of `tiles` in the chunk's body for ones matching their current mesh
+fn replace_tile_colliders<C: Chunk>(chunk: &mut C, tiles: &[usize], physics: &mut Physics) {
+    let Some(meshes) = chunk.mesh_loops() else {
+        return;
+    };
+    let new = tiles
+        .iter()
+        .flat_map(|&tile| tile_colliders(tile, &meshes[tile]))
+        .collect::<Vec<_>>();
+    let in_tiles = |c: &Collider| tiles.contains(&(c.user_data as usize));
+
+    match chunk.rigidbody_mut() {
+        Some(ChunkRigidBodyState::Active(handle)) => {
+            let Some(body) = physics.bodies.get(*handle) else {
+                return;
+            };
+            let old = body
+                .colliders()
+                .iter()
+                .copied()
+                .filter(|ch| physics.colliders.get(*ch).is_some_and(in_tiles))
+                .collect::<Vec<_>>();
+            for ch in old {
+                physics
+                    .colliders
+                    .remove(ch, &mut physics.islands, &mut physics.bodies, true);
+            }
+            for collider in new {
+                physics
+                    .colliders
+                    .insert_with_parent(collider, *handle, &mut physics.bodies);
+            }
+        },
+        Some(ChunkRigidBodyState::Inactive(_, colliders)) => {
+            colliders.retain(|c| !in_tiles(c));
+            colliders.extend(new);
+        },
+        None => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::world::{
+        test_chunk::{self, TestChunk},
+        ChunkState,
+    };
+
+    /// Chunks -1..=1 on both axes, with a stone floor from y 60 to 70 across chunk (0, 0)
+    fn floor_world() -> World<TestChunk> {
+        let mut world = test_chunk::world((-1..=1).flat_map(|y| (-1..=1).map(move |x| (x, y))));
+        for y in 60..70 {
+            for x in 0..100 {
+                world
+                    .chunk_handler
+                    .set_pixel(x, y, test_chunk::stone())
+                    .unwrap();
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn tick_runs_headless() {
+        let mut world = floor_world();
+        test_chunk::tick(&mut world, Settings::default(), 2);
+
+        let chunk = world.chunk_handler.manager.chunk_at((0, 0)).unwrap();
+        assert_eq!(chunk.state(), ChunkState::Active);
+        // built, but only added to physics once a body comes near
+        assert!(matches!(
+            chunk.rigidbody(),
+            Some(ChunkRigidBodyState::Inactive(..))
+        ));
+    }
+
+    #[test]
+    fn rigidbody_lands_on_terrain() {
+        let mut world = floor_world();
+        // breaking or settling would replace the body
+        let settings = Settings {
+            fracture_rigidbodies: false,
+            settle_rigidbodies: false,
+            ..Settings::default()
+        };
+        let rb = test_chunk::stone_body(10, 10, (4.0, 2.0), &mut world.physics);
+        world.rigidbodies.push(rb);
+
+        test_chunk::tick(&mut world, settings, 120);
+
+        assert_eq!(world.rigidbodies.len(), 1);
+        let body = world.rigidbodies[0].get_body(&world.physics).unwrap();
+        let bottom = body.translation().y * PHYSICS_SCALE + 10.0;
+        assert!((58.0..62.0).contains(&bottom), "body bottom at {bottom}");
+        assert!(body.linvel().norm() < 0.1);
+    }
+
+    #[test]
+    fn sand_falls_onto_terrain() {
+        let mut world = floor_world();
+        let sand = material::COBBLE_DIRT.instance(PhysicsType::Sand, Color::YELLOW);
+        world.chunk_handler.set_pixel(50, 10, sand).unwrap();
+
+        test_chunk::tick(&mut world, Settings::default(), 120);
+
+        assert_eq!(
+            world.chunk_handler.pixel(50, 10).unwrap().physics,
+            PhysicsType::Air
+        );
+        let landed = (40..60)
+            .filter(|&x| world.chunk_handler.pixel(x, 59).unwrap().physics == PhysicsType::Sand)
+            .count();
+        assert_eq!(landed, 1);
+    }
+}
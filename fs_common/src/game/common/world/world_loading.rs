@@ -4,6 +4,8 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use crate::game::common::Registries;
+
 use super::{Chunk, World};
 
 #[derive(Debug)]
@@ -18,6 +20,10 @@ pub struct WorldMeta {
     pub last_played_version: String,
     pub save_format: String,
     pub last_played_time: toml::value::Datetime,
+    /// Data packs enabled for this world, layered in order on top of the base packs.
+    /// Paths are relative to the `datapacks` folder in the game dir.
+    #[serde(default)]
+    pub data_packs: Vec<PathBuf>,
 }
 
 impl<C: Chunk + Send + Sync + 'static> World<C> {
@@ -60,7 +66,7 @@ impl<C: Chunk + Send + Sync + 'static> World<C> {
         Ok(toml::from_str::<WorldMeta>(&fs::read_to_string(path)?)?)
     }
 
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Self {
-        Self::create(Some(path.as_ref().to_path_buf()), None)
+    pub fn from_file<P: AsRef<Path>>(path: P, registries: &Registries) -> Self {
+        Self::create(Some(path.as_ref().to_path_buf()), None, registries)
     }
 }
@@ -1,10 +1,13 @@
 use crate::game::common::world::World;
 use crate::game::common::Settings;
 
+use super::common::asset_watcher::AssetWatcher;
+use super::common::lang::{Lang, DEFAULT_LANGUAGE, LANG_DIR};
 use super::common::world::Chunk;
 use super::common::{FileHelper, Registries};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 pub struct GameData<C: Chunk> {
     pub world: Option<World<C>>,
@@ -15,6 +18,8 @@ pub struct GameData<C: Chunk> {
     pub settings: Settings,
     pub file_helper: FileHelper,
     pub registries: Arc<Registries>,
+    pub lang: Lang,
+    pub asset_watcher: AssetWatcher,
     pub build_data: BuildData,
 }
 
@@ -42,8 +47,11 @@ pub struct FPSCounter {
 impl<C: Chunk + Send + Sync + 'static> GameData<C> {
     #[profiling::function]
     pub fn new(file_helper: FileHelper, build_data: BuildData) -> Self {
+        let settings = Settings::default();
+        let lang = load_lang(&settings.language, &file_helper);
+        let registries = Arc::new(Registries::init(&file_helper));
         GameData {
-            world: Some(World::create(None, Some(3))), // TODO: non constant seed
+            world: Some(World::create(None, Some(3), &registries)), // TODO: non constant seed
             tick_time: 0,
             frame_count: 0,
             fps_counter: FPSCounter {
@@ -57,10 +65,168 @@ impl<C: Chunk + Send + Sync + 'static> GameData<C> {
                 tick_physics_times: [0.0; 200],
             },
             process_stats: ProcessStats { cpu_usage: None, memory: None },
-            settings: Settings::default(),
-            registries: Arc::new(Registries::init(&file_helper)),
+            settings,
+            registries,
+            lang,
+            asset_watcher: AssetWatcher::for_packs(file_helper.packs(), Duration::from_secs(1)),
             file_helper,
             build_data,
         }
     }
+
+    /// Saves and closes the current world, then loads the world whose `world_info.toml` is at `meta_path`
+    ///   with its data packs applied.
+    pub fn load_world(&mut self, meta_path: &Path) -> Result<(), String> {
+        let world_meta = World::<C>::parse_file_meta(meta_path)
+            .map_err(|e| format!("Failed to parse {}: {e}", meta_path.display()))?;
+        let world_dir = meta_path
+            .parent()
+            .ok_or_else(|| {
+                format!(
+                    "World meta file has no parent directory: {}",
+                    meta_path.display()
+                )
+            })?
+            .to_path_buf();
+
+        // the old world is dropped even if the new one fails to load, since it's already closed
+        if let Some(mut w) = self.world.take() {
+            log::info!("Unload current world...");
+            w.save().map_err(|e| format!("World save failed: {e}"))?;
+            w.close().map_err(|e| format!("World unload failed: {e}"))?;
+        }
+
+        self.set_data_packs(&world_meta.data_packs).map_err(|e| {
+            format!(
+                "Failed to load data packs for world \"{}\": {e}",
+                world_meta.name
+            )
+        })?;
+
+        log::info!("Load world \"{}\"...", world_meta.name);
+        // TODO: save/load seed from file
+        self.world = Some(World::create(Some(world_dir), Some(3), &self.registries));
+
+        Ok(())
+    }
+}
+
+impl<C: Chunk> GameData<C> {
+    /// Checks the assets dir for changes (if enabled) and reloads any affected registries.
+    /// Returns the changed asset paths so the caller can reload anything else that uses them (eg. shaders).
+    pub fn check_asset_changes(&mut self) -> Vec<PathBuf> {
+        if !self.settings.hot_reload_assets {
+            return Vec::new();
+        }
+
+        let changed = self.asset_watcher.poll();
+        for zip in self.asset_watcher.take_changed_zips() {
+            if let Err(e) = self.file_helper.reopen_pack(&zip) {
+                log::error!("Failed to reopen data pack: {e}");
+            }
+        }
+        if changed.iter().any(|p| Registries::is_registry_asset(p)) {
+            log::info!("Assets changed: {changed:?}");
+            self.swap_registries(self.registries.reload(&self.file_helper, &changed));
+        }
+        if changed.iter().any(|p| p.starts_with(LANG_DIR)) {
+            self.reload_lang();
+        }
+
+        changed
+    }
+
+    /// Layers `data_packs` (eg. the ones listed in a world's `world_info.toml`) on top of the base packs,
+    /// then reloads every registry and the language files.
+    /// If the packs or registries fail to load, nothing is changed.
+    pub fn set_data_packs(&mut self, data_packs: &[PathBuf]) -> Result<(), String> {
+        let file_helper = self.file_helper.with_extra_packs(data_packs)?;
+        let registries = Registries::try_init(&file_helper)?;
+
+        self.file_helper = file_helper;
+        self.asset_watcher =
+            AssetWatcher::for_packs(self.file_helper.packs(), Duration::from_secs(1));
+        self.swap_registries(Ok(registries));
+        self.reload_lang();
+
+        Ok(())
+    }
+
+    /// Reloads every registry from disk.
+    /// If anything fails to load, the error is logged and the current registries are kept.
+    pub fn reload_registries(&mut self) -> bool {
+        self.swap_registries(Registries::try_init(&self.file_helper))
+    }
+
+    /// Reloads the language files for `settings.language`.
+    /// Call this after changing the language setting.
+    pub fn reload_lang(&mut self) {
+        self.lang = load_lang(&self.settings.language, &self.file_helper);
+        log::info!("Loaded language {}", self.lang.language());
+    }
+
+    fn swap_registries(&mut self, registries: Result<Registries, String>) -> bool {
+        match registries {
+            Ok(registries) => {
+                // anything still holding the old `Arc` (eg. in-progress chunk generation) keeps using the old registries until it's done
+                self.registries = Arc::new(registries);
+                log::info!("Reloaded registries");
+                true
+            },
+            Err(e) => {
+                log::error!("Failed to reload registries, keeping the old ones: {e}");
+                false
+            },
+        }
+    }
+}
+
+/// Falls back to [`DEFAULT_LANGUAGE`] if `language` fails to load, and to no translations at all if that fails too.
+fn load_lang(language: &str, file_helper: &FileHelper) -> Lang {
+    Lang::load(language, file_helper)
+        .or_else(|e| {
+            log::error!("Failed to load language {language}: {e}");
+            Lang::load(DEFAULT_LANGUAGE, file_helper)
+        })
+        .unwrap_or_else(|e| {
+            log::error!("Failed to load default language: {e}");
+            Lang::empty()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::common::{test_dir, world::test_chunk::TestChunk};
+
+    #[test]
+    fn load_world_with_missing_pack_fails() {
+        let file_helper =
+            FileHelper::new("../gamedir/".into(), vec!["../gamedir/assets/".into()]).unwrap();
+        let mut data =
+            GameData::<TestChunk>::new(file_helper, BuildData { datetime: None, git_hash: None });
+        let registries = data.registries.clone();
+
+        let dir = test_dir("load_world_missing_pack");
+        let meta = dir.join("world_info.toml");
+        std::fs::write(
+            &meta,
+            r#"
+name = "test"
+last_played_version = "0"
+save_format = "0"
+last_played_time = 2000-01-01T00:00:00Z
+data_packs = ["missing_pack"]
+"#,
+        )
+        .unwrap();
+
+        let err = data.load_world(&meta).unwrap_err();
+        assert!(err.contains("missing_pack"), "{err}");
+        assert!(data.world.is_none());
+        assert!(Arc::ptr_eq(&registries, &data.registries));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -1,16 +1,21 @@
-use std::{fs::File, thread};
+use std::{fs::File, path::Path, thread};
 
 use backtrace::Backtrace;
 use fs_client::{render::Renderer, world::ClientWorld, ClientGame};
 use fs_common::game::{
     common::{
         cli::{CLArgs, CLSubcommand},
-        world::{entity::Player, Camera, Target},
-        FileHelper,
+        lang::{Lang, DEFAULT_LANGUAGE},
+        world::{
+            entity::Player,
+            gen::{preview::generate_preview, surface::SurfaceGenerator},
+            Camera, Target,
+        },
+        DataPack, FileHelper, Rect, Registries,
     },
     BuildData,
 };
-use fs_server::ServerGame;
+use fs_server::{world::ServerChunk, ServerGame};
 use log::{error, info, LevelFilter};
 
 // use salva2d::{integrations::rapier::ColliderSampling, object::Boundary};
@@ -46,16 +51,38 @@ pub fn main() -> Result<(), String> {
 
     let cl_args = CLArgs::parse_args();
 
-    let file_helper = FileHelper::new(cl_args.game_dir.clone(), cl_args.assets_dir.clone());
+    let base_packs = std::iter::once(cl_args.assets_dir.clone())
+        .chain(cl_args.data_packs.iter().cloned())
+        .collect();
+    let file_helper = FileHelper::new(cl_args.game_dir.clone(), base_packs)?;
 
     if !file_helper.game_path("").exists() {
         info!("game dir missing, creating it...");
         std::fs::create_dir_all(file_helper.game_path("")).expect("Failed to create game dir:");
     }
 
-    if !file_helper.asset_path("").exists() {
-        info!("asset dir missing, creating it...");
-        std::fs::create_dir_all(file_helper.asset_path("")).expect("Failed to create asset dir:");
+    for pack in file_helper.packs() {
+        if let DataPack::Dir(path) = pack {
+            if !path.exists() {
+                info!("asset dir {path:?} missing, creating it...");
+                std::fs::create_dir_all(path).expect("Failed to create asset dir:");
+            }
+        }
+    }
+
+    if matches!(cl_args.subcommand, Some(CLSubcommand::ValidateAssets)) {
+        validate_assets(&file_helper);
+    }
+
+    if let Some(CLSubcommand::WorldgenPreview { seed, x, y, width, height, out }) =
+        &cl_args.subcommand
+    {
+        worldgen_preview(
+            &file_helper,
+            *seed,
+            Rect::new_wh(*x, *y, i32::from(*width), i32::from(*height)),
+            out,
+        );
     }
 
     let server = matches!(cl_args.subcommand, Some(CLSubcommand::Server { .. }));
@@ -109,6 +136,12 @@ pub fn main() -> Result<(), String> {
             println!("Starting server...");
             let mut game: ServerGame = ServerGame::new(file_helper, build_data);
 
+            if let Some(CLSubcommand::Server { world: Some(world), .. }) = &cl_args.subcommand {
+                if let Err(e) = game.0.load_world(world) {
+                    panic!("Failed to load world: {e}");
+                }
+            }
+
             if let Some(w) = &mut game.0.world {
                 Player::create_and_add(w);
             }
@@ -212,3 +245,98 @@ pub fn main() -> Result<(), String> {
 
     Ok(())
 }
+
+/// Prints a report of any broken registry references and exits, with a non-zero code if there were any.
+fn validate_assets(file_helper: &FileHelper) -> ! {
+    println!("Validating assets...");
+    for pack in file_helper.packs() {
+        println!("  pack: {}", pack.path().display());
+    }
+
+    let mut problems = match Registries::try_init(file_helper) {
+        Ok(registries) => {
+            let mut problems = registries.validate();
+            match Lang::load(DEFAULT_LANGUAGE, file_helper) {
+                Ok(lang) => {
+                    let keys = (&registries.materials)
+                        .into_iter()
+                        .map(|(_, m)| &m.display_name)
+                        .chain(
+                            (&registries.material_placers)
+                                .into_iter()
+                                .map(|(_, p)| &p.meta.display_name),
+                        );
+                    for key in keys {
+                        if !lang.contains(key) {
+                            problems.push(format!(
+                                "Missing translation for \"{key}\" in {DEFAULT_LANGUAGE}"
+                            ));
+                        }
+                    }
+                },
+                Err(e) => problems.push(e),
+            }
+            problems
+        },
+        Err(e) => vec![e],
+    };
+    problems.sort();
+    problems.dedup();
+
+    if problems.is_empty() {
+        println!("No problems found.");
+        std::process::exit(0);
+    }
+
+    println!("Found {} problem(s):", problems.len());
+    for problem in &problems {
+        println!("  - {problem}");
+    }
+    std::process::exit(1);
+}
+
+/// Generates `area` (in chunks) with the same generator as new worlds, writes the preview images to `out`, and exits.
+fn worldgen_preview(file_helper: &FileHelper, seed: i32, area: Rect<i32>, out: &Path) -> ! {
+    let registries = match Registries::try_init(file_helper) {
+        Ok(registries) => registries,
+        Err(e) => {
+            eprintln!("Failed to load registries: {e}");
+            std::process::exit(1);
+        },
+    };
+
+    println!(
+        "Generating chunks {},{} to {},{} with seed {seed}...",
+        area.left(),
+        area.top(),
+        area.right(),
+        area.bottom()
+    );
+    let generator = SurfaceGenerator::<ServerChunk>::new();
+    let preview = generate_preview(&generator, area, seed, &registries);
+
+    if let Err(e) = std::fs::create_dir_all(out) {
+        eprintln!("Failed to create {}: {e}", out.display());
+        std::process::exit(1);
+    }
+
+    for (name, img) in [
+        ("color.png", &preview.color),
+        ("biomes.png", &preview.biomes),
+        ("structures.png", &preview.structures),
+    ] {
+        let path = out.join(name);
+        if let Err(e) = img.save(&path) {
+            eprintln!("Failed to write {}: {e}", path.display());
+            std::process::exit(1);
+        }
+        println!("Wrote {}", path.display());
+    }
+
+    println!("Biomes:");
+    for (id, color) in &preview.biome_legend {
+        println!("  #{:02x}{:02x}{:02x} {id}", color[0], color[1], color[2]);
+    }
+
+    std::process::exit(0);
+}
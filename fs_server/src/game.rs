@@ -1,6 +1,7 @@
 use clap::error::ContextKind;
 use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
 use log::{debug, error, info, warn};
+use specs::{Join, ReadStorage};
 use std::{
     io::{Read, Write},
     net::{SocketAddr, TcpListener, TcpStream},
@@ -23,7 +24,11 @@ use fs_common::game::{
         cli::{CLArgs, CLSubcommand},
         commands::CommandHandler,
         networking::{Packet, PacketType},
-        world::{Chunk, ChunkState, CHUNK_AREA},
+        world::{
+            entity::Player,
+            rope::{Rope, RopeAnchor},
+            Chunk, ChunkState, Position, CHUNK_AREA, CHUNK_SIZE,
+        },
         FileHelper,
     },
     BuildData, GameData,
@@ -60,7 +65,9 @@ impl ServerGame {
 
         term.clear().unwrap();
 
-        let CLSubcommand::Server { port } = args.subcommand.as_ref().unwrap();
+        let Some(CLSubcommand::Server { port, .. }) = args.subcommand.as_ref() else {
+            return Err("ServerGame::run called without the server subcommand".to_owned());
+        };
         let net_listener =
             TcpListener::bind(format!("127.0.0.1:{port}")).map_err(|e| e.to_string())?;
         net_listener
@@ -262,6 +269,33 @@ impl ServerGame {
                                         Ok(m) => {
                                             if m.subcommand_matches("shutdown").is_some() {
                                                 break 'mainLoop;
+                                            } else if m.subcommand_matches("reload").is_some() {
+                                                self.0.reload_registries();
+                                                self.0.reload_lang();
+                                            } else if let Some(m) = m.subcommand_matches("locate") {
+                                                let structure =
+                                                    m.get_one::<String>("structure").unwrap();
+                                                let radius = *m.get_one::<u32>("radius").unwrap();
+                                                self.locate(structure, radius);
+                                            } else if let Some(m) = m.subcommand_matches("explode")
+                                            {
+                                                let radius = *m.get_one::<f32>("radius").unwrap();
+                                                let power = *m.get_one::<f32>("power").unwrap();
+                                                let pos = m
+                                                    .get_one::<f64>("x")
+                                                    .zip(m.get_one::<f64>("y"))
+                                                    .map(|(x, y)| (*x, *y));
+                                                self.explode(pos, radius, power);
+                                            } else if let Some(m) = m.subcommand_matches("rope") {
+                                                let [x1, y1, x2, y2] = ["x1", "y1", "x2", "y2"]
+                                                    .map(|a| *m.get_one::<f32>(a).unwrap());
+                                                self.rope(
+                                                    (x1, y1),
+                                                    (x2, y2),
+                                                    m.get_flag("loose"),
+                                                    m.get_one::<f32>("length").copied(),
+                                                    m.get_flag("chain"),
+                                                );
                                             }
                                         },
                                         Err(e)
@@ -450,6 +484,8 @@ impl ServerGame {
     fn tick(&mut self) {
         self.0.tick_time += 1;
 
+        self.0.check_asset_changes();
+
         if let Some(w) = &mut self.0.world {
             w.tick(
                 self.0.tick_time,
@@ -460,6 +496,105 @@ impl ServerGame {
         }
     }
 
+    /// Logs the nearest chunk to the first player (or the origin) where `structure` generates.
+    /// `structure` can be a structure set or a configured structure.
+    fn locate(&self, structure: &str, radius: u32) {
+        let Some(world) = &self.0.world else {
+            error!(target: "", "No world loaded");
+            return;
+        };
+
+        let pos = {
+            let (player, position) = world
+                .ecs
+                .system_data::<(ReadStorage<Player>, ReadStorage<Position>)>();
+            (&player, &position)
+                .join()
+                .next()
+                .map_or((0.0, 0.0), |(_, p)| (p.x, p.y))
+        };
+
+        let registries = &self.0.registries;
+        let result = if registries.structure_sets.get(structure).is_some() {
+            world.locate_structure_set(&structure.into(), pos, radius, registries)
+        } else {
+            world.locate_configured_structure(&structure.into(), pos, radius, registries)
+        };
+
+        match result {
+            Ok(Some((chunk_x, chunk_y))) => {
+                info!(target: "", "Nearest {structure} is in chunk ({chunk_x}, {chunk_y}), around ({}, {})",
+                    chunk_x * i32::from(CHUNK_SIZE), chunk_y * i32::from(CHUNK_SIZE));
+            },
+            Ok(None) => {
+                info!(target: "", "No {structure} within {radius} chunks of ({:.0}, {:.0})", pos.0, pos.1);
+            },
+            Err(e) => {
+                error!(target: "", "{e}");
+            },
+        }
+    }
+
+    /// Causes an explosion at `pos`, or at the first player (or the origin) if `None`.
+    fn explode(&mut self, pos: Option<(f64, f64)>, radius: f32, power: f32) {
+        let Some(world) = &mut self.0.world else {
+            error!(target: "", "No world loaded");
+            return;
+        };
+
+        let (x, y) = pos.unwrap_or_else(|| {
+            let (player, position) = world
+                .ecs
+                .system_data::<(ReadStorage<Player>, ReadStorage<Position>)>();
+            (&player, &position)
+                .join()
+                .next()
+                .map_or((0.0, 0.0), |(_, p)| (p.x, p.y))
+        });
+
+        world.explode(x, y, radius, power, &self.0.registries);
+        info!(target: "", "Explosion at ({x:.0}, {y:.0}) with radius {radius} and power {power}");
+    }
+
+    /// Hangs a rope from `start` to `end`, or from `start` with its other end free if `loose`.
+    /// Each end is attached to the rigidbody or terrain there, or fixed in place if there is nothing.
+    /// `length` defaults to the distance between the positions.
+    fn rope(
+        &mut self,
+        start: (f32, f32),
+        end: (f32, f32),
+        loose: bool,
+        length: Option<f32>,
+        chain: bool,
+    ) {
+        let Some(world) = &mut self.0.world else {
+            error!(target: "", "No world loaded");
+            return;
+        };
+
+        let anchor = |(x, y): (f32, f32)| {
+            world
+                .rope_anchor_at(x, y)
+                .unwrap_or(RopeAnchor::Fixed { x, y })
+        };
+        let start_anchor = anchor(start);
+        let end_anchor = (!loose).then(|| anchor(end));
+
+        let rope = if chain {
+            Rope::chain(start_anchor, end_anchor)
+        } else {
+            Rope::new(start_anchor, end_anchor)
+        };
+        let length = length.unwrap_or_else(|| (end.0 - start.0).hypot(end.1 - start.1));
+
+        match world.add_rope(rope.with_length(length)) {
+            Ok(()) => {
+                info!(target: "", "Rope from {start_anchor:?} to {end_anchor:?} with length {length:.0}");
+            },
+            Err(e) => error!(target: "", "{e}"),
+        }
+    }
+
     fn draw_terminal<TB: Backend>(
         &mut self,
         frame: &mut Frame<TB>,
@@ -4,7 +4,7 @@ use fs_common::game::common::world::chunk_index::ChunkLocalIndex;
 use fs_common::game::common::world::chunk_index::ChunkLocalPosition;
 use fs_common::game::common::world::material::color::Color;
 use fs_common::game::common::world::material::MaterialInstance;
-use fs_common::game::common::world::mesh;
+use fs_common::game::common::world::mesh::Mesh;
 use fs_common::game::common::world::tile_entity::TileEntity;
 use fs_common::game::common::world::tile_entity::TileEntityCommon;
 use fs_common::game::common::world::tile_entity::TileEntitySided;
@@ -13,7 +13,6 @@ use fs_common::game::common::world::ChunkRigidBodyState;
 use fs_common::game::common::world::ChunkState;
 use fs_common::game::common::world::SidedChunk;
 use fs_common::game::common::world::CHUNK_AREA;
-use fs_common::game::common::world::CHUNK_SIZE;
 use fs_common::game::common::Rect;
 
 pub struct ServerChunk {
@@ -70,6 +69,14 @@ impl Chunk for ServerChunk {
         self.data.dirty_rect = rect;
     }
 
+    fn integrity_rect(&self) -> Option<Rect<i32>> {
+        self.data.integrity_rect
+    }
+
+    fn set_integrity_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.integrity_rect = rect;
+    }
+
     fn refresh(&mut self) {}
 
     fn set_pixel(&mut self, pos: ChunkLocalPosition, mat: MaterialInstance) -> Result<(), String> {
@@ -176,23 +183,20 @@ impl Chunk for ServerChunk {
         self.dirty = true;
     }
 
-    fn generate_mesh(&mut self) -> Result<(), String> {
-        if self.data.pixels.is_none() {
-            return Err("generate_mesh failed: self.data.pixels is None".to_owned());
-        }
-
-        let vs: Vec<f64> = mesh::pixels_to_valuemap(self.data.pixels.as_ref().unwrap().as_ref());
-
-        let generated =
-            mesh::generate_mesh_only_simplified(&vs, u32::from(CHUNK_SIZE), u32::from(CHUNK_SIZE));
+    fn generate_mesh_tiles(&mut self, tiles: &[usize]) -> Result<(), String> {
+        self.data.generate_mesh_tiles(tiles).map(|_| ())
+    }
 
-        self.data.mesh_simplified = generated.ok();
+    fn mesh_loops(&self) -> &Option<Vec<Mesh>> {
+        &self.data.mesh_simplified
+    }
 
-        Ok(())
+    fn mesh_rect(&self) -> Option<Rect<i32>> {
+        self.data.mesh_rect
     }
 
-    fn mesh_loops(&self) -> &Option<Vec<Vec<Vec<Vec<f64>>>>> {
-        &self.data.mesh_simplified
+    fn set_mesh_rect(&mut self, rect: Option<Rect<i32>>) {
+        self.data.mesh_rect = rect;
     }
 
     fn rigidbody(&self) -> &Option<ChunkRigidBodyState> {
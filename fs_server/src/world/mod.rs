@@ -20,7 +20,8 @@ mod tests {
     #[test]
     fn chunk_loading() {
         let registries = std::sync::Arc::new(Registries::empty());
-        let file_helper = FileHelper::new("../gamedir/".into(), "../gamedir/assets/".into());
+        let file_helper =
+            FileHelper::new("../gamedir/".into(), vec!["../gamedir/assets/".into()]).unwrap();
 
         let mut ch: ChunkHandler<ServerChunk> =
             ChunkHandler::<ServerChunk>::new(TestGenerator::new(), None);